@@ -1,21 +1,31 @@
 #[cfg(all(target_os = "macos", target_arch = "aarch64"))]
 use crate::apple_intelligence;
 use crate::audio_feedback::{play_feedback_sound, play_feedback_sound_blocking, SoundType};
+use crate::llm_client::{AttemptOutcome, ChatCompletion, ChatRequest, LlmAttempt};
 use crate::managers::audio::AudioRecordingManager;
-use crate::managers::history::HistoryManager;
+use crate::managers::history::{HistoryManager, NewHistoryEntry};
 use crate::managers::transcription::TranscriptionManager;
-use crate::settings::{get_settings, AppSettings, APPLE_INTELLIGENCE_PROVIDER_ID};
+use crate::prompt_template::{render_prompt_template, uses_variable, PromptInputs, PromptValues};
+use crate::redaction::Redactor;
+use crate::settings::{
+    get_settings, AppSettings, LLMPrompt, LlmStreamingMode, PostProcessProvider,
+    APPLE_INTELLIGENCE_PROVIDER_ID,
+};
 use crate::shortcut;
+use crate::text_pipeline::{AttemptLog, PipelineContext, StreamCallback, TextPipeline, TextStage};
 use crate::tray::{change_tray_icon, TrayIconState};
 use crate::utils::{self, show_recording_overlay, show_transcribing_overlay};
 use ferrous_opencc::{config::BuiltinConfig, OpenCC};
-use log::{debug, error};
+use log::{debug, error, warn};
 use once_cell::sync::Lazy;
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
 use std::time::Instant;
 use tauri::AppHandle;
 use tauri::Manager;
+use tauri_plugin_clipboard_manager::ClipboardExt;
+use whisper_rs::{get_lang_id, get_lang_str};
 
 // Shortcut Action Trait
 pub trait ShortcutAction: Send + Sync {
@@ -26,106 +36,266 @@ pub trait ShortcutAction: Send + Sync {
 // Transcribe Action
 struct TranscribeAction;
 
-async fn maybe_post_process_transcription(
-    settings: &AppSettings,
+/// Runs LLM post-processing with the context's prompt, regardless of whether
+/// post-processing is enabled for regular dictation. With a stream callback in
+/// the context the reply is passed on piece by piece as it arrives.
+pub(crate) async fn post_process_transcription(
+    context: &PipelineContext<'_>,
     transcription: &str,
 ) -> Option<String> {
-    if !settings.post_process_enabled {
-        return None;
-    }
-
-    let provider = match settings.active_post_process_provider().cloned() {
-        Some(provider) => provider,
+    let prompt = match context.post_process_llm_prompt() {
+        Some(prompt) => prompt,
         None => {
-            debug!("Post-processing enabled but no provider is selected");
+            debug!(
+                "Post-processing skipped because prompt '{}' was not found",
+                context
+                    .post_process_prompt_id
+                    .as_deref()
+                    .unwrap_or_default()
+            );
             return None;
         }
     };
 
-    let model = settings
-        .post_process_models
-        .get(&provider.id)
-        .cloned()
-        .unwrap_or_default();
+    if prompt.prompt.trim().is_empty() {
+        debug!("Post-processing skipped because the selected prompt is empty");
+        return None;
+    }
 
-    if model.trim().is_empty() {
+    let values = PromptValues {
+        output: transcription,
+        language: &context.language,
+        custom_words: &context.settings.custom_words,
+        inputs: &context.prompt_inputs,
+    };
+    let user_prompt = render_prompt_template(&prompt.prompt, &values);
+    let system_prompt = render_prompt_template(&prompt.system_prompt, &values);
+    debug!(
+        "Processed prompt length: {} chars (system: {} chars)",
+        user_prompt.len(),
+        system_prompt.len()
+    );
+
+    complete_with_providers(
+        context.settings,
+        user_prompt,
+        Some(system_prompt.as_str()).filter(|system| !system.trim().is_empty()),
+        context.llm_stream.as_deref(),
+        &context.llm_attempts,
+    )
+    .await
+}
+
+/// Primary subtag of a language code such as "pt-BR", or the code of a
+/// language name such as "German", as Whisper knows them
+fn primary_language_code(language: &str) -> String {
+    let language = language.trim().to_lowercase();
+    let primary = language.split('-').next().unwrap_or(&language);
+    // Whisper looks names up as C strings
+    if primary.contains('\0') {
+        return primary.to_string();
+    }
+    get_lang_id(primary)
+        .and_then(get_lang_str)
+        .unwrap_or(primary)
+        .to_string()
+}
+
+/// Translates the dictated text into the configured target language with the
+/// active LLM provider. Skipped when the source is unknown or already in that language.
+pub(crate) async fn maybe_translate_transcription(
+    settings: &AppSettings,
+    text: &str,
+    source_language: Option<&str>,
+    attempts: &AttemptLog,
+) -> Option<String> {
+    if !settings.translation_enabled {
+        return None;
+    }
+
+    let target_language = settings.translation_target_language.trim();
+    if target_language.is_empty() {
+        debug!("Translation skipped because no target language is set");
+        return None;
+    }
+
+    let source_language = source_language.unwrap_or(&settings.selected_language);
+    if source_language == "auto" {
+        debug!("Translation skipped because the source language is unknown");
+        return None;
+    }
+    if primary_language_code(source_language) == primary_language_code(target_language) {
         debug!(
-            "Post-processing skipped because provider '{}' has no model configured",
-            provider.id
+            "Translation skipped because the source is already in {}",
+            target_language
         );
         return None;
     }
 
-    let selected_prompt_id = match &settings.post_process_selected_prompt_id {
-        Some(id) => id.clone(),
-        None => {
-            debug!("Post-processing skipped because no prompt is selected");
-            return None;
+    let prompt = settings
+        .translation_prompt
+        .replace("${target_language}", target_language)
+        .replace("${output}", text);
+
+    debug!("Translating transcription into {}", target_language);
+    complete_with_providers(settings, prompt, None, None, attempts).await
+}
+
+/// Providers to ask in order: the selected one, then the configured fallbacks
+fn post_process_provider_chain(settings: &AppSettings) -> Vec<&PostProcessProvider> {
+    let mut chain: Vec<&PostProcessProvider> = settings
+        .active_post_process_provider()
+        .into_iter()
+        .collect();
+    for id in &settings.post_process_fallback_provider_ids {
+        match settings.post_process_provider(id) {
+            Some(provider) if chain.iter().all(|existing| existing.id != provider.id) => {
+                chain.push(provider)
+            }
+            Some(_) => {}
+            None => debug!("Skipping unknown post-processing provider '{}'", id),
         }
-    };
+    }
+    chain
+}
 
-    let prompt = match settings
-        .post_process_prompts
-        .iter()
-        .find(|prompt| prompt.id == selected_prompt_id)
-    {
-        Some(prompt) => prompt.prompt.clone(),
-        None => {
-            debug!(
-                "Post-processing skipped because prompt '{}' was not found",
-                selected_prompt_id
-            );
+/// Sends a fully expanded prompt to the selected post-processing provider,
+/// moving on to the configured fallbacks when it fails. Returns the first
+/// reply, or `None` once every provider has failed so the unprocessed text is
+/// kept. Every provider asked is recorded in `attempts`.
+/// Providers that can't stream pass their whole reply to `on_delta` at once.
+async fn complete_with_providers(
+    settings: &AppSettings,
+    prompt: String,
+    system: Option<&str>,
+    on_delta: Option<&(dyn Fn(&str) + Send + Sync)>,
+    attempts: &AttemptLog,
+) -> Option<String> {
+    let chain = post_process_provider_chain(settings);
+    if chain.is_empty() {
+        debug!("LLM request skipped because no provider is selected");
+        return None;
+    }
+
+    for provider in chain {
+        let model = settings
+            .post_process_models
+            .get(&provider.id)
+            .cloned()
+            .unwrap_or_default();
+
+        let streamed = AtomicBool::new(false);
+        let track_delta = |delta: &str| {
+            streamed.store(true, Ordering::Relaxed);
+            if let Some(on_delta) = on_delta {
+                on_delta(delta);
+            }
+        };
+        let tracked: Option<&(dyn Fn(&str) + Send + Sync)> = match on_delta {
+            Some(_) => Some(&track_delta),
+            None => None,
+        };
+
+        let started = Instant::now();
+        let result =
+            complete_with_provider(settings, provider, &model, &prompt, system, tracked).await;
+        let (outcome, usage) = match &result {
+            Ok(completion) if completion.content.is_some() => {
+                (AttemptOutcome::Succeeded, completion.usage)
+            }
+            Ok(completion) => (AttemptOutcome::Empty, completion.usage),
+            Err(error) => (
+                AttemptOutcome::Failed {
+                    error: error.clone(),
+                },
+                None,
+            ),
+        };
+        attempts.push(LlmAttempt {
+            provider_id: provider.id.clone(),
+            model,
+            duration_ms: started.elapsed().as_millis() as u64,
+            outcome,
+            usage,
+        });
+
+        match result.map(|completion| completion.content) {
+            Ok(Some(content)) => {
+                debug!(
+                    "LLM request succeeded for provider '{}'. Output length: {} chars",
+                    provider.id,
+                    content.len()
+                );
+                return Some(content);
+            }
+            Ok(None) => error!("LLM API response from '{}' has no content", provider.id),
+            Err(e) => error!("LLM request failed for provider '{}': {}", provider.id, e),
+        }
+
+        // A fallback's reply would follow the partial one already shown
+        if streamed.load(Ordering::Relaxed) {
+            warn!("Not trying further providers after partially streamed output");
             return None;
         }
-    };
+    }
 
-    if prompt.trim().is_empty() {
-        debug!("Post-processing skipped because the selected prompt is empty");
-        return None;
+    debug!("Every post-processing provider failed. Falling back to original text.");
+    None
+}
+
+/// Asks a single provider for a reply to the prompt
+async fn complete_with_provider(
+    settings: &AppSettings,
+    provider: &PostProcessProvider,
+    model: &str,
+    prompt: &str,
+    system: Option<&str>,
+    on_delta: Option<&(dyn Fn(&str) + Send + Sync)>,
+) -> Result<ChatCompletion, String> {
+    if model.trim().is_empty() {
+        return Err(format!(
+            "No model is configured for provider '{}'",
+            provider.id
+        ));
     }
 
     debug!(
-        "Starting LLM post-processing with provider '{}' (model: {})",
+        "Starting LLM request with provider '{}' (model: {})",
         provider.id, model
     );
 
-    // Replace ${output} variable in the prompt with the actual text
-    let processed_prompt = prompt.replace("${output}", transcription);
-    debug!("Processed prompt length: {} chars", processed_prompt.len());
-
     if provider.id == APPLE_INTELLIGENCE_PROVIDER_ID {
         #[cfg(all(target_os = "macos", target_arch = "aarch64"))]
         {
             if !apple_intelligence::check_apple_intelligence_availability() {
-                debug!("Apple Intelligence selected but not currently available on this device");
-                return None;
+                return Err(
+                    "Apple Intelligence is not currently available on this device".to_string(),
+                );
             }
 
-            let token_limit = model.trim().parse::<i32>().unwrap_or(0);
-            return match apple_intelligence::process_text(&processed_prompt, token_limit) {
-                Ok(result) => {
-                    if result.trim().is_empty() {
-                        debug!("Apple Intelligence returned an empty response");
-                        None
-                    } else {
-                        debug!(
-                            "Apple Intelligence post-processing succeeded. Output length: {} chars",
-                            result.len()
-                        );
-                        Some(result)
-                    }
-                }
-                Err(err) => {
-                    error!("Apple Intelligence post-processing failed: {}", err);
-                    None
-                }
+            // The on-device model takes a single prompt
+            let prompt = match system {
+                Some(system) => format!("{}\n\n{}", system, prompt),
+                None => prompt.to_string(),
             };
+            let token_limit = model.trim().parse::<i32>().unwrap_or(0);
+            let result = apple_intelligence::process_text(&prompt, token_limit)?;
+            if result.trim().is_empty() {
+                return Ok(ChatCompletion::default());
+            }
+            if let Some(on_delta) = on_delta {
+                on_delta(&result);
+            }
+            // The on-device model is free and doesn't count tokens
+            return Ok(ChatCompletion {
+                content: Some(result),
+                usage: None,
+            });
         }
 
         #[cfg(not(all(target_os = "macos", target_arch = "aarch64")))]
         {
-            debug!("Apple Intelligence provider selected on unsupported platform");
-            return None;
+            return Err("Apple Intelligence is not supported on this platform".to_string());
         }
     }
 
@@ -135,34 +305,67 @@ async fn maybe_post_process_transcription(
         .cloned()
         .unwrap_or_default();
 
-    // Send the chat completion request
-    match crate::llm_client::send_chat_completion(&provider, api_key, &model, processed_prompt)
-        .await
-    {
-        Ok(Some(content)) => {
-            debug!(
-                "LLM post-processing succeeded for provider '{}'. Output length: {} chars",
-                provider.id,
-                content.len()
-            );
-            Some(content)
-        }
-        Ok(None) => {
-            error!("LLM API response has no content");
-            None
-        }
-        Err(e) => {
-            error!(
-                "LLM post-processing failed for provider '{}': {}. Falling back to original transcription.",
-                provider.id,
-                e
-            );
-            None
-        }
+    // Personal data never leaves the machine for a cloud provider
+    let mut redactor = Redactor::new(&settings.pii_redaction.names);
+    let (prompt, system) = if settings.pii_redaction.enabled && !provider.local {
+        (redactor.redact(prompt), system.map(|s| redactor.redact(s)))
+    } else {
+        (prompt.to_string(), system.map(str::to_string))
+    };
+
+    let request = ChatRequest {
+        model,
+        system: system.as_deref(),
+        prompt: &prompt,
+        max_tokens: None,
+    };
+
+    if redactor.is_empty() {
+        return match on_delta {
+            Some(on_delta) => {
+                crate::llm_client::stream_chat_completion(provider, api_key, &request, on_delta)
+                    .await
+            }
+            None => crate::llm_client::send_chat_completion(provider, api_key, &request).await,
+        };
     }
+
+    debug!(
+        "Redacted {} entities before sending to provider '{}'",
+        redactor.len(),
+        provider.id
+    );
+    let result = match on_delta {
+        Some(on_delta) => {
+            let restorer = Mutex::new(redactor.stream_restorer());
+            let restore_delta = |delta: &str| {
+                let restored = restorer.lock().unwrap().push(delta);
+                if !restored.is_empty() {
+                    on_delta(&restored);
+                }
+            };
+            let result = crate::llm_client::stream_chat_completion(
+                provider,
+                api_key,
+                &request,
+                &restore_delta,
+            )
+            .await;
+            let rest = restorer.into_inner().unwrap().finish();
+            if result.is_ok() && !rest.is_empty() {
+                on_delta(&rest);
+            }
+            result
+        }
+        None => crate::llm_client::send_chat_completion(provider, api_key, &request).await,
+    };
+    result.map(|completion| ChatCompletion {
+        content: completion.content.map(|content| redactor.restore(&content)),
+        usage: completion.usage,
+    })
 }
 
-async fn maybe_convert_chinese_variant(
+pub(crate) async fn maybe_convert_chinese_variant(
     settings: &AppSettings,
     transcription: &str,
 ) -> Option<String> {
@@ -206,6 +409,102 @@ async fn maybe_convert_chinese_variant(
     }
 }
 
+/// Reads the values from outside the text that a prompt refers to
+async fn gather_prompt_inputs(app: &AppHandle, prompt: &LLMPrompt) -> PromptInputs {
+    let uses = |variable: &str| {
+        uses_variable(&prompt.prompt, variable) || uses_variable(&prompt.system_prompt, variable)
+    };
+
+    let mut inputs = PromptInputs::default();
+    if uses("previous_output") {
+        let hm = app.state::<Arc<HistoryManager>>();
+        inputs.previous_output = match hm.get_latest_entry().await {
+            Ok(entry) => entry.map(|entry| entry.final_text().to_string()),
+            Err(e) => {
+                warn!("Failed to read the previous history entry: {}", e);
+                None
+            }
+        };
+    }
+    if uses("clipboard") {
+        inputs.clipboard = app.clipboard().read_text().ok();
+    }
+    if uses("selection") {
+        inputs.selection = read_selection(app);
+    }
+    inputs
+}
+
+/// Reads the focused app's selection on the main thread, where key presses
+/// are sent from
+fn read_selection(app: &AppHandle) -> Option<String> {
+    let (sender, receiver) = std::sync::mpsc::channel();
+    let app_clone = app.clone();
+    if let Err(e) = app.run_on_main_thread(move || {
+        let _ = sender.send(utils::read_selected_text(&app_clone));
+    }) {
+        error!("Failed to read the selection on the main thread: {:?}", e);
+        return None;
+    }
+
+    match receiver.recv() {
+        Ok(Ok(selection)) => selection,
+        Ok(Err(e)) => {
+            warn!("Failed to read the selection: {}", e);
+            None
+        }
+        Err(_) => None,
+    }
+}
+
+/// Builds the callback that shows LLM output in the overlay as it streams in,
+/// or types it into the focused app when no later stage changes the output.
+/// Typed text is collected in `typed`.
+fn llm_stream_callback(
+    app: &AppHandle,
+    mode: LlmStreamingMode,
+    output_is_final: bool,
+    typed: Arc<Mutex<String>>,
+) -> Option<StreamCallback> {
+    let app = app.clone();
+    match mode {
+        LlmStreamingMode::Disabled => None,
+        LlmStreamingMode::Type if output_is_final => Some(Arc::new(move |delta: &str| {
+            typed.lock().unwrap().push_str(delta);
+            let app_clone = app.clone();
+            let delta = delta.to_string();
+            // Queued in order on the main thread, like the final paste
+            let _ = app.run_on_main_thread(move || {
+                if let Err(e) = utils::type_text(&delta, &app_clone) {
+                    error!("Failed to type streamed text: {}", e);
+                }
+            });
+        })),
+        _ => {
+            let shown = Mutex::new(String::new());
+            Some(Arc::new(move |delta: &str| {
+                let mut shown = shown.lock().unwrap();
+                shown.push_str(delta);
+                utils::show_overlay_text(&app, &shown);
+            }))
+        }
+    }
+}
+
+/// Types what the pipeline added after the streamed LLM output, such as a
+/// trailing space. If the LLM request failed part way, the final text doesn't
+/// continue the typed text and replaces it instead.
+fn finish_streamed_text(typed: &str, final_text: String, app: &AppHandle) -> Result<(), String> {
+    match final_text.strip_prefix(typed) {
+        Some("") => Ok(()),
+        Some(rest) => utils::type_text(rest, app),
+        None => {
+            warn!("Streamed LLM output was incomplete; replacing it with the final text");
+            utils::replace_typed_text(typed, &final_text, app)
+        }
+    }
+}
+
 impl ShortcutAction for TranscribeAction {
     fn start(&self, app: &AppHandle, binding_id: &str, _shortcut_str: &str) {
         let start_time = Instant::now();
@@ -317,7 +616,8 @@ impl ShortcutAction for TranscribeAction {
                 let transcription_time = Instant::now();
                 let samples_clone = samples.clone(); // Clone for history saving
                 match tm.transcribe(samples) {
-                    Ok(transcription) => {
+                    Ok(output) => {
+                        let transcription = output.text;
                         debug!(
                             "Transcription completed in {:?}: '{}'",
                             transcription_time.elapsed(),
@@ -325,48 +625,58 @@ impl ShortcutAction for TranscribeAction {
                         );
                         if !transcription.is_empty() {
                             let settings = get_settings(&ah);
-                            let mut final_text = transcription.clone();
-                            let mut post_processed_text: Option<String> = None;
-                            let mut post_process_prompt: Option<String> = None;
-
-                            // First, check if Chinese variant conversion is needed
-                            if let Some(converted_text) =
-                                maybe_convert_chinese_variant(&settings, &transcription).await
-                            {
-                                final_text = converted_text.clone();
-                                post_processed_text = Some(converted_text);
-                            }
-                            // Then apply regular post-processing if enabled
-                            else if let Some(processed_text) =
-                                maybe_post_process_transcription(&settings, &transcription).await
-                            {
-                                final_text = processed_text.clone();
-                                post_processed_text = Some(processed_text);
-
-                                // Get the prompt that was used
-                                if let Some(prompt_id) = &settings.post_process_selected_prompt_id {
-                                    if let Some(prompt) = settings
-                                        .post_process_prompts
-                                        .iter()
-                                        .find(|p| &p.id == prompt_id)
-                                    {
-                                        post_process_prompt = Some(prompt.prompt.clone());
-                                    }
-                                }
+                            let pipeline = TextPipeline::from_settings(&settings);
+                            let mut context = PipelineContext::for_binding(
+                                &settings,
+                                &binding_id,
+                                output.detected_language.as_deref(),
+                            );
+                            context.llm_attempts = AttemptLog::recording_usage(Arc::clone(&hm));
+                            if let Some(prompt) = context.post_process_llm_prompt() {
+                                context.prompt_inputs = gather_prompt_inputs(&ah, prompt).await;
                             }
+                            let typed = Arc::new(Mutex::new(String::new()));
+                            context.llm_stream = llm_stream_callback(
+                                &ah,
+                                settings.post_process_streaming,
+                                pipeline.llm_output_is_final(&context),
+                                Arc::clone(&typed),
+                            );
+                            let run = pipeline.run(&transcription, &context).await;
+                            let final_text = run.output().to_string();
+                            let typed_text = std::mem::take(&mut *typed.lock().unwrap());
+
+                            // History keeps the text before translation as the
+                            // processed text and the pasted text as the translation
+                            let processed = run.text_before(TextStage::Translation).trim();
+                            let post_processed_text =
+                                (processed != transcription).then(|| processed.to_string());
+                            let post_process_prompt = if run.applied(TextStage::LlmPostProcess) {
+                                context.post_process_prompt()
+                            } else {
+                                None
+                            };
+                            let translated_text = run
+                                .applied(TextStage::Translation)
+                                .then(|| final_text.trim().to_string());
+                            let translation_language = translated_text
+                                .as_ref()
+                                .map(|_| settings.translation_target_language.clone());
 
                             // Save to history with post-processed text and prompt
                             let hm_clone = Arc::clone(&hm);
-                            let transcription_for_history = transcription.clone();
+                            let entry = NewHistoryEntry {
+                                transcription_text: transcription.clone(),
+                                post_processed_text,
+                                post_process_prompt,
+                                detected_language: output.detected_language,
+                                translated_text,
+                                translation_language,
+                                post_process_attempts: context.llm_attempts.attempts(),
+                            };
                             tauri::async_runtime::spawn(async move {
-                                if let Err(e) = hm_clone
-                                    .save_transcription(
-                                        samples_clone,
-                                        transcription_for_history,
-                                        post_processed_text,
-                                        post_process_prompt,
-                                    )
-                                    .await
+                                if let Err(e) =
+                                    hm_clone.save_transcription(samples_clone, entry).await
                                 {
                                     error!("Failed to save transcription to history: {}", e);
                                 }
@@ -376,7 +686,12 @@ impl ShortcutAction for TranscribeAction {
                             let ah_clone = ah.clone();
                             let paste_time = Instant::now();
                             ah.run_on_main_thread(move || {
-                                match utils::paste(final_text, ah_clone.clone()) {
+                                let result = if typed_text.is_empty() {
+                                    utils::paste(final_text, ah_clone.clone())
+                                } else {
+                                    finish_streamed_text(&typed_text, final_text, &ah_clone)
+                                };
+                                match result {
                                     Ok(()) => debug!(
                                         "Text pasted successfully in {:?}",
                                         paste_time.elapsed()
@@ -417,6 +732,104 @@ impl ShortcutAction for TranscribeAction {
     }
 }
 
+// Voice Edit Action
+struct VoiceEditAction;
+
+const VOICE_EDIT_SYSTEM_PROMPT: &str = "You edit text following the user's spoken instruction. Reply with only the edited text, without explanations, quotes or code fences.";
+
+fn voice_edit_prompt(instruction: &str, selection: &str) -> String {
+    format!(
+        "Instruction: {}\n\nText:\n{}",
+        instruction.trim(),
+        selection
+    )
+}
+
+/// Transcribes the spoken instruction and applies it to the selected text,
+/// returning the edited text
+async fn apply_voice_edit(
+    app: &AppHandle,
+    rm: &AudioRecordingManager,
+    tm: &TranscriptionManager,
+    binding_id: &str,
+) -> Result<String, String> {
+    let samples = rm
+        .stop_recording(binding_id)
+        .ok_or("No samples retrieved from recording stop")?;
+    let instruction = tm
+        .transcribe(samples)
+        .map_err(|e| format!("Transcription failed: {}", e))?
+        .text;
+    if instruction.trim().is_empty() {
+        return Err("No instruction was heard".to_string());
+    }
+    debug!("Voice edit instruction: '{}'", instruction);
+
+    let selection = read_selection(app).ok_or("Nothing is selected")?;
+    let settings = get_settings(app);
+    let attempts = AttemptLog::recording_usage(Arc::clone(&app.state::<Arc<HistoryManager>>()));
+    let edited = complete_with_providers(
+        &settings,
+        voice_edit_prompt(&instruction, &selection),
+        Some(VOICE_EDIT_SYSTEM_PROMPT),
+        None,
+        &attempts,
+    )
+    .await
+    .ok_or("No LLM provider returned an edit")?;
+
+    Ok(edited.trim().to_string())
+}
+
+impl ShortcutAction for VoiceEditAction {
+    fn start(&self, app: &AppHandle, binding_id: &str, shortcut_str: &str) {
+        // The instruction is recorded just like a dictation
+        TranscribeAction.start(app, binding_id, shortcut_str);
+    }
+
+    fn stop(&self, app: &AppHandle, binding_id: &str, _shortcut_str: &str) {
+        shortcut::unregister_cancel_shortcut(app);
+        debug!("VoiceEditAction::stop called for binding: {}", binding_id);
+
+        let ah = app.clone();
+        let rm = Arc::clone(&app.state::<Arc<AudioRecordingManager>>());
+        let tm = Arc::clone(&app.state::<Arc<TranscriptionManager>>());
+
+        change_tray_icon(app, TrayIconState::Transcribing);
+        show_transcribing_overlay(app);
+        rm.remove_mute();
+        play_feedback_sound(app, SoundType::Stop);
+
+        let binding_id = binding_id.to_string();
+        tauri::async_runtime::spawn(async move {
+            let edited = match apply_voice_edit(&ah, &rm, &tm, &binding_id).await {
+                Ok(edited) => edited,
+                Err(e) => {
+                    warn!("Voice edit skipped: {}", e);
+                    utils::hide_recording_overlay(&ah);
+                    change_tray_icon(&ah, TrayIconState::Idle);
+                    return;
+                }
+            };
+
+            // Pasting replaces the selection, which is still active
+            let ah_clone = ah.clone();
+            ah.run_on_main_thread(move || {
+                if let Err(e) = utils::paste_over_selection(&edited, &ah_clone) {
+                    error!("Failed to paste voice edit: {}", e);
+                }
+                utils::hide_recording_overlay(&ah_clone);
+                change_tray_icon(&ah_clone, TrayIconState::Idle);
+            })
+            .unwrap_or_else(|e| {
+                error!("Failed to run paste on main thread: {:?}", e);
+                utils::hide_recording_overlay(&ah);
+                change_tray_icon(&ah, TrayIconState::Idle);
+            });
+        });
+    }
+}
+
 // Cancel Action
 struct CancelAction;
 
@@ -453,13 +866,17 @@ impl ShortcutAction for TestAction {
     }
 }
 
-// Static Action Map
-pub static ACTION_MAP: Lazy<HashMap<String, Arc<dyn ShortcutAction>>> = Lazy::new(|| {
+// Static Action Map. Extra transcribe bindings are added while the app runs.
+static ACTION_MAP: Lazy<RwLock<HashMap<String, Arc<dyn ShortcutAction>>>> = Lazy::new(|| {
     let mut map = HashMap::new();
     map.insert(
         "transcribe".to_string(),
         Arc::new(TranscribeAction) as Arc<dyn ShortcutAction>,
     );
+    map.insert(
+        "voice_edit".to_string(),
+        Arc::new(VoiceEditAction) as Arc<dyn ShortcutAction>,
+    );
     map.insert(
         "cancel".to_string(),
         Arc::new(CancelAction) as Arc<dyn ShortcutAction>,
@@ -468,5 +885,21 @@ pub static ACTION_MAP: Lazy<HashMap<String, Arc<dyn ShortcutAction>>> = Lazy::ne
         "test".to_string(),
         Arc::new(TestAction) as Arc<dyn ShortcutAction>,
     );
-    map
+    RwLock::new(map)
 });
+
+pub fn action_for(binding_id: &str) -> Option<Arc<dyn ShortcutAction>> {
+    ACTION_MAP.read().unwrap().get(binding_id).cloned()
+}
+
+/// Makes an extra transcribe binding dictate when its shortcut is used
+pub fn register_transcribe_action(binding_id: &str) {
+    ACTION_MAP.write().unwrap().insert(
+        binding_id.to_string(),
+        Arc::new(TranscribeAction) as Arc<dyn ShortcutAction>,
+    );
+}
+
+pub fn unregister_action(binding_id: &str) {
+    ACTION_MAP.write().unwrap().remove(binding_id);
+}
@@ -8,5 +8,5 @@ mod visualizer;
 pub use device::{list_input_devices, list_output_devices, CpalDeviceInfo};
 pub use recorder::AudioRecorder;
 pub use resampler::FrameResampler;
-pub use utils::save_wav_file;
+pub use utils::{read_wav_file, save_wav_file};
 pub use visualizer::AudioVisualiser;
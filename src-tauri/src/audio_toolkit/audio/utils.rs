@@ -1,7 +1,11 @@
 use anyhow::Result;
-use hound::{WavSpec, WavWriter};
+use hound::{WavReader, WavSpec, WavWriter};
 use log::debug;
 use std::path::Path;
+use std::time::Duration;
+
+use super::FrameResampler;
+use crate::audio_toolkit::constants::WHISPER_SAMPLE_RATE;
 
 /// Save audio samples as a WAV file
 pub async fn save_wav_file<P: AsRef<Path>>(file_path: P, samples: &[f32]) -> Result<()> {
@@ -24,3 +28,55 @@ pub async fn save_wav_file<P: AsRef<Path>>(file_path: P, samples: &[f32]) -> Res
     debug!("Saved WAV file: {:?}", file_path.as_ref());
     Ok(())
 }
+
+/// Read a WAV file into mono 16 kHz f32 samples, downmixing and resampling
+/// if the file uses a different layout than [`save_wav_file`] writes
+pub fn read_wav_file<P: AsRef<Path>>(file_path: P) -> Result<Vec<f32>> {
+    let mut reader = WavReader::open(file_path.as_ref())?;
+    let spec = reader.spec();
+
+    let interleaved = match spec.sample_format {
+        hound::SampleFormat::Int => {
+            let scale = (1_i64 << (spec.bits_per_sample - 1)) as f32;
+            reader
+                .samples::<i32>()
+                .map(|s| s.map(|s| s as f32 / scale))
+                .collect::<Result<Vec<f32>, _>>()?
+        }
+        hound::SampleFormat::Float => reader.samples::<f32>().collect::<Result<Vec<f32>, _>>()?,
+    };
+
+    // Downmix to mono by averaging channels
+    let channels = spec.channels.max(1) as usize;
+    let mono: Vec<f32> = if channels == 1 {
+        interleaved
+    } else {
+        interleaved
+            .chunks(channels)
+            .map(|frame| frame.iter().sum::<f32>() / frame.len() as f32)
+            .collect()
+    };
+
+    let samples = if spec.sample_rate == WHISPER_SAMPLE_RATE {
+        mono
+    } else {
+        let mut resampler = FrameResampler::new(
+            spec.sample_rate as usize,
+            WHISPER_SAMPLE_RATE as usize,
+            Duration::from_millis(30),
+        );
+        let mut resampled = Vec::with_capacity(
+            mono.len() * WHISPER_SAMPLE_RATE as usize / spec.sample_rate as usize,
+        );
+        resampler.push(&mono, |frame| resampled.extend_from_slice(frame));
+        resampler.finish(|frame| resampled.extend_from_slice(frame));
+        resampled
+    };
+
+    debug!(
+        "Read WAV file: {:?} ({} samples)",
+        file_path.as_ref(),
+        samples.len()
+    );
+    Ok(samples)
+}
@@ -1,12 +1,18 @@
 pub mod audio;
 pub mod constants;
+pub mod phonetics;
 pub mod text;
 pub mod utils;
 pub mod vad;
 
 pub use audio::{
-    list_input_devices, list_output_devices, save_wav_file, AudioRecorder, CpalDeviceInfo,
+    list_input_devices, list_output_devices, read_wav_file, save_wav_file, AudioRecorder,
+    CpalDeviceInfo,
+};
+pub use phonetics::PhoneticAlgorithm;
+pub use text::{
+    adapt_to_insertion_context, apply_custom_words, apply_inverse_normalization,
+    apply_spoken_commands, capitalize_sentences, remove_disfluencies, DisfluencyOptions,
 };
-pub use text::apply_custom_words;
 pub use utils::get_cpal_host;
 pub use vad::{SileroVad, VoiceActivityDetector};
@@ -0,0 +1,858 @@
+use natural::phonetics::soundex;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// Phonetic comparison used when fuzzy matching custom words
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum PhoneticAlgorithm {
+    /// English Soundex
+    Soundex,
+    /// Double Metaphone, which handles many European spellings and names
+    DoubleMetaphone,
+    /// Kölner Phonetik, designed for German
+    Cologne,
+    /// No phonetic comparison, only edit distance
+    EditDistance,
+}
+
+impl PhoneticAlgorithm {
+    /// Default algorithm for a language code such as "de" or "pt-BR"
+    pub fn for_language(language: &str) -> Self {
+        let primary = language
+            .split('-')
+            .next()
+            .unwrap_or(language)
+            .to_lowercase();
+        match primary.as_str() {
+            "en" | "auto" | "" => PhoneticAlgorithm::Soundex,
+            "de" => PhoneticAlgorithm::Cologne,
+            "fr" | "es" | "it" | "pt" | "nl" | "ca" | "ro" | "pl" | "cs" | "sk" | "sl" | "hr"
+            | "sv" | "da" | "no" | "nb" | "nn" | "fi" | "et" | "lv" | "lt" | "hu" | "tr" | "id"
+            | "ms" | "vi" | "tl" | "sw" | "cy" | "ga" | "eu" | "gl" | "af" | "is" => {
+                PhoneticAlgorithm::DoubleMetaphone
+            }
+            // Phonetic codes are meaningless for non-Latin scripts
+            _ => PhoneticAlgorithm::EditDistance,
+        }
+    }
+
+    /// Whether two lowercase words sound alike under this algorithm
+    pub fn matches(&self, a: &str, b: &str) -> bool {
+        match self {
+            PhoneticAlgorithm::Soundex => soundex(a, b),
+            PhoneticAlgorithm::DoubleMetaphone => {
+                let (primary_a, alternate_a) = double_metaphone(a);
+                let (primary_b, alternate_b) = double_metaphone(b);
+                !primary_a.is_empty()
+                    && (primary_a == primary_b
+                        || primary_a == alternate_b
+                        || alternate_a == primary_b
+                        || alternate_a == alternate_b)
+            }
+            PhoneticAlgorithm::Cologne => {
+                let code = cologne(a);
+                !code.is_empty() && code == cologne(b)
+            }
+            PhoneticAlgorithm::EditDistance => false,
+        }
+    }
+}
+
+/// Encodes a word with Kölner Phonetik
+pub fn cologne(word: &str) -> String {
+    let letters: Vec<char> = word
+        .chars()
+        .flat_map(char::to_uppercase)
+        .filter_map(|c| match c {
+            'Ä' => Some('A'),
+            'Ö' => Some('O'),
+            'Ü' => Some('U'),
+            'ß' => Some('S'),
+            c if c.is_ascii_alphabetic() => Some(c),
+            _ => None,
+        })
+        .collect();
+
+    let mut digits = String::new();
+    for (i, &c) in letters.iter().enumerate() {
+        let previous = if i > 0 { letters[i - 1] } else { '\0' };
+        let next = letters.get(i + 1).copied().unwrap_or('\0');
+
+        let code = match c {
+            'A' | 'E' | 'I' | 'J' | 'O' | 'U' | 'Y' => "0",
+            'H' => "",
+            'B' => "1",
+            'P' if next == 'H' => "3",
+            'P' => "1",
+            'D' | 'T' if matches!(next, 'C' | 'S' | 'Z') => "8",
+            'D' | 'T' => "2",
+            'F' | 'V' | 'W' => "3",
+            'G' | 'K' | 'Q' => "4",
+            'C' if i == 0 => {
+                if matches!(next, 'A' | 'H' | 'K' | 'L' | 'O' | 'Q' | 'R' | 'U' | 'X') {
+                    "4"
+                } else {
+                    "8"
+                }
+            }
+            'C' => {
+                if matches!(previous, 'S' | 'Z')
+                    || !matches!(next, 'A' | 'H' | 'K' | 'O' | 'Q' | 'U' | 'X')
+                {
+                    "8"
+                } else {
+                    "4"
+                }
+            }
+            'X' if matches!(previous, 'C' | 'K' | 'Q') => "8",
+            'X' => "48",
+            'L' => "5",
+            'M' | 'N' => "6",
+            'R' => "7",
+            'S' | 'Z' => "8",
+            _ => "",
+        };
+        digits.push_str(code);
+    }
+
+    // Collapse repeated codes, then drop vowels except at the start
+    let mut collapsed: Vec<char> = digits.chars().collect();
+    collapsed.dedup();
+    collapsed
+        .iter()
+        .enumerate()
+        .filter(|(i, &c)| c != '0' || *i == 0)
+        .map(|(_, &c)| c)
+        .collect()
+}
+
+const DOUBLE_METAPHONE_MAX_LEN: usize = 4;
+
+/// Encodes a word with Double Metaphone, returning the primary and alternate
+/// codes
+pub fn double_metaphone(word: &str) -> (String, String) {
+    let mut encoder = DoubleMetaphone::new(word);
+    encoder.encode();
+    encoder.primary.truncate(DOUBLE_METAPHONE_MAX_LEN);
+    encoder.alternate.truncate(DOUBLE_METAPHONE_MAX_LEN);
+    (encoder.primary, encoder.alternate)
+}
+
+/// State for encoding one word, following Lawrence Philips' rules
+struct DoubleMetaphone {
+    chars: Vec<char>,
+    primary: String,
+    alternate: String,
+    slavo_germanic: bool,
+}
+
+impl DoubleMetaphone {
+    fn new(word: &str) -> Self {
+        let chars: Vec<char> = word
+            .chars()
+            .flat_map(char::to_uppercase)
+            .filter(|c| c.is_alphabetic() || *c == ' ')
+            .collect();
+        let text: String = chars.iter().collect();
+        let slavo_germanic = text.contains('W')
+            || text.contains('K')
+            || text.contains("CZ")
+            || text.contains("WITZ");
+        Self {
+            chars,
+            primary: String::new(),
+            alternate: String::new(),
+            slavo_germanic,
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.chars.len()
+    }
+
+    fn char_at(&self, index: isize) -> char {
+        if index < 0 {
+            return '\0';
+        }
+        self.chars.get(index as usize).copied().unwrap_or('\0')
+    }
+
+    fn is_vowel_at(&self, index: isize) -> bool {
+        matches!(self.char_at(index), 'A' | 'E' | 'I' | 'O' | 'U' | 'Y')
+    }
+
+    /// Whether the text at `start` equals any of the options, which must all
+    /// be the same length
+    fn contains(&self, start: isize, options: &[&str]) -> bool {
+        let Some(first) = options.first() else {
+            return false;
+        };
+        let length = first.chars().count() as isize;
+        if start < 0 || start + length > self.len() as isize {
+            return false;
+        }
+        let slice: String = self.chars[start as usize..(start + length) as usize]
+            .iter()
+            .collect();
+        options.iter().any(|option| *option == slice)
+    }
+
+    fn add(&mut self, code: &str) {
+        self.primary.push_str(code);
+        self.alternate.push_str(code);
+    }
+
+    fn add_both(&mut self, primary: &str, alternate: &str) {
+        self.primary.push_str(primary);
+        self.alternate.push_str(alternate);
+    }
+
+    fn is_complete(&self) -> bool {
+        self.primary.len() >= DOUBLE_METAPHONE_MAX_LEN
+            && self.alternate.len() >= DOUBLE_METAPHONE_MAX_LEN
+    }
+
+    fn starts_germanic(&self) -> bool {
+        self.contains(0, &["VAN ", "VON "]) || self.contains(0, &["SCH"])
+    }
+
+    fn encode(&mut self) {
+        let mut index: isize = 0;
+        if self.contains(0, &["GN", "KN", "PN", "WR", "PS"]) {
+            index = 1;
+        }
+        if self.char_at(0) == 'X' {
+            self.add("S");
+            index = 1;
+        }
+
+        while !self.is_complete() && (index as usize) < self.len() {
+            let next = self.char_at(index + 1);
+            index = match self.char_at(index) {
+                'A' | 'E' | 'I' | 'O' | 'U' | 'Y' => {
+                    if index == 0 {
+                        self.add("A");
+                    }
+                    index + 1
+                }
+                'B' => {
+                    self.add("P");
+                    if next == 'B' {
+                        index + 2
+                    } else {
+                        index + 1
+                    }
+                }
+                'Ç' => {
+                    self.add("S");
+                    index + 1
+                }
+                'C' => self.handle_c(index),
+                'D' => self.handle_d(index),
+                'F' => self.add_skipping_double(index, 'F', "F"),
+                'G' => self.handle_g(index),
+                'H' => {
+                    if (index == 0 || self.is_vowel_at(index - 1)) && self.is_vowel_at(index + 1) {
+                        self.add("H");
+                        index + 2
+                    } else {
+                        index + 1
+                    }
+                }
+                'J' => self.handle_j(index),
+                'K' => self.add_skipping_double(index, 'K', "K"),
+                'L' => self.handle_l(index),
+                'M' => {
+                    self.add("M");
+                    if self.m_is_doubled(index) {
+                        index + 2
+                    } else {
+                        index + 1
+                    }
+                }
+                'N' => self.add_skipping_double(index, 'N', "N"),
+                'Ñ' => {
+                    self.add("N");
+                    index + 1
+                }
+                'P' => {
+                    if next == 'H' {
+                        self.add("F");
+                        index + 2
+                    } else {
+                        self.add("P");
+                        if matches!(next, 'P' | 'B') {
+                            index + 2
+                        } else {
+                            index + 1
+                        }
+                    }
+                }
+                'Q' => self.add_skipping_double(index, 'Q', "K"),
+                'R' => self.handle_r(index),
+                'S' => self.handle_s(index),
+                'T' => self.handle_t(index),
+                'V' => self.add_skipping_double(index, 'V', "F"),
+                'W' => self.handle_w(index),
+                'X' => self.handle_x(index),
+                'Z' => self.handle_z(index),
+                _ => index + 1,
+            };
+        }
+    }
+
+    fn add_skipping_double(&mut self, index: isize, letter: char, code: &str) -> isize {
+        self.add(code);
+        if self.char_at(index + 1) == letter {
+            index + 2
+        } else {
+            index + 1
+        }
+    }
+
+    fn handle_c(&mut self, index: isize) -> isize {
+        if self.is_germanic_ach(index) {
+            self.add("K");
+            index + 2
+        } else if index == 0 && self.contains(index, &["CAESAR"]) {
+            self.add("S");
+            index + 2
+        } else if self.contains(index, &["CH"]) {
+            self.handle_ch(index)
+        } else if self.contains(index, &["CZ"]) && !self.contains(index - 2, &["WICZ"]) {
+            self.add_both("S", "X");
+            index + 2
+        } else if self.contains(index + 1, &["CIA"]) {
+            self.add("X");
+            index + 3
+        } else if self.contains(index, &["CC"]) && !(index == 1 && self.char_at(0) == 'M') {
+            self.handle_cc(index)
+        } else if self.contains(index, &["CK", "CG", "CQ"]) {
+            self.add("K");
+            index + 2
+        } else if self.contains(index, &["CI", "CE", "CY"]) {
+            if self.contains(index, &["CIO", "CIE", "CIA"]) {
+                self.add_both("S", "X");
+            } else {
+                self.add("S");
+            }
+            index + 2
+        } else {
+            self.add("K");
+            if self.contains(index + 1, &[" C", " Q", " G"]) {
+                index + 3
+            } else if self.contains(index + 1, &["C", "K", "Q"])
+                && !self.contains(index + 1, &["CE", "CI"])
+            {
+                index + 2
+            } else {
+                index + 1
+            }
+        }
+    }
+
+    fn is_germanic_ach(&self, index: isize) -> bool {
+        if self.contains(index, &["CHIA"]) {
+            return true;
+        }
+        if index <= 1 || self.is_vowel_at(index - 2) || !self.contains(index - 1, &["ACH"]) {
+            return false;
+        }
+        let after = self.char_at(index + 2);
+        (after != 'I' && after != 'E') || self.contains(index - 2, &["BACHER", "MACHER"])
+    }
+
+    fn handle_cc(&mut self, index: isize) -> isize {
+        if self.contains(index + 2, &["I", "E", "H"]) && !self.contains(index + 2, &["HU"]) {
+            if (index == 1 && self.char_at(index - 1) == 'A')
+                || self.contains(index - 1, &["UCCEE", "UCCES"])
+            {
+                self.add("KS");
+            } else {
+                self.add("X");
+            }
+            index + 3
+        } else {
+            self.add("K");
+            index + 2
+        }
+    }
+
+    fn handle_ch(&mut self, index: isize) -> isize {
+        if index > 0 && self.contains(index, &["CHAE"]) {
+            self.add_both("K", "X");
+        } else if self.is_greek_ch(index) || self.is_hard_ch(index) {
+            self.add("K");
+        } else if index > 0 {
+            if self.contains(0, &["MC"]) {
+                self.add("K");
+            } else {
+                self.add_both("X", "K");
+            }
+        } else {
+            self.add("X");
+        }
+        index + 2
+    }
+
+    fn is_greek_ch(&self, index: isize) -> bool {
+        index == 0
+            && (self.contains(index + 1, &["HARAC", "HARIS"])
+                || self.contains(index + 1, &["HOR", "HYM", "HIA", "HEM"]))
+            && !self.contains(0, &["CHORE"])
+    }
+
+    fn is_hard_ch(&self, index: isize) -> bool {
+        self.starts_germanic()
+            || self.contains(index - 2, &["ORCHES", "ARCHIT", "ORCHID"])
+            || self.contains(index + 2, &["T", "S"])
+            || ((self.contains(index - 1, &["A", "O", "U", "E"]) || index == 0)
+                && (self.contains(
+                    index + 2,
+                    &["L", "R", "N", "M", "B", "H", "F", "V", "W", " "],
+                ) || index + 1 == self.len() as isize - 1))
+    }
+
+    fn handle_d(&mut self, index: isize) -> isize {
+        if self.contains(index, &["DG"]) {
+            if self.contains(index + 2, &["I", "E", "Y"]) {
+                self.add("J");
+                index + 3
+            } else {
+                self.add("TK");
+                index + 2
+            }
+        } else if self.contains(index, &["DT", "DD"]) {
+            self.add("T");
+            index + 2
+        } else {
+            self.add("T");
+            index + 1
+        }
+    }
+
+    fn handle_g(&mut self, index: isize) -> isize {
+        let next = self.char_at(index + 1);
+        if next == 'H' {
+            return self.handle_gh(index);
+        }
+
+        if next == 'N' {
+            if index == 1 && self.is_vowel_at(0) && !self.slavo_germanic {
+                self.add_both("KN", "N");
+            } else if !self.contains(index + 2, &["EY"])
+                && self.char_at(index + 1) != 'Y'
+                && !self.slavo_germanic
+            {
+                self.add_both("N", "KN");
+            } else {
+                self.add("KN");
+            }
+            index + 2
+        } else if self.contains(index + 1, &["LI"]) && !self.slavo_germanic {
+            self.add_both("KL", "L");
+            index + 2
+        } else if self.has_k_or_j_g(index) {
+            self.add_both("K", "J");
+            index + 2
+        } else if self.contains(index + 1, &["E", "I", "Y"])
+            || self.contains(index - 1, &["AGGI", "OGGI"])
+        {
+            if self.starts_germanic() || self.contains(index + 1, &["ET"]) {
+                self.add("K");
+            } else if self.contains(index + 1, &["IER"]) {
+                self.add("J");
+            } else {
+                self.add_both("J", "K");
+            }
+            index + 2
+        } else if next == 'G' {
+            self.add("K");
+            index + 2
+        } else {
+            self.add("K");
+            index + 1
+        }
+    }
+
+    /// "G" that may be hard or soft, as in "gerald" or "tiger"
+    fn has_k_or_j_g(&self, index: isize) -> bool {
+        let next = self.char_at(index + 1);
+        if index == 0 {
+            return next == 'Y'
+                || self.contains(
+                    index + 1,
+                    &[
+                        "ES", "EP", "EB", "EL", "EY", "IB", "IL", "IN", "IE", "EI", "ER",
+                    ],
+                );
+        }
+        (self.contains(index + 1, &["ER"]) || next == 'Y')
+            && !self.contains(0, &["DANGER", "RANGER", "MANGER"])
+            && !self.contains(index - 1, &["E", "I"])
+            && !self.contains(index - 1, &["RGY", "OGY"])
+    }
+
+    fn handle_gh(&mut self, index: isize) -> isize {
+        if index > 0 && !self.is_vowel_at(index - 1) {
+            self.add("K");
+        } else if index == 0 {
+            if self.char_at(index + 2) == 'I' {
+                self.add("J");
+            } else {
+                self.add("K");
+            }
+        } else if (index > 1 && self.contains(index - 2, &["B", "H", "D"]))
+            || (index > 2 && self.contains(index - 3, &["B", "H", "D"]))
+            || (index > 3 && self.contains(index - 4, &["B", "H"]))
+        {
+            // Silent, as in "bough" or "night"
+        } else if index > 2
+            && self.char_at(index - 1) == 'U'
+            && self.contains(index - 3, &["C", "G", "L", "R", "T"])
+        {
+            self.add("F");
+        } else if index > 0 && self.char_at(index - 1) != 'I' {
+            self.add("K");
+        }
+        index + 2
+    }
+
+    fn handle_j(&mut self, index: isize) -> isize {
+        if self.contains(index, &["JOSE"]) || self.contains(0, &["SAN "]) {
+            if (index == 0 && self.char_at(index + 4) == ' ')
+                || self.len() == 4
+                || self.contains(0, &["SAN "])
+            {
+                self.add("H");
+            } else {
+                self.add_both("J", "H");
+            }
+            return index + 1;
+        }
+
+        let next = self.char_at(index + 1);
+        if index == 0 {
+            self.add_both("J", "A");
+        } else if self.is_vowel_at(index - 1) && !self.slavo_germanic && matches!(next, 'A' | 'O') {
+            self.add_both("J", "H");
+        } else if index == self.len() as isize - 1 {
+            self.add_both("J", "");
+        } else if !self.contains(index + 1, &["L", "T", "K", "S", "N", "M", "B", "Z"])
+            && !self.contains(index - 1, &["S", "K", "L"])
+        {
+            self.add("J");
+        }
+
+        if next == 'J' {
+            index + 2
+        } else {
+            index + 1
+        }
+    }
+
+    fn handle_l(&mut self, index: isize) -> isize {
+        if self.char_at(index + 1) != 'L' {
+            self.add("L");
+            return index + 1;
+        }
+
+        // Spanish "-illo", "-illa" and "-alle" endings
+        let last = self.len() as isize - 1;
+        let spanish = (index == last - 2 && self.contains(index - 1, &["ILLO", "ILLA", "ALLE"]))
+            || ((self.contains(last - 1, &["AS", "OS"]) || self.contains(last, &["A", "O"]))
+                && self.contains(index - 1, &["ALLE"]));
+        if spanish {
+            self.add_both("L", "");
+        } else {
+            self.add("L");
+        }
+        index + 2
+    }
+
+    fn m_is_doubled(&self, index: isize) -> bool {
+        self.char_at(index + 1) == 'M'
+            || (self.contains(index - 1, &["UMB"])
+                && (index + 1 == self.len() as isize - 1 || self.contains(index + 2, &["ER"])))
+    }
+
+    fn handle_r(&mut self, index: isize) -> isize {
+        // French final "-ier" is silent in the primary code
+        if index == self.len() as isize - 1
+            && !self.slavo_germanic
+            && self.contains(index - 2, &["IE"])
+            && !self.contains(index - 4, &["ME", "MA"])
+        {
+            self.add_both("", "R");
+        } else {
+            self.add("R");
+        }
+        if self.char_at(index + 1) == 'R' {
+            index + 2
+        } else {
+            index + 1
+        }
+    }
+
+    fn handle_s(&mut self, index: isize) -> isize {
+        if self.contains(index - 1, &["ISL", "YSL"]) {
+            index + 1
+        } else if index == 0 && self.contains(index, &["SUGAR"]) {
+            self.add_both("X", "S");
+            index + 1
+        } else if self.contains(index, &["SH"]) {
+            if self.contains(index + 1, &["HEIM", "HOEK", "HOLM", "HOLZ"]) {
+                self.add("S");
+            } else {
+                self.add("X");
+            }
+            index + 2
+        } else if self.contains(index, &["SIO", "SIA"]) || self.contains(index, &["SIAN"]) {
+            if self.slavo_germanic {
+                self.add("S");
+            } else {
+                self.add_both("S", "X");
+            }
+            index + 3
+        } else if (index == 0 && self.contains(index + 1, &["M", "N", "L", "W"]))
+            || self.contains(index + 1, &["Z"])
+        {
+            self.add_both("S", "X");
+            if self.contains(index + 1, &["Z"]) {
+                index + 2
+            } else {
+                index + 1
+            }
+        } else if self.contains(index, &["SC"]) {
+            self.handle_sc(index)
+        } else {
+            if index == self.len() as isize - 1 && self.contains(index - 2, &["AI", "OI"]) {
+                self.add_both("", "S");
+            } else {
+                self.add("S");
+            }
+            if self.contains(index + 1, &["S", "Z"]) {
+                index + 2
+            } else {
+                index + 1
+            }
+        }
+    }
+
+    fn handle_sc(&mut self, index: isize) -> isize {
+        if self.char_at(index + 2) == 'H' {
+            if self.contains(index + 3, &["OO", "ER", "EN", "UY", "ED", "EM"]) {
+                if self.contains(index + 3, &["ER", "EN"]) {
+                    self.add_both("X", "SK");
+                } else {
+                    self.add("SK");
+                }
+            } else if index == 0 && !self.is_vowel_at(3) && self.char_at(3) != 'W' {
+                self.add_both("X", "S");
+            } else {
+                self.add("X");
+            }
+        } else if self.contains(index + 2, &["I", "E", "Y"]) {
+            self.add("S");
+        } else {
+            self.add("SK");
+        }
+        index + 3
+    }
+
+    fn handle_t(&mut self, index: isize) -> isize {
+        if self.contains(index, &["TION"]) || self.contains(index, &["TIA", "TCH"]) {
+            self.add("X");
+            index + 3
+        } else if self.contains(index, &["TH"]) || self.contains(index, &["TTH"]) {
+            if self.contains(index + 2, &["OM", "AM"]) || self.starts_germanic() {
+                self.add("T");
+            } else {
+                self.add_both("0", "T");
+            }
+            index + 2
+        } else {
+            self.add("T");
+            if self.contains(index + 1, &["T", "D"]) {
+                index + 2
+            } else {
+                index + 1
+            }
+        }
+    }
+
+    fn handle_w(&mut self, index: isize) -> isize {
+        if self.contains(index, &["WR"]) {
+            self.add("R");
+            return index + 2;
+        }
+
+        if index == 0 && (self.is_vowel_at(index + 1) || self.contains(index, &["WH"])) {
+            if self.is_vowel_at(index + 1) {
+                self.add_both("A", "F");
+            } else {
+                self.add("A");
+            }
+            index + 1
+        } else if (index == self.len() as isize - 1 && self.is_vowel_at(index - 1))
+            || self.contains(index - 1, &["EWSKI", "EWSKY", "OWSKI", "OWSKY"])
+            || self.contains(0, &["SCH"])
+        {
+            self.add_both("", "F");
+            index + 1
+        } else if self.contains(index, &["WICZ", "WITZ"]) {
+            self.add_both("TS", "FX");
+            index + 4
+        } else {
+            index + 1
+        }
+    }
+
+    fn handle_x(&mut self, index: isize) -> isize {
+        if index == 0 {
+            self.add("S");
+            return index + 1;
+        }
+
+        // French final "-aux" and "-eau" are silent
+        let silent_french = index == self.len() as isize - 1
+            && (self.contains(index - 3, &["IAU", "EAU"])
+                || self.contains(index - 2, &["AU", "OU"]));
+        if !silent_french {
+            self.add("KS");
+        }
+        if self.contains(index + 1, &["C", "X"]) {
+            index + 2
+        } else {
+            index + 1
+        }
+    }
+
+    fn handle_z(&mut self, index: isize) -> isize {
+        if self.char_at(index + 1) == 'H' {
+            self.add("J");
+            return index + 2;
+        }
+
+        if self.contains(index + 1, &["ZO", "ZI", "ZA"])
+            || (self.slavo_germanic && index > 0 && self.char_at(index - 1) != 'T')
+        {
+            self.add_both("S", "TS");
+        } else {
+            self.add("S");
+        }
+        if self.char_at(index + 1) == 'Z' {
+            index + 2
+        } else {
+            index + 1
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_double_metaphone_codes() {
+        assert_eq!(
+            double_metaphone("Smith"),
+            ("SM0".to_string(), "XMT".to_string())
+        );
+        assert_eq!(
+            double_metaphone("Schmidt"),
+            ("XMT".to_string(), "SMT".to_string())
+        );
+        assert_eq!(
+            double_metaphone("Thompson"),
+            ("TMPS".to_string(), "TMPS".to_string())
+        );
+        assert_eq!(
+            double_metaphone("Jose"),
+            ("HS".to_string(), "HS".to_string())
+        );
+        assert_eq!(
+            double_metaphone("Xavier"),
+            ("SF".to_string(), "SFR".to_string())
+        );
+        assert_eq!(
+            double_metaphone("knight"),
+            ("NT".to_string(), "NT".to_string())
+        );
+    }
+
+    #[test]
+    fn test_cologne_codes() {
+        assert_eq!(cologne("Müller-Lüdenscheidt"), "65752682");
+        assert_eq!(cologne("Wikipedia"), "3412");
+        assert_eq!(cologne("Breschnew"), "17863");
+        assert_eq!(cologne("Christoph"), "47823");
+    }
+
+    /// Each algorithm against pairs it should and should not treat as alike
+    #[test]
+    fn test_algorithm_matrix() {
+        let cases: &[(PhoneticAlgorithm, &str, &str, bool)] = &[
+            (PhoneticAlgorithm::Soundex, "robert", "rupert", true),
+            (PhoneticAlgorithm::Soundex, "handy", "hande", true),
+            (PhoneticAlgorithm::Soundex, "robert", "michael", false),
+            (PhoneticAlgorithm::DoubleMetaphone, "smith", "schmidt", true),
+            (PhoneticAlgorithm::DoubleMetaphone, "philip", "filip", true),
+            (PhoneticAlgorithm::DoubleMetaphone, "jean", "jane", true),
+            (
+                PhoneticAlgorithm::DoubleMetaphone,
+                "philip",
+                "margot",
+                false,
+            ),
+            (PhoneticAlgorithm::Cologne, "meier", "mayer", true),
+            (PhoneticAlgorithm::Cologne, "müller", "mueller", true),
+            (PhoneticAlgorithm::Cologne, "schmidt", "schmitt", true),
+            (PhoneticAlgorithm::Cologne, "müller", "schmidt", false),
+            (PhoneticAlgorithm::EditDistance, "meier", "meier", false),
+            (PhoneticAlgorithm::EditDistance, "robert", "rupert", false),
+        ];
+
+        for (algorithm, a, b, expected) in cases {
+            assert_eq!(
+                algorithm.matches(a, b),
+                *expected,
+                "{:?}: {} vs {}",
+                algorithm,
+                a,
+                b
+            );
+        }
+    }
+
+    #[test]
+    fn test_non_latin_words_never_match_phonetically() {
+        assert!(!PhoneticAlgorithm::Cologne.matches("東京", "大阪"));
+        assert!(!PhoneticAlgorithm::DoubleMetaphone.matches("東京", "大阪"));
+    }
+
+    #[test]
+    fn test_for_language() {
+        assert_eq!(
+            PhoneticAlgorithm::for_language("en"),
+            PhoneticAlgorithm::Soundex
+        );
+        assert_eq!(
+            PhoneticAlgorithm::for_language("auto"),
+            PhoneticAlgorithm::Soundex
+        );
+        assert_eq!(
+            PhoneticAlgorithm::for_language("de"),
+            PhoneticAlgorithm::Cologne
+        );
+        assert_eq!(
+            PhoneticAlgorithm::for_language("pt-BR"),
+            PhoneticAlgorithm::DoubleMetaphone
+        );
+        assert_eq!(
+            PhoneticAlgorithm::for_language("zh-Hans"),
+            PhoneticAlgorithm::EditDistance
+        );
+    }
+}
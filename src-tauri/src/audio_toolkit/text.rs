@@ -1,96 +1,203 @@
-use natural::phonetics::soundex;
 use strsim::levenshtein;
 
+use crate::audio_toolkit::phonetics::PhoneticAlgorithm;
+
+/// Transcript words are compared in windows of up to this many words, so that
+/// multi-word custom entries and names split into several tokens can match
+const MAX_WINDOW_WORDS: usize = 4;
+
+/// A custom word prepared for matching
+struct CustomEntry<'a> {
+    word: &'a String,
+    /// Lowercase with the spaces removed, so "Tauri specta" and "taurispecta"
+    /// compare equal
+    compact: String,
+    word_count: usize,
+}
+
 /// Applies custom word corrections to transcribed text using fuzzy matching
 ///
 /// This function corrects words in the input text by finding the best matches
 /// from a list of custom words using a combination of:
 /// - Levenshtein distance for string similarity
-/// - Soundex phonetic matching for pronunciation similarity
+/// - Phonetic matching for pronunciation similarity, using the algorithm suited
+///   to the spoken language
+///
+/// Custom words may contain several words ("Kubernetes operator"). Windows of
+/// consecutive transcript words are compared against them, so an entry can
+/// replace a phrase, a name the model split in two ("hand e" -> "Handy") or a
+/// phrase the model merged into one token.
 ///
 /// # Arguments
 /// * `text` - The input text to correct
 /// * `custom_words` - List of custom words to match against
 /// * `threshold` - Maximum similarity score to accept (0.0 = exact match, 1.0 = any match)
+/// * `phonetic` - Algorithm used to decide whether two words sound alike
 ///
 /// # Returns
 /// The corrected text with custom words applied
-pub fn apply_custom_words(text: &str, custom_words: &[String], threshold: f64) -> String {
+pub fn apply_custom_words(
+    text: &str,
+    custom_words: &[String],
+    threshold: f64,
+    phonetic: PhoneticAlgorithm,
+) -> String {
     if custom_words.is_empty() {
         return text.to_string();
     }
 
-    // Pre-compute lowercase versions to avoid repeated allocations
-    let custom_words_lower: Vec<String> = custom_words.iter().map(|w| w.to_lowercase()).collect();
+    // Pre-compute the compact forms to avoid repeated allocations
+    let entries: Vec<CustomEntry> = custom_words
+        .iter()
+        .map(|word| CustomEntry {
+            word,
+            compact: word.split_whitespace().collect::<String>().to_lowercase(),
+            word_count: word.split_whitespace().count().max(1),
+        })
+        .collect();
+    // An entry may be spoken as one more token than it has words
+    let max_window = entries
+        .iter()
+        .map(|entry| entry.word_count + 1)
+        .max()
+        .unwrap_or(1)
+        .min(MAX_WINDOW_WORDS);
 
     let words: Vec<&str> = text.split_whitespace().collect();
+    let cleaned_words: Vec<String> = words
+        .iter()
+        .map(|word| {
+            word.trim_matches(|c: char| !c.is_alphabetic())
+                .to_lowercase()
+        })
+        .collect();
     let mut corrected_words = Vec::new();
 
-    for word in words {
-        let cleaned_word = word
-            .trim_matches(|c: char| !c.is_alphabetic())
-            .to_lowercase();
+    let mut i = 0;
+    while i < words.len() {
+        let best_match = find_best_match(
+            &words[i..],
+            &cleaned_words[i..],
+            &entries,
+            max_window,
+            threshold,
+            phonetic,
+        );
 
+        if let Some((replacement, window_len)) = best_match {
+            let window = &words[i..i + window_len];
+
+            // Preserve the original case pattern as much as possible
+            let corrected = preserve_case_pattern(&window.concat(), replacement);
+
+            // Preserve punctuation around the replaced words
+            let (prefix, _) = extract_punctuation(window[0]);
+            let (_, suffix) = extract_punctuation(window[window_len - 1]);
+            corrected_words.push(format!("{}{}{}", prefix, corrected, suffix));
+            i += window_len;
+        } else {
+            corrected_words.push(words[i].to_string());
+            i += 1;
+        }
+    }
+
+    corrected_words.join(" ")
+}
+
+/// Finds the custom word that best matches a window of words starting at the
+/// first of `words`, returning it with the number of words it replaces. On a
+/// tie the longer window wins so split names are merged.
+fn find_best_match<'a>(
+    words: &[&str],
+    cleaned_words: &[String],
+    entries: &[CustomEntry<'a>],
+    max_window: usize,
+    threshold: f64,
+    phonetic: PhoneticAlgorithm,
+) -> Option<(&'a String, usize)> {
+    let mut best_match = None;
+    let mut best_score = f64::MAX;
+    let mut candidate = String::new();
+
+    for window_len in 1..=max_window.min(words.len()) {
+        let cleaned_word = &cleaned_words[window_len - 1];
         if cleaned_word.is_empty() {
-            corrected_words.push(word.to_string());
-            continue;
+            break;
         }
 
+        // Don't join words across punctuation, e.g. "hand, e"
+        if window_len > 1 {
+            let (_, previous_suffix) = extract_punctuation(words[window_len - 2]);
+            let (prefix, _) = extract_punctuation(words[window_len - 1]);
+            if !previous_suffix.is_empty() || !prefix.is_empty() {
+                break;
+            }
+        }
+
+        candidate.push_str(cleaned_word);
+
         // Skip extremely long words to avoid performance issues
-        if cleaned_word.len() > 50 {
-            corrected_words.push(word.to_string());
-            continue;
+        if candidate.len() > 50 {
+            break;
         }
 
-        let mut best_match: Option<&String> = None;
-        let mut best_score = f64::MAX;
+        for entry in entries {
+            // Only try windows at most one word longer than the entry, so common
+            // words aren't merged into short custom words
+            if window_len > entry.word_count + 1 {
+                continue;
+            }
 
-        for (i, custom_word_lower) in custom_words_lower.iter().enumerate() {
             // Skip if lengths are too different (optimization)
-            let len_diff = (cleaned_word.len() as i32 - custom_word_lower.len() as i32).abs();
+            let len_diff = (candidate.len() as i32 - entry.compact.len() as i32).abs();
             if len_diff > 5 {
                 continue;
             }
 
-            // Calculate Levenshtein distance (normalized by length)
-            let levenshtein_dist = levenshtein(&cleaned_word, custom_word_lower);
-            let max_len = cleaned_word.len().max(custom_word_lower.len()) as f64;
-            let levenshtein_score = if max_len > 0.0 {
-                levenshtein_dist as f64 / max_len
-            } else {
-                1.0
-            };
-
-            // Calculate phonetic similarity using Soundex
-            let phonetic_match = soundex(&cleaned_word, custom_word_lower);
+            let score = similarity_score(&candidate, &entry.compact, phonetic);
 
-            // Combine scores: favor phonetic matches, but also consider string similarity
-            let combined_score = if phonetic_match {
-                levenshtein_score * 0.3 // Give significant boost to phonetic matches
-            } else {
-                levenshtein_score
-            };
+            // Don't swallow a leading word that doesn't help the match, e.g.
+            // "the" in "the kubernetes operator"
+            if window_len > 1 {
+                let without_first = cleaned_words[1..window_len].concat();
+                if similarity_score(&without_first, &entry.compact, phonetic) <= score {
+                    continue;
+                }
+            }
 
             // Accept if the score is good enough (configurable threshold)
-            if combined_score < threshold && combined_score < best_score {
-                best_match = Some(&custom_words[i]);
-                best_score = combined_score;
+            let is_better = score < best_score
+                || (score == best_score && best_match.is_some_and(|(_, len)| len < window_len));
+            if score < threshold && is_better {
+                best_match = Some((entry.word, window_len));
+                best_score = score;
             }
         }
+    }
 
-        if let Some(replacement) = best_match {
-            // Preserve the original case pattern as much as possible
-            let corrected = preserve_case_pattern(word, replacement);
+    best_match
+}
 
-            // Preserve punctuation from original word
-            let (prefix, suffix) = extract_punctuation(word);
-            corrected_words.push(format!("{}{}{}", prefix, corrected, suffix));
-        } else {
-            corrected_words.push(word.to_string());
-        }
-    }
+/// Scores how close two lowercase words are, 0.0 being identical
+fn similarity_score(word: &str, custom_word: &str, phonetic: PhoneticAlgorithm) -> f64 {
+    // Calculate Levenshtein distance (normalized by length)
+    let levenshtein_dist = levenshtein(word, custom_word);
+    let max_len = word.len().max(custom_word.len()) as f64;
+    let levenshtein_score = if max_len > 0.0 {
+        levenshtein_dist as f64 / max_len
+    } else {
+        1.0
+    };
 
-    corrected_words.join(" ")
+    // Calculate phonetic similarity
+    let phonetic_match = phonetic.matches(word, custom_word);
+
+    // Combine scores: favor phonetic matches, but also consider string similarity
+    if phonetic_match {
+        levenshtein_score * 0.3 // Give significant boost to phonetic matches
+    } else {
+        levenshtein_score
+    }
 }
 
 /// Preserves the case pattern of the original word when applying a replacement
@@ -132,6 +239,778 @@ fn extract_punctuation(word: &str) -> (&str, &str) {
     (prefix, suffix)
 }
 
+/// Splits text at whitespace into words, each with the whitespace after it,
+/// so that line breaks can be written back. Also returns the whitespace before
+/// the first word.
+fn split_words(text: &str) -> (&str, Vec<(&str, &str)>) {
+    let mut rest = text.trim_start();
+    let leading = &text[..text.len() - rest.len()];
+
+    let mut words = Vec::new();
+    while !rest.is_empty() {
+        let word_end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        let (word, after) = rest.split_at(word_end);
+        let space_end = after
+            .find(|c: char| !c.is_whitespace())
+            .unwrap_or(after.len());
+        words.push((word, &after[..space_end]));
+        rest = &after[space_end..];
+    }
+    (leading, words)
+}
+
+/// A formatting command that can be dictated instead of typed
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum SpokenCommand {
+    /// Punctuation attached to the previous word
+    Punctuation(&'static str),
+    /// Punctuation that ends a sentence; the next word is capitalized
+    SentenceEnd(&'static str),
+    NewLine,
+    NewParagraph,
+    OpenQuote,
+    CloseQuote,
+    /// Upper-cases the next word
+    AllCaps,
+}
+
+struct SpokenCommandTable {
+    /// Saying this word before a command phrase types the phrase literally
+    escape_word: &'static str,
+    commands: &'static [(&'static str, SpokenCommand)],
+}
+
+static ENGLISH_COMMANDS: SpokenCommandTable = SpokenCommandTable {
+    escape_word: "literal",
+    commands: &[
+        ("comma", SpokenCommand::Punctuation(",")),
+        ("colon", SpokenCommand::Punctuation(":")),
+        ("semicolon", SpokenCommand::Punctuation(";")),
+        ("period", SpokenCommand::SentenceEnd(".")),
+        ("full stop", SpokenCommand::SentenceEnd(".")),
+        ("question mark", SpokenCommand::SentenceEnd("?")),
+        ("exclamation mark", SpokenCommand::SentenceEnd("!")),
+        ("exclamation point", SpokenCommand::SentenceEnd("!")),
+        ("new line", SpokenCommand::NewLine),
+        ("new paragraph", SpokenCommand::NewParagraph),
+        ("open quote", SpokenCommand::OpenQuote),
+        ("close quote", SpokenCommand::CloseQuote),
+        ("end quote", SpokenCommand::CloseQuote),
+        ("all caps", SpokenCommand::AllCaps),
+    ],
+};
+
+static GERMAN_COMMANDS: SpokenCommandTable = SpokenCommandTable {
+    escape_word: "wörtlich",
+    commands: &[
+        ("komma", SpokenCommand::Punctuation(",")),
+        ("doppelpunkt", SpokenCommand::Punctuation(":")),
+        ("semikolon", SpokenCommand::Punctuation(";")),
+        ("punkt", SpokenCommand::SentenceEnd(".")),
+        ("fragezeichen", SpokenCommand::SentenceEnd("?")),
+        ("ausrufezeichen", SpokenCommand::SentenceEnd("!")),
+        ("neue zeile", SpokenCommand::NewLine),
+        ("neuer absatz", SpokenCommand::NewParagraph),
+        ("anführungszeichen auf", SpokenCommand::OpenQuote),
+        ("anführungszeichen zu", SpokenCommand::CloseQuote),
+        ("großbuchstaben", SpokenCommand::AllCaps),
+    ],
+};
+
+static FRENCH_COMMANDS: SpokenCommandTable = SpokenCommandTable {
+    escape_word: "littéralement",
+    commands: &[
+        ("virgule", SpokenCommand::Punctuation(",")),
+        ("deux points", SpokenCommand::Punctuation(" :")),
+        ("point virgule", SpokenCommand::Punctuation(" ;")),
+        ("point", SpokenCommand::SentenceEnd(".")),
+        ("point d'interrogation", SpokenCommand::SentenceEnd(" ?")),
+        ("point d'exclamation", SpokenCommand::SentenceEnd(" !")),
+        ("à la ligne", SpokenCommand::NewLine),
+        ("nouveau paragraphe", SpokenCommand::NewParagraph),
+        ("ouvrez les guillemets", SpokenCommand::OpenQuote),
+        ("fermez les guillemets", SpokenCommand::CloseQuote),
+        ("tout en majuscules", SpokenCommand::AllCaps),
+    ],
+};
+
+static SPANISH_COMMANDS: SpokenCommandTable = SpokenCommandTable {
+    escape_word: "literal",
+    commands: &[
+        ("coma", SpokenCommand::Punctuation(",")),
+        ("dos puntos", SpokenCommand::Punctuation(":")),
+        ("punto y coma", SpokenCommand::Punctuation(";")),
+        ("punto", SpokenCommand::SentenceEnd(".")),
+        ("signo de interrogación", SpokenCommand::SentenceEnd("?")),
+        ("signo de exclamación", SpokenCommand::SentenceEnd("!")),
+        ("nueva línea", SpokenCommand::NewLine),
+        ("nuevo párrafo", SpokenCommand::NewParagraph),
+        ("abrir comillas", SpokenCommand::OpenQuote),
+        ("cerrar comillas", SpokenCommand::CloseQuote),
+        ("todo mayúsculas", SpokenCommand::AllCaps),
+    ],
+};
+
+/// Command table for a language code, falling back to English
+fn spoken_command_table(language: &str) -> &'static SpokenCommandTable {
+    match language.split('-').next().unwrap_or(language) {
+        "de" => &GERMAN_COMMANDS,
+        "fr" => &FRENCH_COMMANDS,
+        "es" => &SPANISH_COMMANDS,
+        _ => &ENGLISH_COMMANDS,
+    }
+}
+
+/// Longest command phrase at the start of `words`, with its length in words
+fn match_spoken_command(
+    table: &SpokenCommandTable,
+    words: &[String],
+) -> Option<(SpokenCommand, usize)> {
+    table
+        .commands
+        .iter()
+        .filter_map(|(phrase, command)| {
+            let phrase_words: Vec<&str> = phrase.split_whitespace().collect();
+            let matches = phrase_words.len() <= words.len()
+                && phrase_words
+                    .iter()
+                    .zip(words)
+                    .all(|(expected, word)| *expected == word);
+            matches.then_some((*command, phrase_words.len()))
+        })
+        .max_by_key(|(_, len)| *len)
+}
+
+/// Accumulates output for [`apply_spoken_commands`]
+#[derive(Default)]
+struct SpokenCommandWriter {
+    output: String,
+    /// Join the next word without a space, e.g. after an opening quote
+    attach_next: bool,
+    capitalize_next: bool,
+    upper_case_next: bool,
+}
+
+impl SpokenCommandWriter {
+    fn push_word(&mut self, word: &str) {
+        if !self.attach_next && !self.output.is_empty() && !self.output.ends_with('\n') {
+            self.output.push(' ');
+        }
+        if self.upper_case_next {
+            self.output.push_str(&word.to_uppercase());
+        } else if self.capitalize_next {
+            let mut chars = word.chars();
+            if let Some(first) = chars.next() {
+                self.output.extend(first.to_uppercase());
+                self.output.push_str(chars.as_str());
+            }
+        } else {
+            self.output.push_str(word);
+        }
+        self.attach_next = false;
+        self.capitalize_next = false;
+        self.upper_case_next = false;
+    }
+
+    fn trim_end_matches(&mut self, pattern: &str) {
+        let len = self
+            .output
+            .trim_end_matches(|c: char| pattern.contains(c))
+            .len();
+        self.output.truncate(len);
+    }
+
+    fn apply(&mut self, command: SpokenCommand) {
+        match command {
+            SpokenCommand::Punctuation(symbol) | SpokenCommand::SentenceEnd(symbol) => {
+                // Drop punctuation the engine guessed next to the command
+                self.trim_end_matches(",.;:!? ");
+                self.output.push_str(symbol);
+                self.capitalize_next = matches!(command, SpokenCommand::SentenceEnd(_));
+            }
+            SpokenCommand::NewLine => {
+                self.trim_end_matches(" ");
+                self.output.push('\n');
+            }
+            SpokenCommand::NewParagraph => {
+                self.trim_end_matches(" ");
+                self.output.push_str("\n\n");
+                self.capitalize_next = true;
+            }
+            SpokenCommand::OpenQuote => {
+                if !self.output.is_empty() && !self.output.ends_with('\n') {
+                    self.output.push(' ');
+                }
+                self.output.push('"');
+                self.attach_next = true;
+            }
+            SpokenCommand::CloseQuote => {
+                self.trim_end_matches(" ");
+                self.output.push('"');
+            }
+            SpokenCommand::AllCaps => self.upper_case_next = true,
+        }
+    }
+}
+
+/// Converts dictated punctuation and formatting commands into symbols
+///
+/// Recognizes phrases such as "comma", "question mark", "new paragraph",
+/// "open quote" / "close quote" and "all caps" (upper-cases the next word),
+/// using the command table for `language`. Punctuation the engine already
+/// placed next to a command is replaced rather than doubled. Saying the
+/// table's escape word (e.g. "literal") before a command types it verbatim.
+///
+/// # Arguments
+/// * `text` - The transcribed text
+/// * `language` - Language code selecting the command table, e.g. "en" or "de"
+///
+/// # Returns
+/// The text with commands applied
+pub fn apply_spoken_commands(text: &str, language: &str) -> String {
+    let table = spoken_command_table(language);
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let normalized: Vec<String> = words
+        .iter()
+        .map(|word| {
+            word.trim_matches(|c: char| !c.is_alphanumeric())
+                .to_lowercase()
+        })
+        .collect();
+
+    let mut writer = SpokenCommandWriter::default();
+    let mut i = 0;
+    while i < words.len() {
+        let escaped = (normalized[i] == table.escape_word)
+            .then(|| match_spoken_command(table, &normalized[i + 1..]))
+            .flatten();
+        if let Some((_, len)) = escaped {
+            // Only an escape when a command follows, so "a literal
+            // translation" keeps its word
+            for word in &words[i + 1..i + 1 + len] {
+                writer.push_word(word);
+            }
+            i += 1 + len;
+        } else if let Some((command, len)) = match_spoken_command(table, &normalized[i..]) {
+            writer.apply(command);
+            i += len;
+        } else {
+            writer.push_word(words[i]);
+            i += 1;
+        }
+    }
+
+    writer.output
+}
+
+/// Which kinds of disfluency [`remove_disfluencies`] removes
+#[derive(Clone, Copy, Debug)]
+pub struct DisfluencyOptions<'a> {
+    pub remove_fillers: bool,
+    pub remove_repetitions: bool,
+    pub remove_self_corrections: bool,
+    /// Additional filler words, always removed
+    pub extra_fillers: &'a [String],
+}
+
+struct DisfluencyTable {
+    /// Hesitation sounds, always removed
+    fillers: &'static [&'static str],
+    /// Fillers that are also ordinary words ("like"), only removed when set off
+    /// by commas
+    contextual_fillers: &'static [&'static str],
+    /// Phrases marking that the preceding words are being corrected
+    correction_markers: &'static [&'static str],
+    /// Words that are legitimately doubled, as in "I know that that works"
+    valid_repetitions: &'static [&'static str],
+}
+
+static ENGLISH_DISFLUENCIES: DisfluencyTable = DisfluencyTable {
+    fillers: &[
+        "um", "umm", "uh", "uhh", "uhm", "er", "erm", "ah", "hmm", "mm", "mhm",
+    ],
+    contextual_fillers: &["like", "you know", "i mean", "you see"],
+    correction_markers: &["i mean", "i meant", "sorry", "no wait", "or rather"],
+    valid_repetitions: &["that", "had"],
+};
+
+static GERMAN_DISFLUENCIES: DisfluencyTable = DisfluencyTable {
+    fillers: &["äh", "ähm", "öhm", "ehm", "hm", "hmm"],
+    contextual_fillers: &["sozusagen", "quasi", "weißt du"],
+    correction_markers: &["ich meine", "ich meinte", "sorry", "nein warte"],
+    valid_repetitions: &["die", "das", "der"],
+};
+
+static FRENCH_DISFLUENCIES: DisfluencyTable = DisfluencyTable {
+    fillers: &["euh", "heu", "hum", "bah"],
+    contextual_fillers: &["genre", "tu vois", "en fait", "quoi"],
+    correction_markers: &["je veux dire", "pardon", "non attends"],
+    valid_repetitions: &["nous", "vous"],
+};
+
+static SPANISH_DISFLUENCIES: DisfluencyTable = DisfluencyTable {
+    fillers: &["eh", "em", "ehm", "mmm"],
+    contextual_fillers: &["este", "o sea", "tipo", "sabes"],
+    correction_markers: &["quiero decir", "perdón", "digo"],
+    valid_repetitions: &[],
+};
+
+/// Disfluency table for a language code, falling back to English
+fn disfluency_table(language: &str) -> &'static DisfluencyTable {
+    match language.split('-').next().unwrap_or(language) {
+        "de" => &GERMAN_DISFLUENCIES,
+        "fr" => &FRENCH_DISFLUENCIES,
+        "es" => &SPANISH_DISFLUENCIES,
+        _ => &ENGLISH_DISFLUENCIES,
+    }
+}
+
+/// How many words back a self-correction may restart from, as in "to John, I
+/// mean to Jane"
+const MAX_REPARANDUM_WORDS: usize = 4;
+
+/// A word split from its surrounding punctuation
+#[derive(Clone)]
+struct DisfluencyWord {
+    prefix: String,
+    body: String,
+    suffix: String,
+    /// Whitespace up to the next word, which may hold dictated line breaks
+    trailing: String,
+    /// Lowercase body, for matching
+    normalized: String,
+}
+
+impl DisfluencyWord {
+    fn new(word: &str, trailing: &str) -> Self {
+        let body = word.trim_matches(|c: char| !c.is_alphanumeric());
+        let start = word.find(body).unwrap_or(0);
+        Self {
+            prefix: word[..start].to_string(),
+            body: body.to_string(),
+            suffix: word[start + body.len()..].to_string(),
+            trailing: trailing.to_string(),
+            normalized: body.to_lowercase(),
+        }
+    }
+
+    fn ends_line(&self) -> bool {
+        self.trailing.contains('\n')
+    }
+
+    fn ends_with_comma(&self) -> bool {
+        self.suffix.contains(',')
+    }
+
+    fn ends_sentence(&self) -> bool {
+        self.suffix.contains(['.', '?', '!'])
+    }
+
+    fn is_capitalized(&self) -> bool {
+        self.body.chars().next().is_some_and(|c| c.is_uppercase())
+    }
+
+    fn capitalize(&mut self) {
+        let mut chars = self.body.chars();
+        if let Some(first) = chars.next() {
+            self.body = first.to_uppercase().chain(chars).collect();
+        }
+    }
+
+    fn to_text(&self) -> String {
+        format!("{}{}{}", self.prefix, self.body, self.suffix)
+    }
+}
+
+/// Length in words of the phrase from `phrases` found at `words[i]`, without
+/// punctuation inside it
+fn match_phrase(words: &[DisfluencyWord], i: usize, phrases: &[&str]) -> Option<usize> {
+    phrases
+        .iter()
+        .filter_map(|phrase| {
+            let parts: Vec<&str> = phrase.split(' ').collect();
+            let candidate = words.get(i..i + parts.len())?;
+            let matches = candidate
+                .iter()
+                .zip(&parts)
+                .all(|(word, part)| word.normalized == *part)
+                && candidate[..parts.len() - 1]
+                    .iter()
+                    .all(|word| word.suffix.is_empty());
+            matches.then_some(parts.len())
+        })
+        .max()
+}
+
+/// Whether the next kept word starts a sentence or line
+fn at_sentence_start(kept: &[DisfluencyWord]) -> bool {
+    kept.last()
+        .is_none_or(|word| word.ends_sentence() || word.ends_line())
+}
+
+/// Moves a line break that followed removed words onto the word before them
+fn keep_line_break(previous: &mut DisfluencyWord, removed: &[DisfluencyWord]) {
+    if previous.ends_line() {
+        return;
+    }
+    if let Some(line_break) = removed.iter().rev().find(|word| word.ends_line()) {
+        previous.trailing = line_break.trailing.clone();
+    }
+}
+
+/// Drops the words from `removed`, keeping sentence punctuation and
+/// capitalisation intact around them
+fn drop_words(
+    kept: &mut [DisfluencyWord],
+    removed: &[DisfluencyWord],
+    ends_text: bool,
+    capitalize_next: &mut bool,
+) {
+    let Some(last_removed) = removed.last() else {
+        return;
+    };
+
+    if at_sentence_start(kept) && removed[0].is_capitalized() {
+        *capitalize_next = true;
+    }
+
+    if let Some(previous) = kept.last_mut() {
+        if last_removed.ends_sentence() || ends_text {
+            // "so um." and "it works, you know." keep their full stop, and
+            // "so, you know" doesn't end on a comma
+            previous.suffix = format!(
+                "{}{}",
+                previous.suffix.trim_end_matches(','),
+                last_removed.suffix
+            );
+        } else if last_removed.ends_with_comma() && previous.ends_with_comma() {
+            // "I, uh, think" reads as "I think"
+            previous.suffix = previous.suffix.replacen(',', "", 1);
+        }
+        keep_line_break(previous, removed);
+    }
+}
+
+fn push_word(kept: &mut Vec<DisfluencyWord>, mut word: DisfluencyWord, capitalize_next: &mut bool) {
+    if std::mem::take(capitalize_next) {
+        word.capitalize();
+    }
+    kept.push(word);
+}
+
+/// Removes "I mean"/"sorry" self-corrections together with the words they
+/// correct: "on Tuesday, sorry, on Wednesday" becomes "on Wednesday"
+fn remove_self_corrections(
+    words: Vec<DisfluencyWord>,
+    table: &DisfluencyTable,
+) -> Vec<DisfluencyWord> {
+    let mut kept: Vec<DisfluencyWord> = Vec::new();
+    let mut capitalize_next = false;
+
+    let mut i = 0;
+    while i < words.len() {
+        let marker = match_phrase(&words, i, table.correction_markers).filter(|&len| {
+            // Only a correction when set off by a comma and followed by the
+            // repair, so "what I mean is" and "I'm sorry" are left alone
+            let set_off = kept.last().is_some_and(DisfluencyWord::ends_with_comma)
+                || words[i + len - 1].ends_with_comma();
+            set_off && !at_sentence_start(&kept) && i + len < words.len()
+        });
+
+        let Some(len) = marker else {
+            push_word(&mut kept, words[i].clone(), &mut capitalize_next);
+            i += 1;
+            continue;
+        };
+
+        // Restart from an earlier occurrence of the repair's first word within
+        // the sentence. Without one there's no telling what is corrected, as
+        // in "Thank you, sorry, for the delay", so the text stays as it is.
+        let sentence_start = kept
+            .iter()
+            .rposition(DisfluencyWord::ends_sentence)
+            .map_or(0, |index| index + 1);
+        let search_from = sentence_start.max(kept.len().saturating_sub(MAX_REPARANDUM_WORDS));
+        let repair = &words[i + len].normalized;
+        let Some(cut) = (search_from..kept.len())
+            .rev()
+            .find(|&index| kept[index].normalized == *repair)
+        else {
+            push_word(&mut kept, words[i].clone(), &mut capitalize_next);
+            i += 1;
+            continue;
+        };
+
+        let removed = kept.split_off(cut);
+        if at_sentence_start(&kept) && removed[0].is_capitalized() {
+            capitalize_next = true;
+        }
+        if let Some(previous) = kept.last_mut() {
+            keep_line_break(previous, &removed);
+            keep_line_break(previous, &words[i..i + len]);
+        }
+        i += len;
+    }
+
+    kept
+}
+
+/// Removes hesitation fillers, and ambiguous fillers when set off by commas
+fn remove_fillers(
+    words: Vec<DisfluencyWord>,
+    table: &DisfluencyTable,
+    extra_fillers: &[String],
+) -> Vec<DisfluencyWord> {
+    let mut kept: Vec<DisfluencyWord> = Vec::new();
+    let mut capitalize_next = false;
+
+    let mut i = 0;
+    while i < words.len() {
+        let word = &words[i];
+        let is_filler = table.fillers.contains(&word.normalized.as_str())
+            || extra_fillers
+                .iter()
+                .any(|filler| filler.to_lowercase() == word.normalized);
+
+        let len = if is_filler {
+            Some(1)
+        } else {
+            match_phrase(&words, i, table.contextual_fillers).filter(|&len| {
+                let last = &words[i + len - 1];
+                let opened = at_sentence_start(&kept)
+                    || kept.last().is_some_and(DisfluencyWord::ends_with_comma);
+                // "like, ..." or "..., you know."
+                (opened && last.ends_with_comma())
+                    || (kept.last().is_some_and(DisfluencyWord::ends_with_comma)
+                        && (last.ends_sentence() || i + len == words.len()))
+            })
+        };
+
+        match len {
+            Some(len) => {
+                let ends_text = i + len == words.len();
+                drop_words(
+                    &mut kept,
+                    &words[i..i + len],
+                    ends_text,
+                    &mut capitalize_next,
+                );
+                i += len;
+            }
+            None => {
+                push_word(&mut kept, word.clone(), &mut capitalize_next);
+                i += 1;
+            }
+        }
+    }
+
+    kept
+}
+
+/// Collapses immediately repeated words and short phrases ("the the", "I
+/// think I think")
+fn remove_repetitions(
+    mut words: Vec<DisfluencyWord>,
+    table: &DisfluencyTable,
+) -> Vec<DisfluencyWord> {
+    for len in (1..=3).rev() {
+        let mut i = 0;
+        while i + 2 * len <= words.len() {
+            let first = &words[i..i + len];
+            let second = &words[i + len..i + 2 * len];
+            let repeated = first
+                .iter()
+                .zip(second)
+                .all(|(a, b)| a.normalized == b.normalized && !a.normalized.is_empty())
+                // Only a comma may separate the repetition, and none inside it
+                && first[..len - 1].iter().all(|word| word.suffix.is_empty())
+                && first[len - 1].suffix.trim_end_matches(',').is_empty()
+                && second.iter().all(|word| word.prefix.is_empty())
+                // "hello new line hello" is not a repetition
+                && !first.iter().chain(&second[..len - 1]).any(DisfluencyWord::ends_line)
+                && !(len == 1 && table.valid_repetitions.contains(&first[0].normalized.as_str()));
+
+            if repeated {
+                // Keep the first occurrence, which carries the sentence's
+                // capitalisation, with the punctuation of the second
+                let DisfluencyWord {
+                    suffix, trailing, ..
+                } = words[i + 2 * len - 1].clone();
+                words[i + len - 1].suffix = suffix;
+                words[i + len - 1].trailing = trailing;
+                words.drain(i + len..i + 2 * len);
+            } else {
+                i += 1;
+            }
+        }
+    }
+
+    words
+}
+
+/// Removes filler words, immediate repetitions and self-corrections from
+/// dictated text, using the word lists for `language`
+pub fn remove_disfluencies(text: &str, language: &str, options: &DisfluencyOptions) -> String {
+    let table = disfluency_table(language);
+    let (leading, split) = split_words(text);
+    let mut words: Vec<DisfluencyWord> = split
+        .into_iter()
+        .map(|(word, trailing)| DisfluencyWord::new(word, trailing))
+        .collect();
+    let final_trailing = words.last().map(|word| word.trailing.clone());
+
+    if options.remove_self_corrections {
+        words = remove_self_corrections(words, table);
+    }
+    if options.remove_fillers {
+        words = remove_fillers(words, table, options.extra_fillers);
+    }
+    if options.remove_repetitions {
+        words = remove_repetitions(words, table);
+    }
+
+    // The text ends the way it did, whichever word is now last
+    if let (Some(last), Some(trailing)) = (words.last_mut(), final_trailing) {
+        last.trailing = trailing;
+    }
+
+    let mut output = leading.to_string();
+    for word in &words {
+        output.push_str(&word.to_text());
+        output.push_str(&word.trailing);
+    }
+    output
+}
+
+/// Upper-cases the first letter of the text and of every sentence after `.`,
+/// `!`, `?` or a line break, leaving the rest of each word untouched
+pub fn capitalize_sentences(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut at_sentence_start = true;
+    let mut after_terminator = false;
+
+    for c in text.chars() {
+        if c.is_alphanumeric() {
+            if at_sentence_start {
+                result.extend(c.to_uppercase());
+            } else {
+                result.push(c);
+            }
+            at_sentence_start = false;
+            after_terminator = false;
+            continue;
+        }
+
+        result.push(c);
+        if matches!(c, '.' | '!' | '?') {
+            after_terminator = true;
+        } else if c == '\n' || (c.is_whitespace() && after_terminator) {
+            at_sentence_start = true;
+        } else if !matches!(c, '"' | '\'' | ')' | '\u{201d}' | '\u{2019}') {
+            // A closing quote or bracket may follow the terminator; anything else
+            // (e.g. the dot in "3.5") means the sentence goes on
+            after_terminator = false;
+        }
+    }
+
+    result
+}
+
+/// Whether `text` ends a sentence, allowing closing quotes or brackets after
+/// the terminator
+fn ends_with_sentence_end(text: &str) -> bool {
+    let text = text.trim_end_matches(['"', '\'', ')', ']', '\u{201d}', '\u{2019}']);
+    text.ends_with(['.', '!', '?'])
+}
+
+/// Whether nothing should separate `last` from the text typed after it
+fn opens_group(last: char, preceding: &str) -> bool {
+    match last {
+        '(' | '[' | '{' | '\u{201c}' | '\u{2018}' | '/' | '@' | '#' => true,
+        // A straight quote opens when it isn't attached to a word
+        '"' | '\'' => {
+            let mut chars = preceding.chars().rev().skip(1);
+            chars.next().is_none_or(char::is_whitespace)
+        }
+        _ => false,
+    }
+}
+
+fn capitalize_first(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Lower-cases the first letter unless the first word looks like a name the
+/// engine would capitalise anyway: "I", acronyms and mixed-case words
+fn lowercase_first(text: &str) -> String {
+    let first_word = text.split_whitespace().next().unwrap_or("");
+    let word_core = first_word.trim_end_matches(|c: char| !c.is_alphanumeric());
+    let is_pronoun_i =
+        word_core == "I" || word_core.starts_with("I'") || word_core.starts_with("I\u{2019}");
+    let rest_is_lowercase = word_core.chars().skip(1).all(|c| !c.is_uppercase());
+    if is_pronoun_i || !rest_is_lowercase {
+        return text.to_string();
+    }
+
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_lowercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Adjusts dictated `text` to fit after `preceding`, the text just before the
+/// cursor. After a sentence end the text is capitalised; mid-sentence the
+/// first letter is lower-cased and a final full stop dropped. A space is added
+/// or removed so the words don't run together. With nothing before the cursor
+/// the text is left as it is.
+pub fn adapt_to_insertion_context(text: &str, preceding: &str) -> String {
+    let body = text.trim_start();
+    let last = match preceding.chars().next_back() {
+        Some(last) => last,
+        None => return text.to_string(),
+    };
+    if body.is_empty() {
+        return text.to_string();
+    }
+
+    let context = preceding.trim_end_matches([' ', '\t']);
+    if context.is_empty() || context.ends_with('\n') {
+        return capitalize_first(body);
+    }
+
+    let mut result = String::with_capacity(body.len() + 1);
+    let starts_with_punctuation = body.starts_with([',', '.', ';', ':', '!', '?', ')']);
+    if !last.is_whitespace() && !opens_group(last, preceding) && !starts_with_punctuation {
+        result.push(' ');
+    }
+
+    if ends_with_sentence_end(context) {
+        result.push_str(&capitalize_first(body));
+        return result;
+    }
+
+    let mut adapted = lowercase_first(body);
+    // Continuing a sentence, so a single closing full stop no longer fits
+    let content_len = adapted.trim_end().len();
+    let content = &adapted[..content_len];
+    if content.ends_with('.') && !content.ends_with("..") {
+        adapted.remove(content_len - 1);
+    }
+    result.push_str(&adapted);
+    result
+}
+
+mod inverse_normalization;
+
+pub use inverse_normalization::{
+    apply_inverse_normalization, EnglishNormalizer, InverseNormalizer,
+};
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -140,7 +1019,7 @@ mod tests {
     fn test_apply_custom_words_exact_match() {
         let text = "hello world";
         let custom_words = vec!["Hello".to_string(), "World".to_string()];
-        let result = apply_custom_words(text, &custom_words, 0.5);
+        let result = apply_custom_words(text, &custom_words, 0.5, PhoneticAlgorithm::Soundex);
         assert_eq!(result, "Hello World");
     }
 
@@ -148,10 +1027,105 @@ mod tests {
     fn test_apply_custom_words_fuzzy_match() {
         let text = "helo wrold";
         let custom_words = vec!["hello".to_string(), "world".to_string()];
-        let result = apply_custom_words(text, &custom_words, 0.5);
+        let result = apply_custom_words(text, &custom_words, 0.5, PhoneticAlgorithm::Soundex);
         assert_eq!(result, "hello world");
     }
 
+    #[test]
+    fn test_apply_custom_words_merges_split_name() {
+        let custom_words = vec!["Handy".to_string()];
+        assert_eq!(
+            apply_custom_words(
+                "I dictate with hand e every day",
+                &custom_words,
+                0.18,
+                PhoneticAlgorithm::Soundex
+            ),
+            "I dictate with Handy every day"
+        );
+        assert_eq!(
+            apply_custom_words("Hand e.", &custom_words, 0.18, PhoneticAlgorithm::Soundex),
+            "Handy."
+        );
+    }
+
+    #[test]
+    fn test_apply_custom_words_multi_word_entry() {
+        let custom_words = vec![
+            "Tauri specta".to_string(),
+            "Kubernetes operator".to_string(),
+        ];
+        assert_eq!(
+            apply_custom_words(
+                "we generate tory specta bindings",
+                &custom_words,
+                0.18,
+                PhoneticAlgorithm::Soundex
+            ),
+            "we generate Tauri specta bindings"
+        );
+        assert_eq!(
+            apply_custom_words(
+                "deploy the kubernetes operator now",
+                &custom_words,
+                0.18,
+                PhoneticAlgorithm::Soundex
+            ),
+            "deploy the Kubernetes operator now"
+        );
+    }
+
+    #[test]
+    fn test_apply_custom_words_splits_merged_phrase() {
+        let custom_words = vec!["Kubernetes operator".to_string()];
+        assert_eq!(
+            apply_custom_words(
+                "the Kubernetesoperator crashed",
+                &custom_words,
+                0.18,
+                PhoneticAlgorithm::Soundex
+            ),
+            "the Kubernetes operator crashed"
+        );
+    }
+
+    #[test]
+    fn test_apply_custom_words_does_not_join_across_punctuation() {
+        let custom_words = vec!["Handy".to_string()];
+        assert_eq!(
+            apply_custom_words(
+                "it was hand, e.g. a tool",
+                &custom_words,
+                0.18,
+                PhoneticAlgorithm::Soundex
+            ),
+            "it was Handy, e.g. a tool"
+        );
+    }
+
+    #[test]
+    fn test_apply_custom_words_uses_given_phonetic_algorithm() {
+        let custom_words = vec!["Schmitt".to_string()];
+        assert_eq!(
+            apply_custom_words(
+                "frau schmidt",
+                &custom_words,
+                0.1,
+                PhoneticAlgorithm::Cologne
+            ),
+            "frau Schmitt"
+        );
+        assert_eq!(
+            apply_custom_words(
+                "frau schmidt",
+                &custom_words,
+                0.1,
+                PhoneticAlgorithm::EditDistance
+            ),
+            "frau schmidt"
+        );
+    }
+
     #[test]
     fn test_preserve_case_pattern() {
         assert_eq!(preserve_case_pattern("HELLO", "world"), "WORLD");
@@ -170,7 +1144,280 @@ mod tests {
     fn test_empty_custom_words() {
         let text = "hello world";
         let custom_words = vec![];
-        let result = apply_custom_words(text, &custom_words, 0.5);
+        let result = apply_custom_words(text, &custom_words, 0.5, PhoneticAlgorithm::Soundex);
         assert_eq!(result, "hello world");
     }
+
+    #[test]
+    fn test_spoken_punctuation() {
+        assert_eq!(
+            apply_spoken_commands("hello comma how are you question mark", "en"),
+            "hello, how are you?"
+        );
+        assert_eq!(
+            apply_spoken_commands("done period next one full stop", "en"),
+            "done. Next one."
+        );
+    }
+
+    #[test]
+    fn test_spoken_punctuation_replaces_engine_punctuation() {
+        assert_eq!(
+            apply_spoken_commands("Hello, comma, world. Period.", "en"),
+            "Hello, world."
+        );
+    }
+
+    #[test]
+    fn test_spoken_line_breaks() {
+        assert_eq!(
+            apply_spoken_commands("dear team new paragraph thanks new line bob", "en"),
+            "dear team\n\nThanks\nbob"
+        );
+    }
+
+    #[test]
+    fn test_spoken_quotes_and_caps() {
+        assert_eq!(
+            apply_spoken_commands("she said open quote all caps stop close quote", "en"),
+            "she said \"STOP\""
+        );
+    }
+
+    #[test]
+    fn test_spoken_command_escape_word() {
+        assert_eq!(
+            apply_spoken_commands("a literal period of time", "en"),
+            "a period of time"
+        );
+        assert_eq!(
+            apply_spoken_commands("type literal new line here", "en"),
+            "type new line here"
+        );
+        // Without a command after it the escape word is an ordinary word
+        assert_eq!(
+            apply_spoken_commands("a literal translation comma please", "en"),
+            "a literal translation, please"
+        );
+        assert_eq!(
+            apply_spoken_commands("wörtlich übersetzt Punkt", "de"),
+            "wörtlich übersetzt."
+        );
+        assert_eq!(
+            apply_spoken_commands("take it literal", "en"),
+            "take it literal"
+        );
+    }
+
+    #[test]
+    fn test_spoken_commands_per_language() {
+        assert_eq!(
+            apply_spoken_commands("hallo Komma wie geht's Fragezeichen", "de"),
+            "hallo, wie geht's?"
+        );
+        assert_eq!(
+            apply_spoken_commands("vraiment point d'interrogation", "fr"),
+            "vraiment ?"
+        );
+        assert_eq!(
+            apply_spoken_commands("uno punto y coma dos", "es"),
+            "uno; dos"
+        );
+        // Unknown languages fall back to English commands
+        assert_eq!(apply_spoken_commands("yes comma no", "auto"), "yes, no");
+    }
+
+    const ALL_DISFLUENCIES: DisfluencyOptions = DisfluencyOptions {
+        remove_fillers: true,
+        remove_repetitions: true,
+        remove_self_corrections: true,
+        extra_fillers: &[],
+    };
+
+    #[test]
+    fn test_remove_fillers() {
+        let cases = [
+            ("Um, so we should ship it.", "So we should ship it."),
+            ("I, uh, think it works", "I think it works"),
+            ("it's, like, really fast", "it's really fast"),
+            ("I like pizza", "I like pizza"),
+            ("Like, what happened?", "What happened?"),
+            ("it works, you know.", "it works."),
+            ("so, you know", "so"),
+            ("do you know him", "do you know him"),
+            ("hmm.", ""),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(
+                remove_disfluencies(input, "en", &ALL_DISFLUENCIES),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn test_remove_repetitions() {
+        let cases = [
+            ("the the cat sat", "the cat sat"),
+            ("The the the cat", "The cat"),
+            ("I think, I think we're done.", "I think we're done."),
+            ("I know that that works", "I know that that works"),
+            ("we we, we tried", "we tried"),
+            ("one. One more", "one. One more"),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(
+                remove_disfluencies(input, "en", &ALL_DISFLUENCIES),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn test_remove_self_corrections() {
+        let cases = [
+            (
+                "Let's meet on Tuesday, sorry, on Wednesday.",
+                "Let's meet on Wednesday.",
+            ),
+            ("send it to John, I mean to Jane", "send it to Jane"),
+            ("the blue, I mean the red one works", "the red one works"),
+            ("What I mean is simple", "What I mean is simple"),
+            ("I'm sorry for that", "I'm sorry for that"),
+            // Nothing earlier for the repair to replace
+            (
+                "Thank you, sorry, for the delay",
+                "Thank you, sorry, for the delay",
+            ),
+            ("Call me, sorry, text me", "Call me, sorry, text me"),
+            (
+                "Let's meet Tuesday, sorry, Wednesday.",
+                "Let's meet Tuesday, sorry, Wednesday.",
+            ),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(
+                remove_disfluencies(input, "en", &ALL_DISFLUENCIES),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn test_disfluency_options() {
+        let text = "um the the meeting is Monday, sorry, Friday";
+        let fillers_only = DisfluencyOptions {
+            remove_repetitions: false,
+            remove_self_corrections: false,
+            ..ALL_DISFLUENCIES
+        };
+        assert_eq!(
+            remove_disfluencies(text, "en", &fillers_only),
+            "the the meeting is Monday, sorry, Friday"
+        );
+
+        let extra = vec!["basically".to_string()];
+        let with_extra = DisfluencyOptions {
+            extra_fillers: &extra,
+            ..ALL_DISFLUENCIES
+        };
+        assert_eq!(
+            remove_disfluencies("basically the meeting is Monday", "en", &with_extra),
+            "the meeting is Monday"
+        );
+    }
+
+    #[test]
+    fn test_disfluencies_per_language() {
+        assert_eq!(
+            remove_disfluencies(
+                "Ähm, ich ich glaube, die Sache klappt",
+                "de",
+                &ALL_DISFLUENCIES
+            ),
+            "Ich glaube, die Sache klappt"
+        );
+        assert_eq!(
+            remove_disfluencies("Ich kenne die, die kommen", "de", &ALL_DISFLUENCIES),
+            "Ich kenne die, die kommen"
+        );
+        assert_eq!(
+            remove_disfluencies(
+                "Euh, on se voit le lundi, pardon, le mardi",
+                "fr",
+                &ALL_DISFLUENCIES
+            ),
+            "On se voit le mardi"
+        );
+    }
+
+    #[test]
+    fn test_disfluency_removal_keeps_spoken_line_breaks() {
+        let text = apply_spoken_commands(
+            "um first point new line uh second point new paragraph the the end um new line hello new line hello",
+            "en",
+        );
+        assert_eq!(
+            remove_disfluencies(&text, "en", &ALL_DISFLUENCIES),
+            "first point\nsecond point\n\nThe end\nhello\nhello"
+        );
+    }
+
+    #[test]
+    fn test_capitalize_sentences() {
+        assert_eq!(
+            capitalize_sentences("hello there. how are you? fine!\nsee iPhone v3.5 docs"),
+            "Hello there. How are you? Fine!\nSee iPhone v3.5 docs"
+        );
+        assert_eq!(
+            capitalize_sentences("she said \"stop.\" then left"),
+            "She said \"stop.\" Then left"
+        );
+    }
+
+    #[test]
+    fn test_adapt_to_insertion_context_mid_sentence() {
+        assert_eq!(
+            adapt_to_insertion_context("Say hello.", "I was going to "),
+            "say hello"
+        );
+        assert_eq!(
+            adapt_to_insertion_context("Say hello. ", "I was going to"),
+            " say hello "
+        );
+        assert_eq!(
+            adapt_to_insertion_context("I think so.", "and, "),
+            "I think so"
+        );
+        assert_eq!(
+            adapt_to_insertion_context("NASA launched it...", "when "),
+            "NASA launched it..."
+        );
+        assert_eq!(
+            adapt_to_insertion_context("See above.", "notes ("),
+            "see above"
+        );
+        assert_eq!(adapt_to_insertion_context(", right?", "fine"), ", right?");
+    }
+
+    #[test]
+    fn test_adapt_to_insertion_context_sentence_start() {
+        assert_eq!(
+            adapt_to_insertion_context("next one.", "Done."),
+            " Next one."
+        );
+        assert_eq!(
+            adapt_to_insertion_context("next one.", "Done! "),
+            "Next one."
+        );
+        assert_eq!(
+            adapt_to_insertion_context("next one.", "He said \"stop.\""),
+            " Next one."
+        );
+        assert_eq!(
+            adapt_to_insertion_context(" next line.", "First line\n"),
+            "Next line."
+        );
+        assert_eq!(adapt_to_insertion_context("Hello.", ""), "Hello.");
+    }
 }
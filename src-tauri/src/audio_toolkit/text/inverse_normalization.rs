@@ -0,0 +1,994 @@
+//! Inverse text normalisation: rewriting spoken forms such as "twenty five
+//! dollars" into their written form, "$25".
+
+use super::split_words;
+
+/// Converts spoken-form expressions of one language into written form
+pub trait InverseNormalizer: Sync {
+    /// Rewrites the expression at the start of `words`, which are lowercase and
+    /// stripped of surrounding punctuation. Returns the written form and the
+    /// number of words it replaces, or `None` to keep the first word as is.
+    /// Returning the words themselves keeps them as spoken, in their original
+    /// case.
+    fn convert(&self, words: &[&str]) -> Option<(String, usize)>;
+}
+
+/// Normaliser for a language code, or `None` if the language isn't supported
+fn inverse_normalizer(language: &str) -> Option<&'static dyn InverseNormalizer> {
+    let primary = language
+        .split('-')
+        .next()
+        .unwrap_or(language)
+        .to_lowercase();
+    match primary.as_str() {
+        "en" => Some(&EnglishNormalizer),
+        _ => None,
+    }
+}
+
+/// A whitespace-separated word, split from its surrounding punctuation
+struct Token<'a> {
+    original: &'a str,
+    prefix: &'a str,
+    word: String,
+    suffix: &'a str,
+    /// What follows the token: its whitespace, which may hold dictated line
+    /// breaks, or "-" inside a hyphenated word such as "twenty-five"
+    trailing: &'a str,
+}
+
+fn tokenize(text: &str) -> (&str, Vec<Token<'_>>) {
+    let (leading, words) = split_words(text);
+    let mut tokens = Vec::new();
+
+    for (original, trailing) in words {
+        let core = original.trim_matches(|c: char| !c.is_alphanumeric());
+        if core.is_empty() {
+            tokens.push(Token {
+                original,
+                prefix: original,
+                word: String::new(),
+                suffix: "",
+                trailing,
+            });
+            continue;
+        }
+
+        let start = original.find(core).unwrap_or(0);
+        let prefix = &original[..start];
+        let mut suffix = &original[start + core.len()..];
+        let word = core.to_lowercase();
+
+        // The final dot of "a.m." belongs to the abbreviation
+        if (word == "a.m" || word == "p.m") && suffix.starts_with('.') {
+            suffix = &suffix[1..];
+        }
+
+        // Split "twenty-five" so its parts can be read as one number. The
+        // first and last parts keep the punctuation around the word, so it is
+        // written back when the number isn't converted.
+        let parts: Vec<&str> = core.split('-').collect();
+        if parts.len() > 1 && parts.iter().all(|part| is_english_number_word(part)) {
+            let last = parts.len() - 1;
+            let mut offset = start;
+            for (i, part) in parts.iter().enumerate() {
+                let from = if i == 0 { 0 } else { offset };
+                let to = if i == last {
+                    original.len()
+                } else {
+                    offset + part.len()
+                };
+                offset += part.len() + 1;
+                tokens.push(Token {
+                    original: &original[from..to],
+                    prefix: if i == 0 { prefix } else { "" },
+                    word: part.to_lowercase(),
+                    suffix: if i == last { suffix } else { "" },
+                    trailing: if i == last { trailing } else { "-" },
+                });
+            }
+            continue;
+        }
+
+        tokens.push(Token {
+            original,
+            prefix,
+            word,
+            suffix,
+            trailing,
+        });
+    }
+
+    (leading, tokens)
+}
+
+/// Converts spoken numbers, dates, times, currency, percentages, phone numbers
+/// and units into written form, e.g. "March third at three thirty pm" becomes
+/// "March 3 at 3:30 PM". Text in unsupported languages is returned unchanged.
+pub fn apply_inverse_normalization(text: &str, language: &str) -> String {
+    let Some(normalizer) = inverse_normalizer(language) else {
+        return text.to_string();
+    };
+
+    let (leading, tokens) = tokenize(text);
+    let mut output = leading.to_string();
+
+    let mut i = 0;
+    while i < tokens.len() {
+        // Expressions can't span punctuation or line breaks, so only look as
+        // far as the next word with either around it
+        let mut end = i + 1;
+        while end < tokens.len()
+            && tokens[end - 1].suffix.is_empty()
+            && !tokens[end - 1].trailing.contains('\n')
+            && tokens[end].prefix.is_empty()
+            && !tokens[end].word.is_empty()
+        {
+            end += 1;
+        }
+        let words: Vec<&str> = tokens[i..end]
+            .iter()
+            .map(|token| token.word.as_str())
+            .collect();
+
+        let converted = if words[0].is_empty() {
+            None
+        } else {
+            normalizer.convert(&words)
+        };
+
+        match converted {
+            Some((written, len)) if written != words[..len].join(" ") => {
+                let last = &tokens[i + len - 1];
+                output.push_str(tokens[i].prefix);
+                output.push_str(&written);
+                output.push_str(last.suffix);
+                output.push_str(last.trailing);
+                i += len;
+            }
+            unchanged => {
+                let len = unchanged.map_or(1, |(_, len)| len);
+                for token in &tokens[i..i + len] {
+                    output.push_str(token.original);
+                    output.push_str(token.trailing);
+                }
+                i += len;
+            }
+        }
+    }
+
+    output
+}
+
+/// Inverse normalisation for English
+pub struct EnglishNormalizer;
+
+impl InverseNormalizer for EnglishNormalizer {
+    fn convert(&self, words: &[&str]) -> Option<(String, usize)> {
+        convert_month_date(words)
+            .or_else(|| convert_day_first_date(words))
+            .or_else(|| convert_digit_sequence(words))
+            .or_else(|| convert_number_phrase(words))
+    }
+}
+
+const UNITS: &[&str] = &[
+    "zero",
+    "one",
+    "two",
+    "three",
+    "four",
+    "five",
+    "six",
+    "seven",
+    "eight",
+    "nine",
+    "ten",
+    "eleven",
+    "twelve",
+    "thirteen",
+    "fourteen",
+    "fifteen",
+    "sixteen",
+    "seventeen",
+    "eighteen",
+    "nineteen",
+];
+
+const TENS: &[(&str, u64)] = &[
+    ("twenty", 20),
+    ("thirty", 30),
+    ("forty", 40),
+    ("fifty", 50),
+    ("sixty", 60),
+    ("seventy", 70),
+    ("eighty", 80),
+    ("ninety", 90),
+];
+
+const SCALES: &[(&str, u64)] = &[
+    ("thousand", 1_000),
+    ("million", 1_000_000),
+    ("billion", 1_000_000_000),
+    ("trillion", 1_000_000_000_000),
+];
+
+const UNIT_ORDINALS: &[(&str, u64)] = &[
+    ("first", 1),
+    ("second", 2),
+    ("third", 3),
+    ("fourth", 4),
+    ("fifth", 5),
+    ("sixth", 6),
+    ("seventh", 7),
+    ("eighth", 8),
+    ("ninth", 9),
+    ("tenth", 10),
+    ("eleventh", 11),
+    ("twelfth", 12),
+    ("thirteenth", 13),
+    ("fourteenth", 14),
+    ("fifteenth", 15),
+    ("sixteenth", 16),
+    ("seventeenth", 17),
+    ("eighteenth", 18),
+    ("nineteenth", 19),
+];
+
+const TENS_ORDINALS: &[(&str, u64)] = &[
+    ("twentieth", 20),
+    ("thirtieth", 30),
+    ("fortieth", 40),
+    ("fiftieth", 50),
+    ("sixtieth", 60),
+    ("seventieth", 70),
+    ("eightieth", 80),
+    ("ninetieth", 90),
+];
+
+const MONTHS: &[&str] = &[
+    "january",
+    "february",
+    "march",
+    "april",
+    "may",
+    "june",
+    "july",
+    "august",
+    "september",
+    "october",
+    "november",
+    "december",
+];
+
+const CURRENCIES: &[(&str, &str)] = &[
+    ("dollars", "$"),
+    ("dollar", "$"),
+    ("bucks", "$"),
+    ("euros", "€"),
+    ("euro", "€"),
+    ("yen", "¥"),
+];
+
+/// Units written after the number, longest phrases first. `true` attaches the
+/// symbol to the number without a space.
+const MEASUREMENT_UNITS: &[(&[&str], &str, bool)] = &[
+    (&["miles", "per", "hour"], "mph", false),
+    (&["kilometers", "per", "hour"], "km/h", false),
+    (&["kilometres", "per", "hour"], "km/h", false),
+    (&["degrees", "celsius"], "°C", true),
+    (&["degrees", "fahrenheit"], "°F", true),
+    (&["degrees"], "°", true),
+    (&["degree"], "°", true),
+    (&["kilometers"], "km", false),
+    (&["kilometer"], "km", false),
+    (&["kilometres"], "km", false),
+    (&["kilometre"], "km", false),
+    (&["meters"], "m", false),
+    (&["meter"], "m", false),
+    (&["metres"], "m", false),
+    (&["metre"], "m", false),
+    (&["centimeters"], "cm", false),
+    (&["centimeter"], "cm", false),
+    (&["centimetres"], "cm", false),
+    (&["millimeters"], "mm", false),
+    (&["millimetres"], "mm", false),
+    (&["kilograms"], "kg", false),
+    (&["kilogram"], "kg", false),
+    (&["kilos"], "kg", false),
+    (&["grams"], "g", false),
+    (&["gram"], "g", false),
+    (&["milligrams"], "mg", false),
+    (&["liters"], "L", false),
+    (&["liter"], "L", false),
+    (&["litres"], "L", false),
+    (&["litre"], "L", false),
+    (&["milliliters"], "mL", false),
+    (&["millilitres"], "mL", false),
+    (&["kilobytes"], "KB", false),
+    (&["megabytes"], "MB", false),
+    (&["gigabytes"], "GB", false),
+    (&["terabytes"], "TB", false),
+    (&["feet"], "ft", false),
+    (&["foot"], "ft", false),
+];
+
+fn unit_value(word: &str) -> Option<u64> {
+    UNITS
+        .iter()
+        .position(|unit| *unit == word)
+        .map(|v| v as u64)
+}
+
+fn lookup(table: &[(&str, u64)], word: &str) -> Option<u64> {
+    table
+        .iter()
+        .find(|(name, _)| *name == word)
+        .map(|(_, value)| *value)
+}
+
+fn is_english_number_word(word: &str) -> bool {
+    let word = word.to_lowercase();
+    unit_value(&word).is_some()
+        || lookup(TENS, &word).is_some()
+        || lookup(UNIT_ORDINALS, &word).is_some()
+        || lookup(TENS_ORDINALS, &word).is_some()
+}
+
+/// A single spoken digit, as read out in phone numbers and decimals
+fn digit_value(word: &str) -> Option<u64> {
+    match word {
+        "oh" | "o" => Some(0),
+        _ => unit_value(word).filter(|v| *v < 10),
+    }
+}
+
+/// What the previous word of a number was, to reject sequences like
+/// "one two" that are not a single number
+#[derive(Clone, Copy, PartialEq)]
+enum NumberPart {
+    Start,
+    Unit,
+    Teen,
+    Tens,
+    Hundred,
+    Scale,
+}
+
+struct SpokenNumber {
+    value: u64,
+    /// Digits after "point", kept as written so "point zero five" stays "05"
+    fraction: Option<String>,
+    ordinal: bool,
+    /// Number of words making up the number
+    len: usize,
+}
+
+impl SpokenNumber {
+    fn is_integer(&self) -> bool {
+        self.fraction.is_none() && !self.ordinal
+    }
+
+    fn format(&self) -> String {
+        let integer = format_integer(self.value);
+        match (&self.fraction, self.ordinal) {
+            (Some(fraction), _) => format!("{}.{}", integer, fraction),
+            (None, true) => format!("{}{}", integer, ordinal_suffix(self.value)),
+            (None, false) => integer,
+        }
+    }
+}
+
+/// Parses a cardinal or ordinal number, with an optional decimal part, from
+/// the start of `words`
+fn parse_number(words: &[&str]) -> Option<SpokenNumber> {
+    let mut total = 0u64;
+    let mut current = 0u64;
+    let mut last = NumberPart::Start;
+    let mut previous_scale = u64::MAX;
+    let mut ordinal = false;
+    let mut len = 0;
+    let mut i = 0;
+
+    while i < words.len() {
+        let word = words[i];
+        let next = words.get(i + 1).copied().unwrap_or("");
+
+        // Digits already written out are only taken on their own, so "25
+        // dollars" still gets its currency symbol
+        if let Ok(value) = word.parse::<u64>() {
+            if last == NumberPart::Start {
+                current = value;
+                len = 1;
+            }
+            break;
+        }
+
+        if word == "a" && last == NumberPart::Start {
+            if next == "hundred" || lookup(SCALES, next).is_some() {
+                current = 1;
+                last = NumberPart::Unit;
+                i += 1;
+                continue;
+            }
+            break;
+        }
+
+        if word == "and" && matches!(last, NumberPart::Hundred | NumberPart::Scale) {
+            if unit_value(next).is_some()
+                || lookup(TENS, next).is_some()
+                || lookup(UNIT_ORDINALS, next).is_some()
+                || lookup(TENS_ORDINALS, next).is_some()
+            {
+                i += 1;
+                continue;
+            }
+            break;
+        }
+
+        let unit = unit_value(word).map(|v| (v, false));
+        let unit = unit.or_else(|| lookup(UNIT_ORDINALS, word).map(|v| (v, true)));
+        if let Some((value, is_ordinal)) = unit {
+            let allowed = match last {
+                NumberPart::Start | NumberPart::Hundred | NumberPart::Scale => true,
+                NumberPart::Tens => value < 10,
+                NumberPart::Unit | NumberPart::Teen => false,
+            };
+            if !allowed {
+                break;
+            }
+            current += value;
+            last = if value < 10 {
+                NumberPart::Unit
+            } else {
+                NumberPart::Teen
+            };
+            len = i + 1;
+            if is_ordinal {
+                ordinal = true;
+                break;
+            }
+            i += 1;
+            continue;
+        }
+
+        let tens = lookup(TENS, word).map(|v| (v, false));
+        let tens = tens.or_else(|| lookup(TENS_ORDINALS, word).map(|v| (v, true)));
+        if let Some((value, is_ordinal)) = tens {
+            if !matches!(
+                last,
+                NumberPart::Start | NumberPart::Hundred | NumberPart::Scale
+            ) {
+                break;
+            }
+            current += value;
+            last = NumberPart::Tens;
+            len = i + 1;
+            if is_ordinal {
+                ordinal = true;
+                break;
+            }
+            i += 1;
+            continue;
+        }
+
+        if word == "hundred" || word == "hundredth" {
+            if !matches!(last, NumberPart::Unit | NumberPart::Teen) || current >= 100 {
+                break;
+            }
+            current *= 100;
+            last = NumberPart::Hundred;
+            len = i + 1;
+            if word == "hundredth" {
+                ordinal = true;
+                break;
+            }
+            i += 1;
+            continue;
+        }
+
+        let scale = lookup(SCALES, word).map(|v| (v, false)).or_else(|| {
+            word.strip_suffix("th")
+                .and_then(|base| lookup(SCALES, base))
+                .map(|v| (v, true))
+        });
+        if let Some((value, is_ordinal)) = scale {
+            // "two million five hundred thousand", but not "thousand million"
+            if current == 0 || value >= previous_scale {
+                break;
+            }
+            total += current * value;
+            current = 0;
+            previous_scale = value;
+            last = NumberPart::Scale;
+            len = i + 1;
+            if is_ordinal {
+                ordinal = true;
+                break;
+            }
+            i += 1;
+            continue;
+        }
+
+        break;
+    }
+
+    // A decimal needs its integer part, so "my point one" stays as spoken
+    if len == 0 {
+        return None;
+    }
+
+    let mut fraction = None;
+    if !ordinal && words.get(len) == Some(&"point") {
+        let digits: String = words[len + 1..]
+            .iter()
+            .map_while(|word| digit_value(word))
+            .map(|digit| char::from_digit(digit as u32, 10).unwrap_or('0'))
+            .collect();
+        if !digits.is_empty() {
+            len += 1 + digits.len();
+            fraction = Some(digits);
+        }
+    }
+
+    Some(SpokenNumber {
+        value: total + current,
+        fraction,
+        ordinal,
+        len,
+    })
+}
+
+/// Writes large numbers with thousands separators, leaving years like 2025
+/// and other four-digit numbers alone
+fn format_integer(value: u64) -> String {
+    if value < 10_000 {
+        return value.to_string();
+    }
+
+    let digits: Vec<char> = value.to_string().chars().collect();
+    let groups: Vec<String> = digits
+        .rchunks(3)
+        .rev()
+        .map(|group| group.iter().collect())
+        .collect();
+    groups.join(",")
+}
+
+fn ordinal_suffix(value: u64) -> &'static str {
+    match (value % 10, value % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    }
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Parses a year such as "twenty twenty five", "nineteen ninety nine",
+/// "twenty oh five" or "two thousand and five"
+fn parse_year(words: &[&str]) -> Option<(u64, usize)> {
+    let first = parse_number(words)?;
+    if !first.is_integer() {
+        return None;
+    }
+    if (1000..=2999).contains(&first.value) {
+        return Some((first.value, first.len));
+    }
+    if !(10..=99).contains(&first.value) {
+        return None;
+    }
+
+    let rest = &words[first.len..];
+    if rest.first() == Some(&"oh") {
+        let digit = rest.get(1).and_then(|word| digit_value(word))?;
+        return Some((first.value * 100 + digit, first.len + 2));
+    }
+    let second = parse_number(rest)?;
+    if second.is_integer() && (10..=99).contains(&second.value) {
+        return Some((first.value * 100 + second.value, first.len + second.len));
+    }
+    None
+}
+
+/// Parses a day of the month, optionally preceded by "the"
+fn parse_day(words: &[&str], ordinal_only: bool) -> Option<(u64, usize)> {
+    let skip = usize::from(words.first() == Some(&"the"));
+    let day = parse_number(&words[skip..])?;
+    let valid = day.fraction.is_none()
+        && (1..=31).contains(&day.value)
+        && (day.ordinal || (!ordinal_only && skip == 0));
+    valid.then_some((day.value, skip + day.len))
+}
+
+/// "March third twenty twenty five" -> "March 3, 2025"
+fn convert_month_date(words: &[&str]) -> Option<(String, usize)> {
+    let month = *MONTHS.iter().find(|month| **month == words[0])?;
+    // "may" is usually the verb, so only take it as a month before an ordinal
+    let (day, day_len) = parse_day(&words[1..], month == "may")?;
+    let len = 1 + day_len;
+    Some(format_date(month, day, &words[len..], len))
+}
+
+/// "the third of march" -> "March 3"
+fn convert_day_first_date(words: &[&str]) -> Option<(String, usize)> {
+    let (day, day_len) = parse_day(words, true)?;
+    if words.get(day_len) != Some(&"of") {
+        return None;
+    }
+    let month = *MONTHS
+        .iter()
+        .find(|month| Some(*month) == words.get(day_len + 1))?;
+    let len = day_len + 2;
+    Some(format_date(month, day, &words[len..], len))
+}
+
+fn format_date(month: &str, day: u64, rest: &[&str], len: usize) -> (String, usize) {
+    match parse_year(rest) {
+        Some((year, year_len)) => (
+            format!("{} {}, {}", capitalize(month), day, year),
+            len + year_len,
+        ),
+        None => (format!("{} {}", capitalize(month), day), len),
+    }
+}
+
+/// Seven or more digits read out one by one, as in phone numbers
+fn convert_digit_sequence(words: &[&str]) -> Option<(String, usize)> {
+    if words[0] == "oh" || words[0] == "o" {
+        return None;
+    }
+    let digits: String = words
+        .iter()
+        .map_while(|word| digit_value(word))
+        .map(|digit| char::from_digit(digit as u32, 10).unwrap_or('0'))
+        .collect();
+    if digits.len() < 7 {
+        return None;
+    }
+
+    let grouped = match digits.len() {
+        7 => format!("{}-{}", &digits[..3], &digits[3..]),
+        10 => format!("{}-{}-{}", &digits[..3], &digits[3..6], &digits[6..]),
+        11 if digits.starts_with('1') => {
+            format!("1-{}-{}-{}", &digits[1..4], &digits[4..7], &digits[7..])
+        }
+        _ => digits.clone(),
+    };
+    Some((grouped, digits.len()))
+}
+
+/// "pm", "p.m." or "p m"
+fn parse_meridiem(words: &[&str]) -> Option<(&'static str, usize)> {
+    match words {
+        ["am" | "a.m", ..] => Some(("AM", 1)),
+        ["pm" | "p.m", ..] => Some(("PM", 1)),
+        ["a", "m", ..] => Some(("AM", 2)),
+        ["p", "m", ..] => Some(("PM", 2)),
+        _ => None,
+    }
+}
+
+/// Times after an hour: "three pm", "three o'clock", "three thirty pm",
+/// "ten oh five am". Minutes without am/pm are too ambiguous to convert.
+fn convert_time(hour: u64, rest: &[&str]) -> Option<(String, usize)> {
+    if !(1..=12).contains(&hour) {
+        return None;
+    }
+
+    if let Some((meridiem, len)) = parse_meridiem(rest) {
+        return Some((format!("{} {}", hour, meridiem), len));
+    }
+
+    if matches!(rest.first(), Some(&"o'clock") | Some(&"o’clock")) {
+        return match parse_meridiem(&rest[1..]) {
+            Some((meridiem, len)) => Some((format!("{}:00 {}", hour, meridiem), 1 + len)),
+            None => Some((format!("{}:00", hour), 1)),
+        };
+    }
+
+    let (minutes, minutes_len) = if rest.first() == Some(&"oh") {
+        // Only a single digit follows "oh", as in "ten oh five"
+        let digit = rest
+            .get(1)
+            .and_then(|word| unit_value(word))
+            .filter(|&digit| digit < 10)?;
+        (digit, 2)
+    } else {
+        let minutes = parse_number(rest)?;
+        if !minutes.is_integer() || !(10..=59).contains(&minutes.value) {
+            return None;
+        }
+        (minutes.value, minutes.len)
+    };
+    if minutes >= 60 {
+        return None;
+    }
+
+    let (meridiem, meridiem_len) = parse_meridiem(&rest[minutes_len..])?;
+    Some((
+        format!("{}:{:02} {}", hour, minutes, meridiem),
+        minutes_len + meridiem_len,
+    ))
+}
+
+/// "and fifty cents" or "fifty cents" after a currency amount
+fn parse_cents(words: &[&str]) -> Option<(u64, usize)> {
+    let skip = usize::from(words.first() == Some(&"and"));
+    let cents = parse_number(&words[skip..])?;
+    let valid = cents.is_integer()
+        && cents.value < 100
+        && matches!(words.get(skip + cents.len), Some(&"cents") | Some(&"cent"));
+    valid.then_some((cents.value, skip + cents.len + 1))
+}
+
+fn convert_number_phrase(words: &[&str]) -> Option<(String, usize)> {
+    let negative = matches!(words[0], "minus" | "negative");
+    let start = usize::from(negative);
+    let number = parse_number(&words[start..])?;
+    let len = start + number.len;
+    let rest = &words[len..];
+    let sign = if negative { "-" } else { "" };
+
+    if number.is_integer() && !negative {
+        if let Some((time, time_len)) = convert_time(number.value, rest) {
+            return Some((time, len + time_len));
+        }
+    }
+
+    // A year read in pairs, "twenty twenty five"
+    if number.is_integer() && !negative {
+        if let Some((year, year_len)) = parse_year(words) {
+            if year_len > number.len && (1900..=2099).contains(&year) {
+                return Some((year.to_string(), year_len));
+            }
+        }
+    }
+
+    let written = format!("{}{}", sign, number.format());
+
+    if rest.first() == Some(&"percent") {
+        return Some((format!("{}%", written), len + 1));
+    }
+
+    if !number.ordinal {
+        if let Some((_, symbol)) = CURRENCIES
+            .iter()
+            .find(|(name, _)| Some(name) == rest.first())
+        {
+            if number.fraction.is_none() {
+                if let Some((cents, cents_len)) = parse_cents(&rest[1..]) {
+                    return Some((
+                        format!(
+                            "{}{}{}.{:02}",
+                            sign,
+                            symbol,
+                            format_integer(number.value),
+                            cents
+                        ),
+                        len + 1 + cents_len,
+                    ));
+                }
+            }
+            return Some((format!("{}{}{}", sign, symbol, number.format()), len + 1));
+        }
+
+        for (phrase, symbol, attached) in MEASUREMENT_UNITS {
+            if rest.starts_with(phrase) {
+                let separator = if *attached { "" } else { " " };
+                return Some((
+                    format!("{}{}{}", written, separator, symbol),
+                    len + phrase.len(),
+                ));
+            }
+        }
+    }
+
+    // Numbers following each other ("three thirty", "one two") are ambiguous,
+    // so keep the whole run as spoken
+    if parse_number(rest).is_some() {
+        let mut run_len = len;
+        while let Some(next) = parse_number(&words[run_len..]) {
+            run_len += next.len;
+        }
+        return Some((words[..run_len].join(" "), run_len));
+    }
+
+    // Numbers under ten read better as words ("one of them", "the first time")
+    if number.len == 1 && number.value < 10 && number.fraction.is_none() && !negative {
+        return None;
+    }
+
+    Some((written, len))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(cases: &[(&str, &str)]) {
+        for (input, expected) in cases {
+            assert_eq!(
+                apply_inverse_normalization(input, "en"),
+                *expected,
+                "input: {}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn test_cardinals() {
+        check(&[
+            ("twenty five", "25"),
+            ("twenty-five people", "25 people"),
+            ("one hundred and five", "105"),
+            ("a hundred", "100"),
+            ("three thousand two hundred", "3200"),
+            ("two million five hundred thousand", "2,500,000"),
+            ("forty two thousand", "42,000"),
+            ("minus fifteen", "-15"),
+            ("eleven", "11"),
+        ]);
+    }
+
+    #[test]
+    fn test_small_numbers_stay_words() {
+        check(&[
+            ("one of them", "one of them"),
+            ("I have two cats", "I have two cats"),
+            ("the first time", "the first time"),
+            ("give me a second", "give me a second"),
+        ]);
+    }
+
+    #[test]
+    fn test_separate_numbers_are_not_joined() {
+        check(&[
+            ("one two", "one two"),
+            ("Fifteen twenty", "Fifteen twenty"),
+            ("forty-two fifty", "forty-two fifty"),
+        ]);
+    }
+
+    #[test]
+    fn test_ordinals() {
+        check(&[
+            ("twenty first", "21st"),
+            ("the twenty second floor", "the 22nd floor"),
+            ("tenth", "10th"),
+            ("one hundredth", "100th"),
+            ("eleventh", "11th"),
+            ("forty third", "43rd"),
+        ]);
+    }
+
+    #[test]
+    fn test_decimals_and_percentages() {
+        check(&[
+            ("three point one four", "3.14"),
+            ("point five", "point five"),
+            ("my point one more time", "my point one more time"),
+            ("zero point zero five", "0.05"),
+            ("twenty five percent", "25%"),
+            ("five percent", "5%"),
+            ("two point five percent", "2.5%"),
+            ("minus three point five", "-3.5"),
+        ]);
+    }
+
+    #[test]
+    fn test_currency() {
+        check(&[
+            ("twenty five dollars", "$25"),
+            ("25 dollars", "$25"),
+            ("one dollar", "$1"),
+            ("twenty five dollars and fifty cents", "$25.50"),
+            ("five dollars five cents", "$5.05"),
+            ("a million dollars", "$1,000,000"),
+            ("ten euros", "€10"),
+            ("two point five dollars", "$2.5"),
+        ]);
+    }
+
+    #[test]
+    fn test_times() {
+        check(&[
+            ("three thirty pm", "3:30 PM"),
+            ("at ten oh five a.m. today", "at 10:05 AM today"),
+            ("seven am", "7 AM"),
+            ("twelve fifteen p m", "12:15 PM"),
+            ("three o'clock", "3:00"),
+            ("nine o'clock pm", "9:00 PM"),
+            ("three thirty", "three thirty"),
+            // Not a time: only a single digit follows "oh"
+            ("ten oh fifteen pm", "10 oh 15 pm"),
+        ]);
+    }
+
+    #[test]
+    fn test_dates() {
+        check(&[
+            ("March third", "March 3"),
+            ("on march thirty first", "on March 31"),
+            ("june the fifth", "June 5"),
+            ("July fourth twenty twenty five", "July 4, 2025"),
+            ("december twelve nineteen ninety nine", "December 12, 1999"),
+            ("the third of march", "March 3"),
+            ("on the fourth of July twenty twenty", "on July 4, 2020"),
+            ("may fourth two thousand and five", "May 4, 2005"),
+            ("may fifth twenty oh five", "May 5, 2005"),
+            ("you may two", "you may two"),
+        ]);
+    }
+
+    #[test]
+    fn test_years() {
+        check(&[
+            ("in twenty twenty five", "in 2025"),
+            ("nineteen eighty four", "1984"),
+            ("two thousand and five", "2005"),
+            ("twenty oh five", "2005"),
+        ]);
+    }
+
+    #[test]
+    fn test_phone_numbers() {
+        check(&[
+            ("five five five one two three four", "555-1234"),
+            (
+                "call five five five one two three four five six seven",
+                "call 555-123-4567",
+            ),
+            ("one eight hundred", "one eight hundred"),
+            ("nine oh two one oh one two", "902-1012"),
+        ]);
+    }
+
+    #[test]
+    fn test_units() {
+        check(&[
+            ("five kilometers", "5 km"),
+            ("sixty miles per hour", "60 mph"),
+            ("twenty degrees celsius", "20°C"),
+            ("ninety degrees", "90°"),
+            ("sixteen gigabytes", "16 GB"),
+            ("two point five kilograms", "2.5 kg"),
+        ]);
+    }
+
+    #[test]
+    fn test_full_sentence_and_punctuation() {
+        check(&[
+            (
+                "Twenty five dollars on March third at three thirty pm.",
+                "$25 on March 3 at 3:30 PM.",
+            ),
+            (
+                "It costs twenty, maybe thirty dollars.",
+                "It costs 20, maybe $30.",
+            ),
+            ("(fifty percent)", "(50%)"),
+            ("\"one-two\"", "\"one-two\""),
+            ("(one-third)", "(one-third)"),
+            ("(forty-two fifty)", "(forty-two fifty)"),
+            ("(twenty-five dollars)", "($25)"),
+        ]);
+    }
+
+    #[test]
+    fn test_unsupported_language_is_unchanged() {
+        assert_eq!(
+            apply_inverse_normalization("vingt cinq dollars", "fr"),
+            "vingt cinq dollars"
+        );
+    }
+}
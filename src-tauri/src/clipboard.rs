@@ -1,7 +1,8 @@
+use crate::audio_toolkit::adapt_to_insertion_context;
 use crate::input::{self, EnigoState};
 use crate::settings::{get_settings, ClipboardHandling, PasteMethod};
 use enigo::Enigo;
-use log::info;
+use log::{debug, info, warn};
 use tauri::{AppHandle, Manager};
 use tauri_plugin_clipboard_manager::ClipboardExt;
 
@@ -54,6 +55,118 @@ fn paste_via_clipboard(
     Ok(())
 }
 
+/// Characters read before the cursor, enough for a terminator followed by a
+/// closing quote, bracket and space
+const CONTEXT_PROBE_CHARS: usize = 4;
+/// How often and how long the probe waits for the copied text to arrive
+const CONTEXT_PROBE_POLLS: usize = 5;
+const CONTEXT_PROBE_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(30);
+
+/// Reads the characters just before the cursor by selecting them with Shift+Left
+/// and copying, then restores the cursor and clipboard. Returns `None` when the
+/// probe can't tell, e.g. at the start of a field or in apps that copy more than
+/// the selection.
+fn read_text_before_cursor(enigo: &mut Enigo, app_handle: &AppHandle) -> Option<String> {
+    #[cfg(target_os = "linux")]
+    if is_wayland() {
+        debug!("Skipping insertion context probe on Wayland");
+        return None;
+    }
+
+    let clipboard = app_handle.clipboard();
+    let clipboard_content = clipboard.read_text().unwrap_or_default();
+
+    // Clear the clipboard so that copying an empty selection is detectable
+    if let Err(e) = clipboard.write_text("") {
+        warn!("Failed to clear clipboard for context probe: {}", e);
+        return None;
+    }
+
+    let probe = input::send_select_left(enigo, CONTEXT_PROBE_CHARS)
+        .and_then(|()| {
+            std::thread::sleep(std::time::Duration::from_millis(30));
+            input::send_copy_ctrl_c(enigo)
+        })
+        .map(|()| {
+            // Some apps fill the clipboard slowly, so only give up on an
+            // empty copy after a few tries
+            let mut copied = String::new();
+            for _ in 0..CONTEXT_PROBE_POLLS {
+                std::thread::sleep(CONTEXT_PROBE_POLL_INTERVAL);
+                copied = clipboard.read_text().unwrap_or_default();
+                if !copied.is_empty() {
+                    break;
+                }
+            }
+            copied
+        });
+
+    // An empty copy means there was nothing to select, e.g. at the start of a
+    // field. Otherwise a selection may still be active, whatever came back,
+    // and the paste would type over it.
+    if !matches!(&probe, Ok(copied) if copied.is_empty()) {
+        if let Err(e) = input::send_collapse_selection_right(enigo) {
+            warn!("Failed to restore cursor after context probe: {}", e);
+        }
+    }
+
+    let preceding = match probe {
+        Ok(copied) if !copied.is_empty() && copied.chars().count() <= CONTEXT_PROBE_CHARS => {
+            Some(copied)
+        }
+        Ok(_) => None,
+        Err(e) => {
+            warn!("Insertion context probe failed: {}", e);
+            None
+        }
+    };
+
+    if let Err(e) = clipboard.write_text(&clipboard_content) {
+        warn!("Failed to restore clipboard after context probe: {}", e);
+    }
+
+    debug!("Text before cursor: {:?}", preceding);
+    preceding
+}
+
+/// Copies the focused app's selection and returns it, leaving the clipboard as
+/// it was. Returns `None` when nothing is selected.
+pub fn read_selected_text(app_handle: &AppHandle) -> Result<Option<String>, String> {
+    #[cfg(target_os = "linux")]
+    if is_wayland() {
+        return Err("Reading the selection is not supported on Wayland".to_string());
+    }
+
+    let enigo_state = app_handle
+        .try_state::<EnigoState>()
+        .ok_or("Enigo state not initialized")?;
+    let mut enigo = enigo_state
+        .0
+        .lock()
+        .map_err(|e| format!("Failed to lock Enigo: {}", e))?;
+
+    let clipboard = app_handle.clipboard();
+    let clipboard_content = clipboard.read_text().unwrap_or_default();
+
+    // Clear the clipboard so that copying an empty selection is detectable
+    clipboard
+        .write_text("")
+        .map_err(|e| format!("Failed to clear clipboard: {}", e))?;
+
+    let copied = input::send_copy_ctrl_c(&mut enigo).map(|()| {
+        std::thread::sleep(std::time::Duration::from_millis(100));
+        clipboard.read_text().unwrap_or_default()
+    });
+
+    if let Err(e) = clipboard.write_text(&clipboard_content) {
+        warn!("Failed to restore clipboard after reading selection: {}", e);
+    }
+
+    let copied = copied?;
+    debug!("Selected text: {} chars", copied.chars().count());
+    Ok((!copied.is_empty()).then_some(copied))
+}
+
 /// Attempts to send a key combination using Linux-native tools.
 /// Returns `Ok(true)` if a native tool handled it, `Ok(false)` to fall back to enigo.
 #[cfg(target_os = "linux")]
@@ -288,17 +401,66 @@ fn paste_direct(enigo: &mut Enigo, text: &str) -> Result<(), String> {
     input::paste_text_direct(enigo, text)
 }
 
+/// Types text into the focused app with the direct typing path, regardless of
+/// the paste method. Used for text that arrives piece by piece.
+pub fn type_text(text: &str, app_handle: &AppHandle) -> Result<(), String> {
+    let enigo_state = app_handle
+        .try_state::<EnigoState>()
+        .ok_or("Enigo state not initialized")?;
+    let mut enigo = enigo_state
+        .0
+        .lock()
+        .map_err(|e| format!("Failed to lock Enigo: {}", e))?;
+
+    paste_direct(&mut enigo, text)
+}
+
+/// Replaces `typed`, just typed before the cursor, with `text` by selecting it
+/// with Shift+Left and pasting over it
+pub fn replace_typed_text(typed: &str, text: &str, app_handle: &AppHandle) -> Result<(), String> {
+    {
+        let enigo_state = app_handle
+            .try_state::<EnigoState>()
+            .ok_or("Enigo state not initialized")?;
+        let mut enigo = enigo_state
+            .0
+            .lock()
+            .map_err(|e| format!("Failed to lock Enigo: {}", e))?;
+        input::send_select_left(&mut enigo, typed.chars().count())?;
+    }
+
+    paste_over_selection(text, app_handle)
+}
+
+/// Pastes text over the focused app's selection. Unlike `paste`, the text is
+/// not fitted to its surroundings and the clipboard is always left as it was.
+pub fn paste_over_selection(text: &str, app_handle: &AppHandle) -> Result<(), String> {
+    let paste_method = get_settings(app_handle).paste_method;
+
+    let enigo_state = app_handle
+        .try_state::<EnigoState>()
+        .ok_or("Enigo state not initialized")?;
+    let mut enigo = enigo_state
+        .0
+        .lock()
+        .map_err(|e| format!("Failed to lock Enigo: {}", e))?;
+
+    match paste_method {
+        PasteMethod::None => {
+            info!("PasteMethod::None selected - skipping paste action");
+            Ok(())
+        }
+        PasteMethod::Direct => paste_direct(&mut enigo, text),
+        PasteMethod::CtrlV | PasteMethod::CtrlShiftV | PasteMethod::ShiftInsert => {
+            paste_via_clipboard(&mut enigo, text, app_handle, &paste_method)
+        }
+    }
+}
+
 pub fn paste(text: String, app_handle: AppHandle) -> Result<(), String> {
     let settings = get_settings(&app_handle);
     let paste_method = settings.paste_method;
 
-    // Append trailing space if setting is enabled
-    let text = if settings.append_trailing_space {
-        format!("{} ", text)
-    } else {
-        text
-    };
-
     info!("Using paste method: {:?}", paste_method);
 
     // Get the managed Enigo instance
@@ -310,6 +472,19 @@ pub fn paste(text: String, app_handle: AppHandle) -> Result<(), String> {
         .lock()
         .map_err(|e| format!("Failed to lock Enigo: {}", e))?;
 
+    // Fit the text to what's already before the cursor. The probe copies with
+    // Ctrl+C, which interrupts the running program in a terminal, so skip it
+    // for the paste methods terminals need.
+    let probe_context = matches!(paste_method, PasteMethod::CtrlV | PasteMethod::Direct);
+    let text = if settings.context_aware_insertion && probe_context {
+        match read_text_before_cursor(&mut enigo, &app_handle) {
+            Some(preceding) => adapt_to_insertion_context(&text, &preceding),
+            None => text,
+        }
+    } else {
+        text
+    };
+
     // Perform the paste operation
     match paste_method {
         PasteMethod::None => {
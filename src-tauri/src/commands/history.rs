@@ -1,4 +1,8 @@
-use crate::managers::history::{HistoryEntry, HistoryManager};
+use crate::audio_toolkit::read_wav_file;
+use crate::managers::history::{HistoryEntry, HistoryManager, HistoryRevision};
+use crate::managers::transcription::TranscriptionManager;
+use crate::text_pipeline::{AttemptLog, PipelineContext, TextPipeline, TextStage};
+use crate::usage::{aggregate_usage, UsageAggregate, UsagePeriod};
 use std::sync::Arc;
 use tauri::{AppHandle, State};
 
@@ -99,3 +103,117 @@ pub async fn update_recording_retention_period(
 
     Ok(())
 }
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_history_entry_revisions(
+    _app: AppHandle,
+    history_manager: State<'_, Arc<HistoryManager>>,
+    id: i64,
+) -> Result<Vec<HistoryRevision>, String> {
+    history_manager
+        .get_revisions(id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Re-run transcription (and post-processing) on a stored recording, optionally
+/// with a different model, language or post-processing prompt. The result is
+/// stored as a new revision of the entry.
+#[tauri::command]
+#[specta::specta]
+pub async fn retranscribe_history_entry(
+    app: AppHandle,
+    history_manager: State<'_, Arc<HistoryManager>>,
+    transcription_manager: State<'_, Arc<TranscriptionManager>>,
+    id: i64,
+    model_id: Option<String>,
+    language: Option<String>,
+    prompt_id: Option<String>,
+) -> Result<HistoryRevision, String> {
+    let entry = history_manager
+        .get_entry_by_id(id)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("History entry {} not found", id))?;
+
+    let audio_path = history_manager.get_audio_file_path(&entry.file_name);
+    let samples = read_wav_file(&audio_path).map_err(|e| e.to_string())?;
+
+    let tm = Arc::clone(&transcription_manager);
+    let model_for_task = model_id.clone();
+    let language_for_task = language.clone();
+    let transcription = tauri::async_runtime::spawn_blocking(move || {
+        tm.transcribe_with_model(
+            samples,
+            model_for_task.as_deref(),
+            language_for_task.as_deref(),
+        )
+    })
+    .await
+    .map_err(|e| format!("Re-transcription task failed: {}", e))?
+    .map_err(|e| e.to_string())?;
+
+    let mut settings = crate::settings::get_settings(&app);
+    if let Some(language) = &language {
+        settings.selected_language = language.clone();
+    }
+
+    let mut post_processed_text = None;
+    let mut post_process_prompt = None;
+    if !transcription.is_empty() {
+        // Revisions don't translate; the entry keeps its original translation
+        let mut context = PipelineContext::for_dictation(&settings, None);
+        if prompt_id.is_some() {
+            context.post_process_prompt_id = prompt_id;
+        }
+        context.translate = false;
+        context.llm_attempts = AttemptLog::recording_usage(Arc::clone(&history_manager));
+        let run = TextPipeline::from_settings(&settings)
+            .run(&transcription, &context)
+            .await;
+
+        let processed = run.output().trim();
+        if processed != transcription {
+            post_processed_text = Some(processed.to_string());
+        }
+        if run.applied(TextStage::LlmPostProcess) {
+            post_process_prompt = context.post_process_prompt();
+        }
+    }
+
+    let model_id = model_id.or_else(|| transcription_manager.get_current_model());
+
+    history_manager
+        .save_revision(
+            id,
+            model_id,
+            language,
+            transcription,
+            post_processed_text,
+            post_process_prompt,
+        )
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Tokens, latency and estimated cost of post-processing per day or month,
+/// provider and model
+#[tauri::command]
+#[specta::specta]
+pub async fn get_post_process_usage(
+    app: AppHandle,
+    history_manager: State<'_, Arc<HistoryManager>>,
+    period: UsagePeriod,
+) -> Result<Vec<UsageAggregate>, String> {
+    let records = history_manager
+        .get_llm_usage()
+        .await
+        .map_err(|e| e.to_string())?;
+    let settings = crate::settings::get_settings(&app);
+    Ok(aggregate_usage(
+        &records,
+        period,
+        &settings.post_process_prices,
+    ))
+}
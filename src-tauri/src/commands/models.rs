@@ -1,8 +1,18 @@
+use crate::audio_toolkit::read_wav_file;
+use crate::managers::benchmark::{run_benchmarks, ModelBenchmark};
+use crate::managers::history::HistoryManager;
 use crate::managers::model::{ModelInfo, ModelManager};
 use crate::managers::transcription::TranscriptionManager;
 use crate::settings::{get_settings, write_settings};
 use std::sync::Arc;
-use tauri::{AppHandle, State};
+use tauri::{AppHandle, Manager, State};
+
+/// Reference clip shipped with the app for benchmarking: ten seconds of
+/// synthesized speech-like audio, bundled through the `resources/**/*` glob
+const BENCHMARK_REFERENCE_CLIP: &str = "resources/benchmark_reference.wav";
+
+/// Benchmarked models slower than this real-time factor are not recommended
+const RECOMMENDED_MAX_REAL_TIME_FACTOR: f32 = 0.5;
 
 #[tauri::command]
 #[specta::specta]
@@ -102,20 +112,29 @@ pub async fn is_model_loading(
 #[tauri::command]
 #[specta::specta]
 pub async fn has_any_models_available(
+    app_handle: AppHandle,
     model_manager: State<'_, Arc<ModelManager>>,
 ) -> Result<bool, String> {
+    let selected_model = get_settings(&app_handle).selected_model;
     let models = model_manager.get_available_models();
-    Ok(models.iter().any(|m| m.is_downloaded))
+    Ok(models.iter().any(|m| is_model_usable(m, &selected_model)))
 }
 
 #[tauri::command]
 #[specta::specta]
 pub async fn has_any_models_or_downloads(
+    app_handle: AppHandle,
     model_manager: State<'_, Arc<ModelManager>>,
 ) -> Result<bool, String> {
+    let selected_model = get_settings(&app_handle).selected_model;
     let models = model_manager.get_available_models();
     // Return true if any models are downloaded OR if any downloads are in progress
-    Ok(models.iter().any(|m| m.is_downloaded))
+    Ok(models.iter().any(|m| is_model_usable(m, &selected_model)))
+}
+
+/// Remote models are always "downloaded", so they only count once the user picked one
+fn is_model_usable(model: &ModelInfo, selected_model: &str) -> bool {
+    model.is_downloaded && (!model.engine_type.is_remote() || model.id == selected_model)
 }
 
 #[tauri::command]
@@ -131,7 +150,102 @@ pub async fn cancel_download(
 
 #[tauri::command]
 #[specta::specta]
-pub async fn get_recommended_first_model() -> Result<String, String> {
+pub async fn get_recommended_first_model(
+    model_manager: State<'_, Arc<ModelManager>>,
+) -> Result<String, String> {
+    // Prefer the most accurate model that measured comfortably faster than real time here
+    let measured = model_manager
+        .get_available_models()
+        .into_iter()
+        .filter(|m| !m.engine_type.is_remote())
+        .filter(|m| {
+            m.benchmark
+                .as_ref()
+                .is_some_and(|b| b.real_time_factor <= RECOMMENDED_MAX_REAL_TIME_FACTOR)
+        })
+        .max_by(|a, b| a.accuracy_score.total_cmp(&b.accuracy_score));
+
+    if let Some(model) = measured {
+        return Ok(model.id);
+    }
+
     // Recommend Parakeet V3 model for first-time users - fastest and most accurate
     Ok("parakeet-tdt-0.6b-v3".to_string())
 }
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_model_benchmarks(
+    model_manager: State<'_, Arc<ModelManager>>,
+) -> Result<Vec<ModelBenchmark>, String> {
+    Ok(model_manager.get_benchmarks())
+}
+
+/// Benchmark downloaded models on this machine. Uses the given history entry as
+/// reference audio, otherwise the bundled reference clip, falling back to the most
+/// recent recording. Progress is reported via `model-benchmark-progress` events.
+#[tauri::command]
+#[specta::specta]
+pub async fn benchmark_models(
+    app_handle: AppHandle,
+    model_manager: State<'_, Arc<ModelManager>>,
+    transcription_manager: State<'_, Arc<TranscriptionManager>>,
+    history_manager: State<'_, Arc<HistoryManager>>,
+    history_id: Option<i64>,
+    model_ids: Option<Vec<String>>,
+) -> Result<Vec<ModelBenchmark>, String> {
+    let reference_path = match history_id {
+        Some(id) => {
+            let entry = history_manager
+                .get_entry_by_id(id)
+                .await
+                .map_err(|e| e.to_string())?
+                .ok_or_else(|| format!("History entry {} not found", id))?;
+            history_manager.get_audio_file_path(&entry.file_name)
+        }
+        None => {
+            let bundled = app_handle
+                .path()
+                .resolve(
+                    BENCHMARK_REFERENCE_CLIP,
+                    tauri::path::BaseDirectory::Resource,
+                )
+                .ok()
+                .filter(|path| path.exists());
+
+            match bundled {
+                Some(path) => path,
+                None => {
+                    let entries = history_manager
+                        .get_history_entries()
+                        .await
+                        .map_err(|e| e.to_string())?;
+                    let latest = entries.first().ok_or_else(|| {
+                        "No reference audio available for benchmarking".to_string()
+                    })?;
+                    history_manager.get_audio_file_path(&latest.file_name)
+                }
+            }
+        }
+    };
+
+    let audio = read_wav_file(&reference_path).map_err(|e| e.to_string())?;
+
+    let model_ids: Vec<String> = match model_ids {
+        Some(ids) => ids,
+        None => model_manager
+            .get_available_models()
+            .into_iter()
+            .filter(|m| m.is_downloaded && !m.engine_type.is_remote())
+            .map(|m| m.id)
+            .collect(),
+    };
+
+    let mm = Arc::clone(&model_manager);
+    let tm = Arc::clone(&transcription_manager);
+    tauri::async_runtime::spawn_blocking(move || {
+        run_benchmarks(&app_handle, &mm, &tm, &model_ids, &audio)
+    })
+    .await
+    .map_err(|e| format!("Benchmark task failed: {}", e))
+}
@@ -1,7 +1,17 @@
+use crate::evaluation::{evaluate_directory, EvaluationReport};
+use crate::managers::history::HistoryManager;
+use crate::managers::model::ModelManager;
+use crate::managers::secrets::mask_secret;
 use crate::managers::transcription::TranscriptionManager;
-use crate::settings::{get_settings, write_settings, ModelUnloadTimeout};
+use crate::settings::{
+    get_settings, write_settings, ModelUnloadTimeout, RemoteTranscriptionSettings,
+};
+use crate::text_pipeline::{AttemptLog, PipelineContext, PipelineRun, TextPipeline};
 use serde::Serialize;
 use specta::Type;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
 use tauri::{AppHandle, State};
 
 #[derive(Serialize, Type)]
@@ -38,3 +48,122 @@ pub fn unload_model_manually(
         .unload_model()
         .map_err(|e| format!("Failed to unload model: {}", e))
 }
+
+/// Update the OpenAI-compatible transcription server. A loaded remote model is
+/// unloaded so the next transcription picks up the new connection details.
+#[tauri::command]
+#[specta::specta]
+pub fn change_remote_transcription_settings(
+    app: AppHandle,
+    transcription_manager: State<'_, Arc<TranscriptionManager>>,
+    model_manager: State<'_, Arc<ModelManager>>,
+    remote: RemoteTranscriptionSettings,
+) -> Result<(), String> {
+    if remote.base_url.trim().is_empty() {
+        return Err("Base URL cannot be empty".to_string());
+    }
+    if remote.timeout_seconds == 0 {
+        return Err("Timeout must be at least one second".to_string());
+    }
+
+    let mut settings = get_settings(&app);
+    // The UI only has the masked key, which it sends back unchanged
+    let current_key = &settings.remote_transcription.api_key;
+    let api_key = if !current_key.is_empty() && mask_secret(current_key) == remote.api_key {
+        current_key.clone()
+    } else {
+        remote.api_key
+    };
+    settings.remote_transcription = RemoteTranscriptionSettings { api_key, ..remote };
+    write_settings(&app, settings);
+
+    let remote_loaded = transcription_manager
+        .get_current_model()
+        .and_then(|id| model_manager.get_model_info(&id))
+        .is_some_and(|info| info.engine_type.is_remote());
+    if remote_loaded {
+        transcription_manager
+            .unload_model()
+            .map_err(|e| format!("Failed to unload model: {}", e))?;
+    }
+
+    Ok(())
+}
+
+/// Set the language → model routing table. With "auto" language selection the
+/// spoken language is detected first and the matching model is used.
+#[tauri::command]
+#[specta::specta]
+pub fn change_language_model_routes_setting(
+    app: AppHandle,
+    model_manager: State<'_, Arc<ModelManager>>,
+    routes: HashMap<String, String>,
+) -> Result<(), String> {
+    if let Some(model_id) = routes
+        .values()
+        .find(|model_id| model_manager.get_model_info(model_id).is_none())
+    {
+        return Err(format!("Model not found: {}", model_id));
+    }
+
+    let mut settings = get_settings(&app);
+    settings.language_model_routes = routes;
+    write_settings(&app, settings);
+    Ok(())
+}
+
+/// Set the languages the user speaks. Automatic detection then only chooses
+/// among these, falling back to the first one when it is unsure.
+#[tauri::command]
+#[specta::specta]
+pub fn change_spoken_languages_setting(
+    app: AppHandle,
+    languages: Vec<String>,
+) -> Result<(), String> {
+    if languages.iter().any(|language| language == "auto") {
+        return Err("\"auto\" is not a spoken language".to_string());
+    }
+
+    let mut settings = get_settings(&app);
+    settings.spoken_languages = languages;
+    write_settings(&app, settings);
+    Ok(())
+}
+
+/// Run arbitrary text through the configured text-processing pipeline and
+/// return the output of every stage. Enabled LLM stages make real requests.
+#[tauri::command]
+#[specta::specta]
+pub async fn preview_text_pipeline(
+    app: AppHandle,
+    history_manager: State<'_, Arc<HistoryManager>>,
+    text: String,
+) -> Result<PipelineRun, String> {
+    let settings = get_settings(&app);
+    let mut context = PipelineContext::for_dictation(&settings, None);
+    context.llm_attempts = AttemptLog::recording_usage(Arc::clone(&history_manager));
+    Ok(TextPipeline::from_settings(&settings)
+        .run(&text, &context)
+        .await)
+}
+
+/// Score the dictation pipeline against a directory of `.wav` files with `.txt`
+/// reference transcripts. Also available as `handy --evaluate <dir>`.
+#[tauri::command]
+#[specta::specta]
+pub async fn evaluate_transcription_accuracy(
+    app: AppHandle,
+    transcription_manager: State<'_, Arc<TranscriptionManager>>,
+    directory: String,
+    model_id: Option<String>,
+    prompt_id: Option<String>,
+) -> Result<EvaluationReport, String> {
+    evaluate_directory(
+        &app,
+        Arc::clone(&transcription_manager),
+        &PathBuf::from(directory),
+        model_id,
+        prompt_id,
+    )
+    .await
+}
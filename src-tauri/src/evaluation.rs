@@ -0,0 +1,466 @@
+use crate::audio_toolkit::read_wav_file;
+use crate::managers::history::HistoryManager;
+use crate::managers::transcription::TranscriptionManager;
+use crate::settings::get_settings;
+use crate::text_pipeline::{AttemptLog, PipelineContext, TextPipeline};
+use log::{debug, info, warn};
+use serde::Serialize;
+use specta::Type;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tauri::{AppHandle, Manager};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum WordOp {
+    Equal,
+    Substitution,
+    Deletion,
+    Insertion,
+}
+
+/// One step of the alignment between reference and hypothesis words
+#[derive(Debug, Clone, PartialEq, Serialize, Type)]
+pub struct WordDiff {
+    pub op: WordOp,
+    pub reference: Option<String>,
+    pub hypothesis: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Type)]
+pub struct ErrorCounts {
+    pub reference_words: usize,
+    pub substitutions: usize,
+    pub deletions: usize,
+    pub insertions: usize,
+    pub word_error_rate: f64,
+}
+
+impl ErrorCounts {
+    fn add(&mut self, other: &ErrorCounts) {
+        self.reference_words += other.reference_words;
+        self.substitutions += other.substitutions;
+        self.deletions += other.deletions;
+        self.insertions += other.insertions;
+        self.word_error_rate = word_error_rate(self);
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct FileEvaluation {
+    pub file: String,
+    pub reference: String,
+    pub hypothesis: String,
+    pub counts: ErrorCounts,
+    pub diff: Vec<WordDiff>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct EvaluationReport {
+    pub timestamp: i64,
+    pub model_id: Option<String>,
+    pub language: String,
+    pub custom_words: Vec<String>,
+    pub word_correction_threshold: f64,
+    pub post_process_prompt_id: Option<String>,
+    pub totals: ErrorCounts,
+    pub files: Vec<FileEvaluation>,
+}
+
+fn word_error_rate(counts: &ErrorCounts) -> f64 {
+    let errors = counts.substitutions + counts.deletions + counts.insertions;
+    if counts.reference_words == 0 {
+        if errors == 0 {
+            0.0
+        } else {
+            1.0
+        }
+    } else {
+        errors as f64 / counts.reference_words as f64
+    }
+}
+
+/// Lowercases and strips punctuation so formatting differences don't count as errors
+fn normalize_words(text: &str) -> Vec<String> {
+    text.split_whitespace()
+        .map(|word| {
+            word.chars()
+                .filter(|c| c.is_alphanumeric() || *c == '\'')
+                .collect::<String>()
+                .to_lowercase()
+        })
+        .filter(|word| !word.is_empty())
+        .collect()
+}
+
+/// Aligns hypothesis against reference with a word-level Levenshtein alignment
+/// and returns the error counts along with the per-word diff.
+pub fn compute_word_errors(reference: &str, hypothesis: &str) -> (ErrorCounts, Vec<WordDiff>) {
+    let reference = normalize_words(reference);
+    let hypothesis = normalize_words(hypothesis);
+    let (n, m) = (reference.len(), hypothesis.len());
+
+    // dist[i][j] = edit distance between reference[..i] and hypothesis[..j]
+    let mut dist = vec![vec![0usize; m + 1]; n + 1];
+    for (i, row) in dist.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in dist[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=n {
+        for j in 1..=m {
+            let cost = usize::from(reference[i - 1] != hypothesis[j - 1]);
+            dist[i][j] = (dist[i - 1][j - 1] + cost)
+                .min(dist[i - 1][j] + 1)
+                .min(dist[i][j - 1] + 1);
+        }
+    }
+
+    // Walk back from the end to recover the operations
+    let mut diff = Vec::new();
+    let mut counts = ErrorCounts {
+        reference_words: n,
+        ..Default::default()
+    };
+    let (mut i, mut j) = (n, m);
+    while i > 0 || j > 0 {
+        if i > 0 && j > 0 {
+            let cost = usize::from(reference[i - 1] != hypothesis[j - 1]);
+            if dist[i][j] == dist[i - 1][j - 1] + cost {
+                let op = if cost == 0 {
+                    WordOp::Equal
+                } else {
+                    counts.substitutions += 1;
+                    WordOp::Substitution
+                };
+                diff.push(WordDiff {
+                    op,
+                    reference: Some(reference[i - 1].clone()),
+                    hypothesis: Some(hypothesis[j - 1].clone()),
+                });
+                i -= 1;
+                j -= 1;
+                continue;
+            }
+        }
+        if i > 0 && dist[i][j] == dist[i - 1][j] + 1 {
+            counts.deletions += 1;
+            diff.push(WordDiff {
+                op: WordOp::Deletion,
+                reference: Some(reference[i - 1].clone()),
+                hypothesis: None,
+            });
+            i -= 1;
+        } else {
+            counts.insertions += 1;
+            diff.push(WordDiff {
+                op: WordOp::Insertion,
+                reference: None,
+                hypothesis: Some(hypothesis[j - 1].clone()),
+            });
+            j -= 1;
+        }
+    }
+    diff.reverse();
+
+    counts.word_error_rate = word_error_rate(&counts);
+    (counts, diff)
+}
+
+/// Collects `(audio, reference)` pairs: every `.wav` file with a `.txt` file of the same stem
+fn collect_samples(dir: &Path) -> Result<Vec<(PathBuf, PathBuf)>, String> {
+    let entries =
+        fs::read_dir(dir).map_err(|e| format!("Failed to read directory {:?}: {}", dir, e))?;
+
+    let mut samples: Vec<(PathBuf, PathBuf)> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            path.extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("wav"))
+        })
+        .filter_map(|audio| {
+            let reference = audio.with_extension("txt");
+            if reference.exists() {
+                Some((audio, reference))
+            } else {
+                warn!("Skipping {:?}: no reference transcript", audio);
+                None
+            }
+        })
+        .collect();
+
+    samples.sort();
+    Ok(samples)
+}
+
+/// Runs every audio file in `dir` through the dictation pipeline (engine, text
+/// stages and optionally LLM post-processing with `prompt_id`) and scores the
+/// output against the `.txt` reference next to it.
+pub async fn evaluate_directory(
+    app: &AppHandle,
+    transcription_manager: Arc<TranscriptionManager>,
+    dir: &Path,
+    model_id: Option<String>,
+    prompt_id: Option<String>,
+) -> Result<EvaluationReport, String> {
+    let settings = get_settings(app);
+    let pipeline = TextPipeline::from_settings(&settings);
+    let samples = collect_samples(dir)?;
+    if samples.is_empty() {
+        return Err(format!(
+            "No .wav files with matching .txt references found in {:?}",
+            dir
+        ));
+    }
+
+    info!("Evaluating {} files from {:?}", samples.len(), dir);
+
+    let mut totals = ErrorCounts::default();
+    let mut files = Vec::new();
+
+    for (audio_path, reference_path) in samples {
+        let file = audio_path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        let reference = fs::read_to_string(&reference_path)
+            .map_err(|e| format!("Failed to read {:?}: {}", reference_path, e))?;
+
+        let tm = Arc::clone(&transcription_manager);
+        let model_for_task = model_id.clone();
+        let transcription = tauri::async_runtime::spawn_blocking(move || {
+            let audio = read_wav_file(&audio_path)?;
+            tm.transcribe_with_model(audio, model_for_task.as_deref(), None)
+        })
+        .await
+        .map_err(|e| format!("Evaluation task failed: {}", e))?;
+
+        let mut hypothesis = match transcription {
+            Ok(text) => text,
+            Err(e) => {
+                warn!("Failed to transcribe {}: {}", file, e);
+                let (counts, diff) = compute_word_errors(&reference, "");
+                totals.add(&counts);
+                files.push(FileEvaluation {
+                    file,
+                    reference,
+                    hypothesis: String::new(),
+                    counts,
+                    diff,
+                    error: Some(e.to_string()),
+                });
+                continue;
+            }
+        };
+
+        // Score what would be pasted, without translating it, and only post-process
+        // with the prompt under evaluation
+        let mut context = PipelineContext::for_dictation(&settings, None);
+        context.post_process_prompt_id = prompt_id.clone();
+        context.translate = false;
+        if let Some(history) = app.try_state::<Arc<HistoryManager>>() {
+            context.llm_attempts = AttemptLog::recording_usage(Arc::clone(&history));
+        }
+        let run = pipeline.run(&hypothesis, &context).await;
+        hypothesis = run.output().trim().to_string();
+
+        let (counts, diff) = compute_word_errors(&reference, &hypothesis);
+        debug!("{}: WER {:.3}", file, counts.word_error_rate);
+        totals.add(&counts);
+        files.push(FileEvaluation {
+            file,
+            reference,
+            hypothesis,
+            counts,
+            diff,
+            error: None,
+        });
+    }
+
+    info!(
+        "Evaluation finished: WER {:.3} over {} reference words",
+        totals.word_error_rate, totals.reference_words
+    );
+
+    Ok(EvaluationReport {
+        timestamp: chrono::Utc::now().timestamp(),
+        model_id: model_id.or_else(|| transcription_manager.get_current_model()),
+        language: settings.selected_language,
+        custom_words: settings.custom_words,
+        word_correction_threshold: settings.word_correction_threshold,
+        post_process_prompt_id: prompt_id,
+        totals,
+        files,
+    })
+}
+
+/// Options for running an evaluation from the command line, e.g.
+/// `handy --evaluate ./samples --model turbo --prompt default_improve_transcriptions --output report.json`
+#[derive(Debug, Clone, PartialEq)]
+pub struct EvaluationCliOptions {
+    pub dir: PathBuf,
+    pub model_id: Option<String>,
+    pub prompt_id: Option<String>,
+    pub output: Option<PathBuf>,
+}
+
+/// Parses the evaluation CLI flags. Returns `None` when `--evaluate` isn't present so
+/// the app starts normally.
+pub fn parse_cli_args(
+    args: impl IntoIterator<Item = String>,
+) -> Option<Result<EvaluationCliOptions, String>> {
+    let args: Vec<String> = args.into_iter().collect();
+    if !args.iter().any(|arg| arg == "--evaluate") {
+        return None;
+    }
+
+    let mut dir = None;
+    let mut model_id = None;
+    let mut prompt_id = None;
+    let mut output = None;
+
+    let mut iter = args.into_iter();
+    while let Some(arg) = iter.next() {
+        let target = match arg.as_str() {
+            "--evaluate" | "--model" | "--prompt" | "--output" => arg,
+            _ => continue,
+        };
+        let value = match iter.next() {
+            Some(value) if !value.starts_with("--") => value,
+            _ => return Some(Err(format!("Missing value for {}", target))),
+        };
+        match target.as_str() {
+            "--evaluate" => dir = Some(PathBuf::from(value)),
+            "--model" => model_id = Some(value),
+            "--prompt" => prompt_id = Some(value),
+            _ => output = Some(PathBuf::from(value)),
+        }
+    }
+
+    Some(
+        dir.ok_or_else(|| "Missing value for --evaluate".to_string())
+            .map(|dir| EvaluationCliOptions {
+                dir,
+                model_id,
+                prompt_id,
+                output,
+            }),
+    )
+}
+
+/// Runs the evaluation requested on the command line, writes the JSON report to
+/// the output file (or stdout) and exits the app.
+pub async fn run_cli_evaluation(
+    app: AppHandle,
+    transcription_manager: Arc<TranscriptionManager>,
+    options: EvaluationCliOptions,
+) {
+    let result = evaluate_directory(
+        &app,
+        transcription_manager,
+        &options.dir,
+        options.model_id,
+        options.prompt_id,
+    )
+    .await
+    .and_then(|report| {
+        serde_json::to_string_pretty(&report)
+            .map_err(|e| format!("Failed to serialize report: {}", e))
+    })
+    .and_then(|json| match &options.output {
+        Some(path) => fs::write(path, json)
+            .map_err(|e| format!("Failed to write report to {:?}: {}", path, e)),
+        None => {
+            println!("{}", json);
+            Ok(())
+        }
+    });
+
+    match result {
+        Ok(()) => app.exit(0),
+        Err(e) => {
+            eprintln!("Evaluation failed: {}", e);
+            app.exit(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_identical_text_has_no_errors() {
+        let (counts, diff) = compute_word_errors("Hello, world!", "hello world");
+        assert_eq!(counts.word_error_rate, 0.0);
+        assert_eq!(counts.reference_words, 2);
+        assert!(diff.iter().all(|d| d.op == WordOp::Equal));
+    }
+
+    #[test]
+    fn test_error_breakdown() {
+        let (counts, diff) = compute_word_errors(
+            "the quick brown fox jumps over",
+            "the quack brown jumps over it",
+        );
+        assert_eq!(counts.substitutions, 1);
+        assert_eq!(counts.deletions, 1);
+        assert_eq!(counts.insertions, 1);
+        assert!((counts.word_error_rate - 0.5).abs() < 1e-9);
+
+        let ops: Vec<WordOp> = diff.iter().map(|d| d.op).collect();
+        assert_eq!(
+            ops,
+            vec![
+                WordOp::Equal,
+                WordOp::Substitution,
+                WordOp::Equal,
+                WordOp::Deletion,
+                WordOp::Equal,
+                WordOp::Equal,
+                WordOp::Insertion,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_empty_hypothesis_is_all_deletions() {
+        let (counts, _) = compute_word_errors("one two three", "");
+        assert_eq!(counts.deletions, 3);
+        assert_eq!(counts.word_error_rate, 1.0);
+    }
+
+    #[test]
+    fn test_parse_cli_args() {
+        let args = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(parse_cli_args(args(&["--start-hidden"])), None);
+        assert_eq!(
+            parse_cli_args(args(&["--evaluate", "samples", "--model", "turbo"])),
+            Some(Ok(EvaluationCliOptions {
+                dir: PathBuf::from("samples"),
+                model_id: Some("turbo".to_string()),
+                prompt_id: None,
+                output: None,
+            }))
+        );
+        assert!(matches!(
+            parse_cli_args(args(&["--evaluate", "--model", "turbo"])),
+            Some(Err(_))
+        ));
+    }
+
+    #[test]
+    fn test_totals_are_weighted_by_reference_length() {
+        let mut totals = ErrorCounts::default();
+        totals.add(&compute_word_errors("a b c d", "a b c d").0);
+        totals.add(&compute_word_errors("e f", "e g").0);
+        assert_eq!(totals.reference_words, 6);
+        assert!((totals.word_error_rate - 1.0 / 6.0).abs() < 1e-9);
+    }
+}
@@ -5,16 +5,23 @@ mod audio_feedback;
 pub mod audio_toolkit;
 mod clipboard;
 mod commands;
+mod evaluation;
 mod helpers;
 mod input;
 mod llm_client;
 mod managers;
 mod overlay;
+mod prompt_template;
+mod redaction;
+mod replacements;
 mod settings;
 mod shortcut;
 mod signal_handle;
+mod text_pipeline;
+mod transcription_backend;
 mod tray;
 mod tray_i18n;
+mod usage;
 mod utils;
 use specta_typescript::{BigIntExportBehavior, Typescript};
 use tauri_specta::{collect_commands, Builder};
@@ -23,6 +30,7 @@ use env_filter::Builder as EnvFilterBuilder;
 use managers::audio::AudioRecordingManager;
 use managers::history::HistoryManager;
 use managers::model::ModelManager;
+use managers::secrets::SecretManager;
 use managers::transcription::TranscriptionManager;
 #[cfg(unix)]
 use signal_hook::consts::SIGUSR2;
@@ -109,15 +117,9 @@ fn show_main_window(app: &AppHandle) {
     }
 }
 
-fn initialize_core_logic(app_handle: &AppHandle) {
-    // Initialize the input state (Enigo singleton for keyboard/mouse simulation)
-    let enigo_state = input::EnigoState::new().expect("Failed to initialize input state (Enigo)");
-    app_handle.manage(enigo_state);
-
-    // Initialize the managers
-    let recording_manager = Arc::new(
-        AudioRecordingManager::new(app_handle).expect("Failed to initialize recording manager"),
-    );
+/// The managers needed to transcribe files and post-process the text, which is
+/// all an evaluation run uses
+fn initialize_transcription_managers(app_handle: &AppHandle) -> Arc<TranscriptionManager> {
     let model_manager =
         Arc::new(ModelManager::new(app_handle).expect("Failed to initialize model manager"));
     let transcription_manager = Arc::new(
@@ -127,11 +129,23 @@ fn initialize_core_logic(app_handle: &AppHandle) {
     let history_manager =
         Arc::new(HistoryManager::new(app_handle).expect("Failed to initialize history manager"));
 
-    // Add managers to Tauri's managed state
-    app_handle.manage(recording_manager.clone());
-    app_handle.manage(model_manager.clone());
+    app_handle.manage(model_manager);
     app_handle.manage(transcription_manager.clone());
-    app_handle.manage(history_manager.clone());
+    app_handle.manage(history_manager);
+    transcription_manager
+}
+
+fn initialize_core_logic(app_handle: &AppHandle) {
+    // Initialize the input state (Enigo singleton for keyboard/mouse simulation)
+    let enigo_state = input::EnigoState::new().expect("Failed to initialize input state (Enigo)");
+    app_handle.manage(enigo_state);
+
+    // Initialize the managers
+    let recording_manager = Arc::new(
+        AudioRecordingManager::new(app_handle).expect("Failed to initialize recording manager"),
+    );
+    app_handle.manage(recording_manager);
+    initialize_transcription_managers(app_handle);
 
     // Initialize the shortcuts
     shortcut::init_shortcuts(app_handle);
@@ -231,9 +245,22 @@ pub fn run() {
     // when the variable is unset
     let console_filter = build_console_filter();
 
+    // `--evaluate <dir>` runs an accuracy evaluation and exits instead of starting normally
+    let evaluation_options = match evaluation::parse_cli_args(std::env::args().skip(1)) {
+        Some(Ok(options)) => Some(options),
+        Some(Err(e)) => {
+            eprintln!("{}", e);
+            std::process::exit(2);
+        }
+        None => None,
+    };
+
     let specta_builder = Builder::<tauri::Wry>::new().commands(collect_commands![
         shortcut::change_binding,
         shortcut::reset_binding,
+        shortcut::add_transcribe_binding,
+        shortcut::remove_transcribe_binding,
+        shortcut::change_binding_prompt_setting,
         shortcut::change_ptt_setting,
         shortcut::change_audio_feedback_setting,
         shortcut::change_audio_feedback_volume_setting,
@@ -248,11 +275,17 @@ pub fn run() {
         shortcut::change_paste_method_setting,
         shortcut::change_clipboard_handling_setting,
         shortcut::change_post_process_enabled_setting,
+        shortcut::change_post_process_streaming_setting,
         shortcut::change_post_process_base_url_setting,
+        shortcut::change_post_process_protocol_setting,
+        shortcut::change_post_process_request_limits_setting,
+        shortcut::change_post_process_fallback_providers_setting,
+        shortcut::change_post_process_prices_setting,
         shortcut::change_post_process_api_key_setting,
         shortcut::change_post_process_model_setting,
         shortcut::set_post_process_provider,
         shortcut::fetch_post_process_models,
+        shortcut::check_post_process_provider_health,
         shortcut::add_post_process_prompt,
         shortcut::update_post_process_prompt,
         shortcut::delete_post_process_prompt,
@@ -262,7 +295,21 @@ pub fn run() {
         shortcut::resume_binding,
         shortcut::change_mute_while_recording_setting,
         shortcut::change_append_trailing_space_setting,
+        shortcut::change_context_aware_insertion_setting,
         shortcut::change_app_language_setting,
+        shortcut::change_translation_enabled_setting,
+        shortcut::change_translation_target_language_setting,
+        shortcut::change_translation_prompt_setting,
+        shortcut::change_spoken_commands_setting,
+        shortcut::update_replacement_rules,
+        shortcut::export_replacement_rules,
+        shortcut::import_replacement_rules,
+        shortcut::change_phonetic_algorithm_setting,
+        shortcut::change_inverse_text_normalization_setting,
+        shortcut::change_disfluency_removal_setting,
+        shortcut::change_pii_redaction_setting,
+        shortcut::change_text_pipeline_setting,
+        shortcut::change_text_casing_setting,
         shortcut::change_update_checks_setting,
         trigger_update_check,
         commands::cancel_operation,
@@ -287,6 +334,8 @@ pub fn run() {
         commands::models::has_any_models_available,
         commands::models::has_any_models_or_downloads,
         commands::models::get_recommended_first_model,
+        commands::models::get_model_benchmarks,
+        commands::models::benchmark_models,
         commands::audio::update_microphone_mode,
         commands::audio::get_microphone_mode,
         commands::audio::get_available_microphones,
@@ -303,12 +352,20 @@ pub fn run() {
         commands::transcription::set_model_unload_timeout,
         commands::transcription::get_model_load_status,
         commands::transcription::unload_model_manually,
+        commands::transcription::evaluate_transcription_accuracy,
+        commands::transcription::change_remote_transcription_settings,
+        commands::transcription::change_language_model_routes_setting,
+        commands::transcription::change_spoken_languages_setting,
+        commands::transcription::preview_text_pipeline,
         commands::history::get_history_entries,
         commands::history::toggle_history_entry_saved,
         commands::history::get_audio_file_path,
         commands::history::delete_history_entry,
         commands::history::update_history_limit,
         commands::history::update_recording_retention_period,
+        commands::history::get_history_entry_revisions,
+        commands::history::retranscribe_history_entry,
+        commands::history::get_post_process_usage,
         helpers::clamshell::is_laptop,
     ]);
 
@@ -349,10 +406,14 @@ pub fn run() {
         builder = builder.plugin(tauri_nspanel::init());
     }
 
-    builder
-        .plugin(tauri_plugin_single_instance::init(|app, _args, _cwd| {
+    // Evaluation runs alongside a normal instance, so don't hand off to it
+    if evaluation_options.is_none() {
+        builder = builder.plugin(tauri_plugin_single_instance::init(|app, _args, _cwd| {
             show_main_window(app);
-        }))
+        }));
+    }
+
+    builder
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_process::init())
         .plugin(tauri_plugin_updater::Builder::new().build())
@@ -368,6 +429,19 @@ pub fn run() {
         ))
         .manage(Mutex::new(ShortcutToggleStates::default()))
         .setup(move |app| {
+            // API keys are read through the secret store, so it has to be in
+            // place before the settings are first loaded
+            match SecretManager::new(app.handle()) {
+                Ok(secret_manager) => {
+                    app.manage(Arc::new(secret_manager));
+                    settings::migrate_secrets_to_secret_store(app.handle());
+                }
+                Err(e) => log::error!(
+                    "Failed to open the secret store, API keys stay in the settings file: {}",
+                    e
+                ),
+            }
+
             let settings = get_settings(&app.handle());
             let tauri_log_level: tauri_plugin_log::LogLevel = settings.log_level.into();
             let file_log_level: log::Level = tauri_log_level.into();
@@ -375,6 +449,18 @@ pub fn run() {
             FILE_LOG_LEVEL.store(file_log_level.to_level_filter() as u8, Ordering::Relaxed);
             let app_handle = app.handle().clone();
 
+            // Evaluation only transcribes files, so it leaves shortcuts, the
+            // tray and audio devices to the instance that may already be running
+            if let Some(options) = evaluation_options.clone() {
+                let tm = initialize_transcription_managers(&app_handle);
+                tauri::async_runtime::spawn(evaluation::run_cli_evaluation(
+                    app_handle.clone(),
+                    tm,
+                    options,
+                ));
+                return Ok(());
+            }
+
             initialize_core_logic(&app_handle);
 
             // Show main window only if not starting hidden
@@ -1,33 +1,82 @@
+mod protocol;
+#[cfg(test)]
+pub(crate) mod test_server;
+
+pub use protocol::{ChatCompletion, ChatRequest, TokenUsage};
+
 use crate::settings::PostProcessProvider;
-use log::debug;
-use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION, CONTENT_TYPE, REFERER, USER_AGENT};
+use futures_util::StreamExt;
+use log::{debug, warn};
+use protocol::{adapter_for, ProtocolAdapter, StreamEvent, StreamParser};
+use reqwest::header::{HeaderMap, HeaderValue, CONTENT_TYPE, REFERER, RETRY_AFTER, USER_AGENT};
+use reqwest::StatusCode;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use specta::Type;
+use std::time::Duration;
 
-#[derive(Debug, Serialize)]
-struct ChatMessage {
-    role: String,
-    content: String,
-}
-
-#[derive(Debug, Serialize)]
-struct ChatCompletionRequest {
-    model: String,
-    messages: Vec<ChatMessage>,
-}
+/// How long a health check waits for a provider to answer
+const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(3);
+const MAX_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(500);
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(10);
 
-#[derive(Debug, Deserialize)]
-struct ChatCompletionResponse {
-    choices: Vec<ChatChoice>,
+/// What came of asking one provider during post-processing
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum AttemptOutcome {
+    Succeeded,
+    /// The provider answered without any text
+    Empty,
+    Failed {
+        error: String,
+    },
 }
 
-#[derive(Debug, Deserialize)]
-struct ChatChoice {
-    message: ChatMessageResponse,
+/// One provider tried for an LLM request, as recorded in history
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct LlmAttempt {
+    pub provider_id: String,
+    pub model: String,
+    pub duration_ms: u64,
+    pub outcome: AttemptOutcome,
+    /// Tokens billed, when the provider reported them
+    #[serde(default)]
+    pub usage: Option<TokenUsage>,
 }
 
-#[derive(Debug, Deserialize)]
-struct ChatMessageResponse {
-    content: Option<String>,
+/// Applies one streamed event to `content` and `usage`, passing new text to
+/// `on_delta`. Returns true once the stream signals it is done.
+fn apply_stream_event(
+    adapter: &dyn ProtocolAdapter,
+    data: &str,
+    content: &mut String,
+    usage: &mut Option<TokenUsage>,
+    on_delta: &mut impl FnMut(&str),
+) -> Result<bool, String> {
+    let mut record_usage = |reported: TokenUsage| {
+        usage
+            .get_or_insert_with(TokenUsage::default)
+            .merge(reported);
+    };
+    match adapter.parse_stream_event(data)? {
+        StreamEvent::Delta(delta) => {
+            on_delta(&delta);
+            content.push_str(&delta);
+            Ok(false)
+        }
+        StreamEvent::Usage(reported) => {
+            record_usage(reported);
+            Ok(false)
+        }
+        StreamEvent::Done(reported) => {
+            if let Some(reported) = reported {
+                record_usage(reported);
+            }
+            Ok(true)
+        }
+        StreamEvent::Ignore => Ok(false),
+    }
 }
 
 /// Build headers for API requests based on provider type
@@ -46,68 +95,157 @@ fn build_headers(provider: &PostProcessProvider, api_key: &str) -> Result<Header
     );
     headers.insert("X-Title", HeaderValue::from_static("Handy"));
 
-    // Provider-specific auth headers
-    if !api_key.is_empty() {
-        if provider.id == "anthropic" {
-            headers.insert(
-                "x-api-key",
-                HeaderValue::from_str(api_key)
-                    .map_err(|e| format!("Invalid API key header value: {}", e))?,
-            );
-            headers.insert("anthropic-version", HeaderValue::from_static("2023-06-01"));
-        } else {
-            headers.insert(
-                AUTHORIZATION,
-                HeaderValue::from_str(&format!("Bearer {}", api_key))
-                    .map_err(|e| format!("Invalid authorization header value: {}", e))?,
-            );
-        }
-    }
+    // Protocol-specific auth headers
+    adapter_for(provider.protocol).auth_headers(&mut headers, api_key)?;
 
     Ok(headers)
 }
 
-/// Create an HTTP client with provider-specific headers
+/// Create an HTTP client with provider-specific headers and timeouts
 fn create_client(provider: &PostProcessProvider, api_key: &str) -> Result<reqwest::Client, String> {
     let headers = build_headers(provider, api_key)?;
+    // The read timeout bounds each wait for data rather than the whole
+    // request, so long streamed replies aren't cut off
+    let timeout = Duration::from_secs(provider.timeout_secs.max(1));
     reqwest::Client::builder()
         .default_headers(headers)
+        .connect_timeout(timeout.min(MAX_CONNECT_TIMEOUT))
+        .read_timeout(timeout)
         .build()
         .map_err(|e| format!("Failed to build HTTP client: {}", e))
 }
 
-/// Send a chat completion request to an OpenAI-compatible API
-/// Returns Ok(Some(content)) on success, Ok(None) if response has no content,
+fn is_retryable(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+/// Delay before the next retry, preferring the server's `Retry-After`
+fn retry_delay(response: &reqwest::Response, retry: u32) -> Duration {
+    let retry_after = response
+        .headers()
+        .get(RETRY_AFTER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse::<u64>().ok())
+        .map(Duration::from_secs);
+    retry_after
+        .unwrap_or_else(|| RETRY_BASE_DELAY.saturating_mul(1 << retry.min(16)))
+        .min(MAX_RETRY_DELAY)
+}
+
+/// Posts `body`, retrying with backoff while the provider answers with a rate
+/// limit or server error. The last response is returned once retries run out.
+async fn post_with_retries(
+    provider: &PostProcessProvider,
+    client: &reqwest::Client,
+    url: &str,
+    body: &Value,
+) -> Result<reqwest::Response, String> {
+    let mut retry = 0;
+    loop {
+        let response = client
+            .post(url)
+            .json(body)
+            .send()
+            .await
+            .map_err(|e| request_error(provider, "HTTP request failed", e))?;
+
+        let status = response.status();
+        if !is_retryable(status) || retry >= provider.max_retries {
+            return Ok(response);
+        }
+
+        let delay = retry_delay(&response, retry);
+        retry += 1;
+        warn!(
+            "Provider '{}' answered with status {}; retrying in {:?} ({}/{})",
+            provider.id, status, delay, retry, provider.max_retries
+        );
+        tokio::time::sleep(delay).await;
+    }
+}
+
+/// Send a chat request using the provider's protocol
+/// Returns the reply, whose content is None if the response has no content,
 /// or Err on actual errors (HTTP, parsing, etc.)
 pub async fn send_chat_completion(
     provider: &PostProcessProvider,
     api_key: String,
-    model: &str,
-    prompt: String,
-) -> Result<Option<String>, String> {
-    let base_url = provider.base_url.trim_end_matches('/');
-    let url = format!("{}/chat/completions", base_url);
+    request: &ChatRequest<'_>,
+) -> Result<ChatCompletion, String> {
+    let adapter = adapter_for(provider.protocol);
+    let url = adapter.chat_url(&provider.base_url);
 
     debug!("Sending chat completion request to: {}", url);
 
     let client = create_client(provider, &api_key)?;
 
-    let request_body = ChatCompletionRequest {
-        model: model.to_string(),
-        messages: vec![ChatMessage {
-            role: "user".to_string(),
-            content: prompt,
-        }],
-    };
+    let response = post_with_retries(
+        provider,
+        &client,
+        &url,
+        &adapter.request_body(request, false),
+    )
+    .await?;
 
-    let response = client
-        .post(&url)
-        .json(&request_body)
-        .send()
+    let status = response.status();
+    if !status.is_success() {
+        let error_text = response
+            .text()
+            .await
+            .unwrap_or_else(|_| "Failed to read error response".to_string());
+        return Err(format!(
+            "API request failed with status {}: {}",
+            status, error_text
+        ));
+    }
+
+    let body = response
+        .text()
         .await
-        .map_err(|e| format!("HTTP request failed: {}", e))?;
+        .map_err(|e| format!("Failed to read API response: {}", e))?;
+    adapter.parse_response(&body)
+}
+
+/// Send a streaming chat request using the provider's protocol, calling
+/// `on_delta` with each piece of text as it arrives. Providers that answer with
+/// a complete response, or reject streaming, are handled like
+/// `send_chat_completion` with the whole reply passed at once.
+pub async fn stream_chat_completion<F>(
+    provider: &PostProcessProvider,
+    api_key: String,
+    request: &ChatRequest<'_>,
+    mut on_delta: F,
+) -> Result<ChatCompletion, String>
+where
+    F: FnMut(&str),
+{
+    let adapter = adapter_for(provider.protocol);
+    let url = adapter.chat_url(&provider.base_url);
+
+    debug!("Sending streaming chat completion request to: {}", url);
+
+    let client = create_client(provider, &api_key)?;
+
+    let response = post_with_retries(
+        provider,
+        &client,
+        &url,
+        &adapter.request_body(request, true),
+    )
+    .await?;
 
     let status = response.status();
+    if status == StatusCode::BAD_REQUEST || status == StatusCode::UNPROCESSABLE_ENTITY {
+        debug!(
+            "Streaming request rejected with status {}; retrying without streaming",
+            status
+        );
+        let completion = send_chat_completion(provider, api_key, request).await?;
+        if let Some(content) = &completion.content {
+            on_delta(content);
+        }
+        return Ok(completion);
+    }
     if !status.is_success() {
         let error_text = response
             .text()
@@ -119,25 +257,139 @@ pub async fn send_chat_completion(
         ));
     }
 
-    let completion: ChatCompletionResponse = response
-        .json()
+    let format = adapter.stream_format();
+    let is_stream = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| format.matches_content_type(value));
+    if !is_stream {
+        debug!("Provider did not stream the response; reading it whole");
+        let body = response
+            .text()
+            .await
+            .map_err(|e| format!("Failed to read API response: {}", e))?;
+        let completion = adapter.parse_response(&body)?;
+        if let Some(content) = &completion.content {
+            on_delta(content);
+        }
+        return Ok(completion);
+    }
+
+    let mut stream = response.bytes_stream();
+    let mut parser = StreamParser::new(format);
+    let mut content = String::new();
+    let mut usage = None;
+    let mut done = false;
+
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.map_err(|e| format!("Failed to read streamed response: {}", e))?;
+        for data in parser.push(&chunk) {
+            if apply_stream_event(adapter, &data, &mut content, &mut usage, &mut on_delta)? {
+                done = true;
+                break;
+            }
+        }
+        if done {
+            break;
+        }
+    }
+    if !done {
+        if let Some(data) = parser.finish() {
+            apply_stream_event(adapter, &data, &mut content, &mut usage, &mut on_delta)?;
+        }
+    }
+
+    Ok(ChatCompletion {
+        content: (!content.is_empty()).then_some(content),
+        usage,
+    })
+}
+
+/// URL the provider lists its models at
+fn models_url(provider: &PostProcessProvider) -> String {
+    format!(
+        "{}{}",
+        provider.base_url.trim_end_matches('/'),
+        provider.models_endpoint.as_deref().unwrap_or("/models")
+    )
+}
+
+/// Error shown when a local provider's server can't be reached
+pub fn server_not_running_message(provider: &PostProcessProvider) -> String {
+    format!(
+        "{} server is not running at {}. Start it and try again.",
+        provider.label, provider.base_url
+    )
+}
+
+/// Describes a failed request, calling out local servers that aren't running
+fn request_error(provider: &PostProcessProvider, context: &str, error: reqwest::Error) -> String {
+    if provider.local && error.is_connect() {
+        return server_not_running_message(provider);
+    }
+    if error.is_timeout() {
+        return format!(
+            "{} did not respond in time ({}s timeout)",
+            provider.label, provider.timeout_secs
+        );
+    }
+    format!("{}: {}", context, error)
+}
+
+/// Reachability of a post-processing provider
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Type)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum ProviderHealth {
+    /// The server answered and lists this many models
+    Ready { models: usize },
+    /// Nothing is listening at the provider's base URL
+    NotRunning { message: String },
+    /// The server answered, but not successfully
+    Unhealthy { message: String },
+}
+
+/// Checks that a provider is reachable by listing its models
+pub async fn check_health(provider: &PostProcessProvider, api_key: String) -> ProviderHealth {
+    let client = match create_client(provider, &api_key) {
+        Ok(client) => client,
+        Err(message) => return ProviderHealth::Unhealthy { message },
+    };
+
+    let response = match client
+        .get(models_url(provider))
+        .timeout(HEALTH_CHECK_TIMEOUT)
+        .send()
         .await
-        .map_err(|e| format!("Failed to parse API response: {}", e))?;
+    {
+        Ok(response) => response,
+        Err(e) if e.is_connect() => {
+            return ProviderHealth::NotRunning {
+                message: server_not_running_message(provider),
+            }
+        }
+        Err(e) => {
+            return ProviderHealth::Unhealthy {
+                message: format!("Health check failed: {}", e),
+            }
+        }
+    };
 
-    Ok(completion
-        .choices
-        .first()
-        .and_then(|choice| choice.message.content.clone()))
+    match read_models(response).await {
+        Ok(models) => ProviderHealth::Ready {
+            models: models.len(),
+        },
+        Err(message) => ProviderHealth::Unhealthy { message },
+    }
 }
 
-/// Fetch available models from an OpenAI-compatible API
+/// Fetch available models from the provider's models endpoint
 /// Returns a list of model IDs
 pub async fn fetch_models(
     provider: &PostProcessProvider,
     api_key: String,
 ) -> Result<Vec<String>, String> {
-    let base_url = provider.base_url.trim_end_matches('/');
-    let url = format!("{}/models", base_url);
+    let url = models_url(provider);
 
     debug!("Fetching models from: {}", url);
 
@@ -147,8 +399,12 @@ pub async fn fetch_models(
         .get(&url)
         .send()
         .await
-        .map_err(|e| format!("Failed to fetch models: {}", e))?;
+        .map_err(|e| request_error(provider, "Failed to fetch models", e))?;
+
+    read_models(response).await
+}
 
+async fn read_models(response: reqwest::Response) -> Result<Vec<String>, String> {
     let status = response.status();
     if !status.is_success() {
         let error_text = response
@@ -166,15 +422,25 @@ pub async fn fetch_models(
         .await
         .map_err(|e| format!("Failed to parse response: {}", e))?;
 
+    Ok(parse_model_list(&parsed))
+}
+
+fn parse_model_list(parsed: &serde_json::Value) -> Vec<String> {
     let mut models = Vec::new();
 
     // Handle OpenAI format: { data: [ { id: "..." }, ... ] }
-    if let Some(data) = parsed.get("data").and_then(|d| d.as_array()) {
+    // and Ollama format: { models: [ { name: "...", model: "..." }, ... ] }
+    if let Some(data) = parsed
+        .get("data")
+        .or_else(|| parsed.get("models"))
+        .and_then(|d| d.as_array())
+    {
         for entry in data {
-            if let Some(id) = entry.get("id").and_then(|i| i.as_str()) {
+            if let Some(id) = ["id", "name", "model"]
+                .iter()
+                .find_map(|key| entry.get(*key).and_then(|v| v.as_str()))
+            {
                 models.push(id.to_string());
-            } else if let Some(name) = entry.get("name").and_then(|n| n.as_str()) {
-                models.push(name.to_string());
             }
         }
     }
@@ -187,5 +453,249 @@ pub async fn fetch_models(
         }
     }
 
-    Ok(models)
+    models
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::ProviderProtocol;
+    use test_server::{closed_port_url, json_response, serve, serve_once, serve_silently};
+
+    fn provider(base_url: String) -> PostProcessProvider {
+        PostProcessProvider {
+            id: "custom".to_string(),
+            label: "Custom".to_string(),
+            base_url,
+            allow_base_url_edit: true,
+            models_endpoint: None,
+            protocol: ProviderProtocol::OpenAiChat,
+            local: false,
+            timeout_secs: 5,
+            max_retries: 2,
+        }
+    }
+
+    fn stream(base_url: String) -> (Result<ChatCompletion, String>, Vec<String>) {
+        let mut deltas = Vec::new();
+        let result = tauri::async_runtime::block_on(stream_chat_completion(
+            &provider(base_url),
+            String::new(),
+            &ChatRequest {
+                model: "test-model",
+                system: None,
+                prompt: "Fix this",
+                max_tokens: None,
+            },
+            |delta| deltas.push(delta.to_string()),
+        ));
+        (result, deltas)
+    }
+
+    fn chunk(content: &str) -> String {
+        format!(
+            "data: {{\"choices\":[{{\"delta\":{{\"content\":{:?}}}}}]}}\n\n",
+            content
+        )
+    }
+
+    #[test]
+    fn test_stream_chat_completion_delivers_deltas() {
+        let (base_url, request) = serve_once(vec![
+            "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nConnection: close\r\n\r\n"
+                .to_string(),
+            chunk("Hel"),
+            chunk("lo"),
+            "data: {\"choices\":[{\"delta\":{\"role\":\"assistant\"}}]}\n\n".to_string(),
+            chunk(" world"),
+            "data: {\"choices\":[],\"usage\":{\"prompt_tokens\":12,\"completion_tokens\":3}}\n\n"
+                .to_string(),
+            "data: [DONE]\n\n".to_string(),
+        ]);
+
+        let (result, deltas) = stream(base_url);
+
+        assert_eq!(
+            result,
+            Ok(ChatCompletion {
+                content: Some("Hello world".to_string()),
+                usage: Some(TokenUsage {
+                    prompt_tokens: 12,
+                    completion_tokens: 3,
+                }),
+            })
+        );
+        assert_eq!(deltas, vec!["Hel", "lo", " world"]);
+        let body = request.recv().unwrap();
+        assert!(body.contains("\"stream\":true"), "{}", body);
+    }
+
+    #[test]
+    fn test_stream_chat_completion_falls_back_to_json() {
+        let (base_url, _request) = serve_once(vec![json_response(
+            "200 OK",
+            r#"{"choices":[{"message":{"content":"Whole reply"}}]}"#,
+        )]);
+
+        let (result, deltas) = stream(base_url);
+
+        assert_eq!(
+            result.map(|completion| completion.content),
+            Ok(Some("Whole reply".to_string()))
+        );
+        assert_eq!(deltas, vec!["Whole reply"]);
+    }
+
+    #[test]
+    fn test_stream_chat_completion_reports_stream_errors() {
+        let (base_url, _request) = serve_once(vec![
+            "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nConnection: close\r\n\r\n"
+                .to_string(),
+            chunk("Partial"),
+            "data: {\"error\":{\"message\":\"overloaded\"}}\n\n".to_string(),
+        ]);
+
+        let (result, deltas) = stream(base_url);
+
+        assert!(result.unwrap_err().contains("overloaded"));
+        assert_eq!(deltas, vec!["Partial"]);
+    }
+
+    fn local_provider(base_url: String) -> PostProcessProvider {
+        PostProcessProvider {
+            id: "ollama".to_string(),
+            label: "Ollama".to_string(),
+            base_url,
+            allow_base_url_edit: true,
+            models_endpoint: Some("/api/tags".to_string()),
+            protocol: ProviderProtocol::OllamaChat,
+            local: true,
+            timeout_secs: 5,
+            max_retries: 0,
+        }
+    }
+
+    #[test]
+    fn test_parse_model_list_shapes() {
+        let openai = serde_json::json!({ "data": [{ "id": "gpt-4o" }, { "name": "other" }] });
+        assert_eq!(parse_model_list(&openai), vec!["gpt-4o", "other"]);
+
+        let ollama = serde_json::json!({
+            "models": [
+                { "name": "llama3.2:latest", "model": "llama3.2:latest", "size": 2019393189 },
+                { "model": "qwen2.5:7b" }
+            ]
+        });
+        assert_eq!(
+            parse_model_list(&ollama),
+            vec!["llama3.2:latest", "qwen2.5:7b"]
+        );
+
+        assert_eq!(
+            parse_model_list(&serde_json::json!(["a", "b"])),
+            vec!["a", "b"]
+        );
+    }
+
+    #[test]
+    fn test_check_health_reports_ready_server() {
+        let (base_url, _request) = serve_once(vec![json_response(
+            "200 OK",
+            r#"{"models":[{"name":"llama3.2:latest"}]}"#,
+        )]);
+
+        let health =
+            tauri::async_runtime::block_on(check_health(&local_provider(base_url), String::new()));
+
+        assert_eq!(health, ProviderHealth::Ready { models: 1 });
+    }
+
+    #[test]
+    fn test_local_server_not_running() {
+        let provider = local_provider(closed_port_url());
+
+        let health = tauri::async_runtime::block_on(check_health(&provider, String::new()));
+        assert_eq!(
+            health,
+            ProviderHealth::NotRunning {
+                message: server_not_running_message(&provider)
+            }
+        );
+
+        let error = tauri::async_runtime::block_on(fetch_models(&provider, String::new()));
+        assert_eq!(error, Err(server_not_running_message(&provider)));
+    }
+
+    fn send(provider: &PostProcessProvider) -> Result<Option<String>, String> {
+        tauri::async_runtime::block_on(send_chat_completion(
+            provider,
+            String::new(),
+            &ChatRequest {
+                model: "test-model",
+                system: None,
+                prompt: "Fix this",
+                max_tokens: None,
+            },
+        ))
+        .map(|completion| completion.content)
+    }
+
+    #[test]
+    fn test_retries_rate_limits_and_server_errors() {
+        let (base_url, requests) = serve(vec![
+            vec![json_response("429 Too Many Requests", "{}")],
+            vec![json_response("503 Service Unavailable", "{}")],
+            vec![json_response(
+                "200 OK",
+                r#"{"choices":[{"message":{"content":"Third time lucky"}}]}"#,
+            )],
+        ]);
+
+        let result = send(&provider(base_url));
+
+        assert_eq!(result, Ok(Some("Third time lucky".to_string())));
+        assert_eq!(requests.try_iter().count(), 3);
+    }
+
+    #[test]
+    fn test_retries_are_bounded() {
+        let (base_url, requests) = serve(vec![
+            vec![json_response("500 Internal Server Error", "{}")],
+            vec![json_response("500 Internal Server Error", "{}")],
+        ]);
+        let provider = PostProcessProvider {
+            max_retries: 1,
+            ..provider(base_url)
+        };
+
+        let error = send(&provider).unwrap_err();
+
+        assert!(error.contains("500"), "{}", error);
+        assert_eq!(requests.try_iter().count(), 2);
+    }
+
+    #[test]
+    fn test_client_errors_are_not_retried() {
+        let (base_url, requests) = serve(vec![vec![json_response(
+            "401 Unauthorized",
+            r#"{"error":"bad key"}"#,
+        )]]);
+
+        let error = send(&provider(base_url)).unwrap_err();
+
+        assert!(error.contains("401"), "{}", error);
+        assert_eq!(requests.try_iter().count(), 1);
+    }
+
+    #[test]
+    fn test_hung_provider_times_out() {
+        let provider = PostProcessProvider {
+            timeout_secs: 1,
+            ..provider(serve_silently(Duration::from_secs(5)))
+        };
+
+        let error = send(&provider).unwrap_err();
+
+        assert_eq!(error, "Custom did not respond in time (1s timeout)");
+    }
 }
@@ -0,0 +1,738 @@
+use crate::settings::ProviderProtocol;
+use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use specta::Type;
+
+/// Anthropic requires a token limit on every request
+const ANTHROPIC_DEFAULT_MAX_TOKENS: u32 = 4096;
+const ANTHROPIC_VERSION: &str = "2023-06-01";
+
+/// A single-turn chat request, independent of the provider's protocol
+#[derive(Debug, Clone, Copy)]
+pub struct ChatRequest<'a> {
+    pub model: &'a str,
+    pub system: Option<&'a str>,
+    pub prompt: &'a str,
+    pub max_tokens: Option<u32>,
+}
+
+/// Tokens a provider billed for one request
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct TokenUsage {
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+}
+
+impl TokenUsage {
+    /// Takes the counts `other` reports, keeping ours where it reports none.
+    /// Streams may report prompt and completion tokens in separate events.
+    pub fn merge(&mut self, other: TokenUsage) {
+        if other.prompt_tokens > 0 {
+            self.prompt_tokens = other.prompt_tokens;
+        }
+        if other.completion_tokens > 0 {
+            self.completion_tokens = other.completion_tokens;
+        }
+    }
+}
+
+/// A provider's reply to a chat request
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ChatCompletion {
+    pub content: Option<String>,
+    /// Absent when the provider doesn't report usage
+    pub usage: Option<TokenUsage>,
+}
+
+/// How a streamed response is framed on the wire
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamFormat {
+    /// `data:` lines separated by blank lines
+    ServerSentEvents,
+    /// One JSON object per line
+    JsonLines,
+}
+
+impl StreamFormat {
+    /// Whether a response with this content type is actually streamed
+    pub fn matches_content_type(self, content_type: &str) -> bool {
+        match self {
+            StreamFormat::ServerSentEvents => content_type.starts_with("text/event-stream"),
+            StreamFormat::JsonLines => content_type.contains("ndjson"),
+        }
+    }
+}
+
+/// Meaning of a single streamed event
+#[derive(Debug, PartialEq, Eq)]
+pub enum StreamEvent {
+    Delta(String),
+    Usage(TokenUsage),
+    /// The stream is over; some protocols report usage in the final event
+    Done(Option<TokenUsage>),
+    Ignore,
+}
+
+/// Maps chat requests and responses to and from one provider protocol
+pub trait ProtocolAdapter: Send + Sync {
+    /// URL of the chat endpoint under a provider's base URL
+    fn chat_url(&self, base_url: &str) -> String;
+
+    /// Adds the protocol's authentication headers
+    fn auth_headers(&self, headers: &mut HeaderMap, api_key: &str) -> Result<(), String>;
+
+    fn request_body(&self, request: &ChatRequest<'_>, stream: bool) -> Value;
+
+    /// Extracts the reply text and usage from a complete, non-streamed response
+    fn parse_response(&self, body: &str) -> Result<ChatCompletion, String>;
+
+    fn stream_format(&self) -> StreamFormat;
+
+    /// Interprets the data of one streamed event
+    fn parse_stream_event(&self, data: &str) -> Result<StreamEvent, String>;
+}
+
+pub fn adapter_for(protocol: ProviderProtocol) -> &'static dyn ProtocolAdapter {
+    match protocol {
+        ProviderProtocol::OpenAiChat => &OpenAiChatAdapter,
+        ProviderProtocol::AnthropicMessages => &AnthropicMessagesAdapter,
+        ProviderProtocol::OllamaChat => &OllamaChatAdapter,
+    }
+}
+
+fn bearer_auth(headers: &mut HeaderMap, api_key: &str) -> Result<(), String> {
+    if !api_key.is_empty() {
+        headers.insert(
+            AUTHORIZATION,
+            HeaderValue::from_str(&format!("Bearer {}", api_key))
+                .map_err(|e| format!("Invalid authorization header value: {}", e))?,
+        );
+    }
+    Ok(())
+}
+
+/// System and user messages in the shape OpenAI and Ollama share
+fn chat_messages(request: &ChatRequest<'_>) -> Vec<Value> {
+    let mut messages = Vec::with_capacity(2);
+    if let Some(system) = request.system {
+        messages.push(json!({ "role": "system", "content": system }));
+    }
+    messages.push(json!({ "role": "user", "content": request.prompt }));
+    messages
+}
+
+fn parse_json<'a, T: Deserialize<'a>>(body: &'a str) -> Result<T, String> {
+    serde_json::from_str(body).map_err(|e| format!("Failed to parse API response: {}", e))
+}
+
+/// Chat Completions API, also spoken by OpenRouter, Groq, Cerebras and most
+/// local servers
+struct OpenAiChatAdapter;
+
+#[derive(Debug, Deserialize)]
+struct OpenAiResponse {
+    choices: Vec<OpenAiChoice>,
+    #[serde(default)]
+    usage: Option<OpenAiUsage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAiUsage {
+    #[serde(default)]
+    prompt_tokens: u64,
+    #[serde(default)]
+    completion_tokens: u64,
+}
+
+impl From<OpenAiUsage> for TokenUsage {
+    fn from(usage: OpenAiUsage) -> Self {
+        TokenUsage {
+            prompt_tokens: usage.prompt_tokens,
+            completion_tokens: usage.completion_tokens,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAiChoice {
+    message: OpenAiMessage,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAiMessage {
+    content: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAiChunk {
+    #[serde(default)]
+    choices: Vec<OpenAiChunkChoice>,
+    #[serde(default)]
+    usage: Option<OpenAiUsage>,
+    #[serde(default)]
+    error: Option<Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAiChunkChoice {
+    #[serde(default)]
+    delta: OpenAiDelta,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct OpenAiDelta {
+    content: Option<String>,
+}
+
+impl ProtocolAdapter for OpenAiChatAdapter {
+    fn chat_url(&self, base_url: &str) -> String {
+        format!("{}/chat/completions", base_url.trim_end_matches('/'))
+    }
+
+    fn auth_headers(&self, headers: &mut HeaderMap, api_key: &str) -> Result<(), String> {
+        bearer_auth(headers, api_key)
+    }
+
+    fn request_body(&self, request: &ChatRequest<'_>, stream: bool) -> Value {
+        let mut body = json!({
+            "model": request.model,
+            "messages": chat_messages(request),
+        });
+        if let Some(max_tokens) = request.max_tokens {
+            body["max_tokens"] = json!(max_tokens);
+        }
+        if stream {
+            body["stream"] = json!(true);
+            // Usage is only reported in a final chunk when asked for
+            body["stream_options"] = json!({ "include_usage": true });
+        }
+        body
+    }
+
+    fn parse_response(&self, body: &str) -> Result<ChatCompletion, String> {
+        let response: OpenAiResponse = parse_json(body)?;
+        Ok(ChatCompletion {
+            content: response
+                .choices
+                .into_iter()
+                .next()
+                .and_then(|choice| choice.message.content),
+            usage: response.usage.map(TokenUsage::from),
+        })
+    }
+
+    fn stream_format(&self) -> StreamFormat {
+        StreamFormat::ServerSentEvents
+    }
+
+    fn parse_stream_event(&self, data: &str) -> Result<StreamEvent, String> {
+        if data.trim() == "[DONE]" {
+            return Ok(StreamEvent::Done(None));
+        }
+
+        let chunk: OpenAiChunk = serde_json::from_str(data)
+            .map_err(|e| format!("Failed to parse streamed chunk: {}", e))?;
+        if let Some(error) = chunk.error {
+            return Err(format!(
+                "Provider reported an error while streaming: {}",
+                error
+            ));
+        }
+
+        let delta = chunk
+            .choices
+            .into_iter()
+            .next()
+            .and_then(|choice| choice.delta.content)
+            .filter(|content| !content.is_empty());
+        // Usage comes in a final chunk without choices
+        Ok(match (delta, chunk.usage) {
+            (Some(delta), _) => StreamEvent::Delta(delta),
+            (None, Some(usage)) => StreamEvent::Usage(usage.into()),
+            (None, None) => StreamEvent::Ignore,
+        })
+    }
+}
+
+/// Anthropic Messages API
+struct AnthropicMessagesAdapter;
+
+#[derive(Debug, Deserialize)]
+struct AnthropicResponse {
+    content: Vec<AnthropicContentBlock>,
+    #[serde(default)]
+    usage: Option<AnthropicUsage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AnthropicUsage {
+    #[serde(default)]
+    input_tokens: u64,
+    #[serde(default)]
+    output_tokens: u64,
+}
+
+impl From<AnthropicUsage> for TokenUsage {
+    fn from(usage: AnthropicUsage) -> Self {
+        TokenUsage {
+            prompt_tokens: usage.input_tokens,
+            completion_tokens: usage.output_tokens,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct AnthropicContentBlock {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    text: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AnthropicStreamEvent {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    delta: Option<AnthropicDelta>,
+    /// Sent with `message_start`
+    #[serde(default)]
+    message: Option<AnthropicStreamMessage>,
+    /// Sent with `message_delta`
+    #[serde(default)]
+    usage: Option<AnthropicUsage>,
+    #[serde(default)]
+    error: Option<Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AnthropicStreamMessage {
+    #[serde(default)]
+    usage: Option<AnthropicUsage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AnthropicDelta {
+    #[serde(default)]
+    text: Option<String>,
+}
+
+impl ProtocolAdapter for AnthropicMessagesAdapter {
+    fn chat_url(&self, base_url: &str) -> String {
+        format!("{}/messages", base_url.trim_end_matches('/'))
+    }
+
+    fn auth_headers(&self, headers: &mut HeaderMap, api_key: &str) -> Result<(), String> {
+        headers.insert(
+            "anthropic-version",
+            HeaderValue::from_static(ANTHROPIC_VERSION),
+        );
+        if !api_key.is_empty() {
+            headers.insert(
+                "x-api-key",
+                HeaderValue::from_str(api_key)
+                    .map_err(|e| format!("Invalid API key header value: {}", e))?,
+            );
+        }
+        Ok(())
+    }
+
+    fn request_body(&self, request: &ChatRequest<'_>, stream: bool) -> Value {
+        let mut body = json!({
+            "model": request.model,
+            "max_tokens": request.max_tokens.unwrap_or(ANTHROPIC_DEFAULT_MAX_TOKENS),
+            "messages": [{ "role": "user", "content": request.prompt }],
+        });
+        if let Some(system) = request.system {
+            body["system"] = json!(system);
+        }
+        if stream {
+            body["stream"] = json!(true);
+        }
+        body
+    }
+
+    fn parse_response(&self, body: &str) -> Result<ChatCompletion, String> {
+        let response: AnthropicResponse = parse_json(body)?;
+        let text: String = response
+            .content
+            .into_iter()
+            .filter(|block| block.kind == "text")
+            .filter_map(|block| block.text)
+            .collect();
+        Ok(ChatCompletion {
+            content: (!text.is_empty()).then_some(text),
+            usage: response.usage.map(TokenUsage::from),
+        })
+    }
+
+    fn stream_format(&self) -> StreamFormat {
+        StreamFormat::ServerSentEvents
+    }
+
+    fn parse_stream_event(&self, data: &str) -> Result<StreamEvent, String> {
+        let event: AnthropicStreamEvent = serde_json::from_str(data)
+            .map_err(|e| format!("Failed to parse streamed event: {}", e))?;
+
+        match event.kind.as_str() {
+            "content_block_delta" => Ok(event
+                .delta
+                .and_then(|delta| delta.text)
+                .filter(|text| !text.is_empty())
+                .map_or(StreamEvent::Ignore, StreamEvent::Delta)),
+            "message_start" | "message_delta" => Ok(event
+                .usage
+                .or(event.message.and_then(|message| message.usage))
+                .map_or(StreamEvent::Ignore, |usage| {
+                    StreamEvent::Usage(usage.into())
+                })),
+            "message_stop" => Ok(StreamEvent::Done(None)),
+            "error" => Err(format!(
+                "Provider reported an error while streaming: {}",
+                event.error.unwrap_or(Value::Null)
+            )),
+            _ => Ok(StreamEvent::Ignore),
+        }
+    }
+}
+
+/// Ollama's native chat API
+struct OllamaChatAdapter;
+
+#[derive(Debug, Deserialize)]
+struct OllamaResponse {
+    #[serde(default)]
+    message: Option<OllamaMessage>,
+    #[serde(default)]
+    done: bool,
+    /// Counts are only sent with the final response
+    #[serde(default)]
+    prompt_eval_count: Option<u64>,
+    #[serde(default)]
+    eval_count: Option<u64>,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+impl OllamaResponse {
+    fn usage(&self) -> Option<TokenUsage> {
+        if self.prompt_eval_count.is_none() && self.eval_count.is_none() {
+            return None;
+        }
+        Some(TokenUsage {
+            prompt_tokens: self.prompt_eval_count.unwrap_or(0),
+            completion_tokens: self.eval_count.unwrap_or(0),
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct OllamaMessage {
+    #[serde(default)]
+    content: String,
+}
+
+impl ProtocolAdapter for OllamaChatAdapter {
+    fn chat_url(&self, base_url: &str) -> String {
+        format!("{}/api/chat", base_url.trim_end_matches('/'))
+    }
+
+    fn auth_headers(&self, headers: &mut HeaderMap, api_key: &str) -> Result<(), String> {
+        bearer_auth(headers, api_key)
+    }
+
+    fn request_body(&self, request: &ChatRequest<'_>, stream: bool) -> Value {
+        // Ollama streams unless told otherwise
+        let mut body = json!({
+            "model": request.model,
+            "messages": chat_messages(request),
+            "stream": stream,
+        });
+        if let Some(max_tokens) = request.max_tokens {
+            body["options"] = json!({ "num_predict": max_tokens });
+        }
+        body
+    }
+
+    fn parse_response(&self, body: &str) -> Result<ChatCompletion, String> {
+        let response: OllamaResponse = parse_json(body)?;
+        if let Some(error) = response.error {
+            return Err(format!("Ollama reported an error: {}", error));
+        }
+        Ok(ChatCompletion {
+            usage: response.usage(),
+            content: response
+                .message
+                .map(|message| message.content)
+                .filter(|content| !content.is_empty()),
+        })
+    }
+
+    fn stream_format(&self) -> StreamFormat {
+        StreamFormat::JsonLines
+    }
+
+    fn parse_stream_event(&self, data: &str) -> Result<StreamEvent, String> {
+        let response: OllamaResponse = serde_json::from_str(data)
+            .map_err(|e| format!("Failed to parse streamed chunk: {}", e))?;
+        if let Some(error) = response.error {
+            return Err(format!("Ollama reported an error: {}", error));
+        }
+        if response.done {
+            return Ok(StreamEvent::Done(response.usage()));
+        }
+        Ok(response
+            .message
+            .map(|message| message.content)
+            .filter(|content| !content.is_empty())
+            .map_or(StreamEvent::Ignore, StreamEvent::Delta))
+    }
+}
+
+/// Splits a streamed response into the data of its events. Bytes are buffered
+/// until a full line arrives, so chunks may split lines or characters.
+pub struct StreamParser {
+    format: StreamFormat,
+    buffer: Vec<u8>,
+    data: Vec<String>,
+}
+
+impl StreamParser {
+    pub fn new(format: StreamFormat) -> Self {
+        Self {
+            format,
+            buffer: Vec::new(),
+            data: Vec::new(),
+        }
+    }
+
+    /// Feeds the next bytes of the stream and returns the data of every event
+    /// they complete
+    pub fn push(&mut self, bytes: &[u8]) -> Vec<String> {
+        self.buffer.extend_from_slice(bytes);
+
+        let mut events = Vec::new();
+        while let Some(end) = self.buffer.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = self.buffer.drain(..=end).collect();
+            let line = String::from_utf8_lossy(&line);
+            self.push_line(line.trim_end_matches(['\n', '\r']), &mut events);
+        }
+        events
+    }
+
+    /// Returns the data of an event the stream ended without terminating
+    pub fn finish(&mut self) -> Option<String> {
+        let mut events = Vec::new();
+        if !self.buffer.is_empty() {
+            let line = String::from_utf8_lossy(&self.buffer).into_owned();
+            self.buffer.clear();
+            self.push_line(line.trim_end_matches('\r'), &mut events);
+        }
+        self.push_line("", &mut events);
+        events.pop()
+    }
+
+    fn push_line(&mut self, line: &str, events: &mut Vec<String>) {
+        match self.format {
+            StreamFormat::JsonLines => {
+                if !line.trim().is_empty() {
+                    events.push(line.to_string());
+                }
+            }
+            StreamFormat::ServerSentEvents => {
+                if line.is_empty() {
+                    if !self.data.is_empty() {
+                        events.push(self.data.join("\n"));
+                        self.data.clear();
+                    }
+                } else if let Some(value) = line.strip_prefix("data:") {
+                    self.data
+                        .push(value.strip_prefix(' ').unwrap_or(value).to_string());
+                }
+                // Comments and the event, id and retry fields carry nothing we need
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const REQUEST: ChatRequest<'static> = ChatRequest {
+        model: "",
+        system: Some("You clean up dictated text."),
+        prompt: "um so the meeting is at three",
+        max_tokens: Some(256),
+    };
+
+    fn fixture_json(fixture: &str) -> Value {
+        serde_json::from_str(fixture).unwrap()
+    }
+
+    /// Feeds a recorded stream through the parser in small chunks and collects
+    /// the text, checking that the stream signals its end
+    fn replay_stream(adapter: &dyn ProtocolAdapter, fixture: &str) -> (String, TokenUsage) {
+        let mut parser = StreamParser::new(adapter.stream_format());
+        let mut text = String::new();
+        let mut usage = TokenUsage::default();
+        let mut done = false;
+        for chunk in fixture.as_bytes().chunks(7) {
+            for data in parser.push(chunk) {
+                match adapter.parse_stream_event(&data).unwrap() {
+                    StreamEvent::Delta(delta) => text.push_str(&delta),
+                    StreamEvent::Usage(reported) => usage.merge(reported),
+                    StreamEvent::Done(reported) => {
+                        usage.merge(reported.unwrap_or_default());
+                        done = true;
+                    }
+                    StreamEvent::Ignore => {}
+                }
+            }
+        }
+        assert!(done, "stream never signalled completion");
+        (text, usage)
+    }
+
+    fn usage(prompt_tokens: u64, completion_tokens: u64) -> TokenUsage {
+        TokenUsage {
+            prompt_tokens,
+            completion_tokens,
+        }
+    }
+
+    #[test]
+    fn test_openai_contract() {
+        let adapter = adapter_for(ProviderProtocol::OpenAiChat);
+        let request = ChatRequest {
+            model: "gpt-4o-mini",
+            ..REQUEST
+        };
+
+        assert_eq!(
+            adapter.chat_url("https://api.openai.com/v1/"),
+            "https://api.openai.com/v1/chat/completions"
+        );
+        assert_eq!(
+            adapter.request_body(&request, false),
+            fixture_json(include_str!("fixtures/openai_request.json"))
+        );
+        let body = adapter.request_body(&request, true);
+        assert_eq!(body["stream"], json!(true));
+        assert_eq!(body["stream_options"]["include_usage"], json!(true));
+        assert_eq!(
+            adapter
+                .parse_response(include_str!("fixtures/openai_response.json"))
+                .unwrap(),
+            ChatCompletion {
+                content: Some("The meeting is at 3.".to_string()),
+                usage: Some(usage(27, 7)),
+            }
+        );
+        assert_eq!(
+            replay_stream(adapter, include_str!("fixtures/openai_stream.txt")),
+            ("The meeting is at 3.".to_string(), usage(27, 7))
+        );
+    }
+
+    #[test]
+    fn test_anthropic_contract() {
+        let adapter = adapter_for(ProviderProtocol::AnthropicMessages);
+        let request = ChatRequest {
+            model: "claude-3-5-haiku-latest",
+            ..REQUEST
+        };
+
+        assert_eq!(
+            adapter.chat_url("https://api.anthropic.com/v1"),
+            "https://api.anthropic.com/v1/messages"
+        );
+        assert_eq!(
+            adapter.request_body(&request, false),
+            fixture_json(include_str!("fixtures/anthropic_request.json"))
+        );
+        let without_limit = ChatRequest {
+            system: None,
+            max_tokens: None,
+            ..request
+        };
+        let body = adapter.request_body(&without_limit, true);
+        assert_eq!(body["max_tokens"], json!(ANTHROPIC_DEFAULT_MAX_TOKENS));
+        assert!(body.get("system").is_none());
+        assert_eq!(body["stream"], json!(true));
+
+        assert_eq!(
+            adapter
+                .parse_response(include_str!("fixtures/anthropic_response.json"))
+                .unwrap(),
+            ChatCompletion {
+                content: Some("The meeting is at 3.".to_string()),
+                usage: Some(usage(24, 10)),
+            }
+        );
+        assert_eq!(
+            replay_stream(adapter, include_str!("fixtures/anthropic_stream.txt")),
+            ("The meeting is at 3.".to_string(), usage(24, 10))
+        );
+
+        let mut headers = HeaderMap::new();
+        adapter.auth_headers(&mut headers, "sk-ant-test").unwrap();
+        assert_eq!(headers["x-api-key"], "sk-ant-test");
+        assert_eq!(headers["anthropic-version"], ANTHROPIC_VERSION);
+        assert!(headers.get(AUTHORIZATION).is_none());
+    }
+
+    #[test]
+    fn test_ollama_contract() {
+        let adapter = adapter_for(ProviderProtocol::OllamaChat);
+        let request = ChatRequest {
+            model: "llama3.2",
+            ..REQUEST
+        };
+
+        assert_eq!(
+            adapter.chat_url("http://localhost:11434"),
+            "http://localhost:11434/api/chat"
+        );
+        assert_eq!(
+            adapter.request_body(&request, false),
+            fixture_json(include_str!("fixtures/ollama_request.json"))
+        );
+        assert_eq!(
+            adapter
+                .parse_response(include_str!("fixtures/ollama_response.json"))
+                .unwrap(),
+            ChatCompletion {
+                content: Some("The meeting is at 3.".to_string()),
+                usage: Some(usage(31, 8)),
+            }
+        );
+        assert_eq!(
+            replay_stream(adapter, include_str!("fixtures/ollama_stream.ndjson")),
+            ("The meeting is at 3.".to_string(), usage(31, 8))
+        );
+        assert!(adapter
+            .parse_response(r#"{"error":"model \"llama9\" not found"}"#)
+            .unwrap_err()
+            .contains("not found"));
+    }
+
+    #[test]
+    fn test_stream_parser_handles_split_lines() {
+        let mut parser = StreamParser::new(StreamFormat::ServerSentEvents);
+        assert!(parser.push(b": keep-alive\n\ndata: {\"a\"").is_empty());
+        assert_eq!(parser.push(b":1}\r\n\r\ndata: x\n"), vec!["{\"a\":1}"]);
+        assert_eq!(parser.push(b"data: y\n\n"), vec!["x\ny"]);
+        // A multi-byte character split across chunks survives
+        let bytes = "data: \u{e9}\n\n".as_bytes();
+        assert!(parser.push(&bytes[..7]).is_empty());
+        assert_eq!(parser.push(&bytes[7..]), vec!["\u{e9}"]);
+        assert!(parser.push(b"data: [DONE]").is_empty());
+        assert_eq!(parser.finish().as_deref(), Some("[DONE]"));
+
+        let mut parser = StreamParser::new(StreamFormat::JsonLines);
+        assert_eq!(parser.push(b"{\"a\":1}\n\n{\"b\""), vec!["{\"a\":1}"]);
+        assert_eq!(parser.finish().as_deref(), Some("{\"b\""));
+    }
+}
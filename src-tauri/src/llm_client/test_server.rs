@@ -0,0 +1,99 @@
+//! Stub HTTP server for exercising provider requests against recorded replies
+
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::mpsc;
+use std::time::Duration;
+
+/// Serves one request per entry of `connections` on a local port, in order.
+/// Each response is written in the given pieces with a pause between them.
+/// Returns the base URL and a receiver for the request bodies.
+pub fn serve(connections: Vec<Vec<String>>) -> (String, mpsc::Receiver<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+    let (sender, receiver) = mpsc::channel();
+
+    std::thread::spawn(move || {
+        for response in connections {
+            let (mut stream, _) = listener.accept().unwrap();
+            if let Some(body) = read_request(&mut stream) {
+                let _ = sender.send(body);
+            }
+
+            for piece in response {
+                if stream.write_all(piece.as_bytes()).is_err() {
+                    break;
+                }
+                let _ = stream.flush();
+                std::thread::sleep(Duration::from_millis(10));
+            }
+        }
+    });
+
+    (base_url, receiver)
+}
+
+/// Serves a single request, see `serve`
+pub fn serve_once(response: Vec<String>) -> (String, mpsc::Receiver<String>) {
+    serve(vec![response])
+}
+
+/// Accepts a connection and never answers it
+pub fn serve_silently(hold_for: Duration) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+
+    std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let _ = read_request(&mut stream);
+        std::thread::sleep(hold_for);
+    });
+
+    base_url
+}
+
+/// A base URL nothing is listening on
+pub fn closed_port_url() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    format!("http://{}", listener.local_addr().unwrap())
+}
+
+/// A complete JSON response with the given status line, e.g. `200 OK`
+pub fn json_response(status: &str, body: &str) -> String {
+    format!(
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nRetry-After: 0\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    )
+}
+
+/// Reads one request and returns its body
+fn read_request(stream: &mut TcpStream) -> Option<String> {
+    let mut request = Vec::new();
+    let mut buf = [0u8; 4096];
+    loop {
+        let read = stream.read(&mut buf).ok()?;
+        request.extend_from_slice(&buf[..read]);
+        let text = String::from_utf8_lossy(&request);
+        if let Some(header_end) = text.find("\r\n\r\n") {
+            let content_length = text[..header_end]
+                .lines()
+                .find_map(|line| {
+                    let (name, value) = line.split_once(':')?;
+                    if name.eq_ignore_ascii_case("content-length") {
+                        value.trim().parse::<usize>().ok()
+                    } else {
+                        None
+                    }
+                })
+                .unwrap_or(0);
+            if request.len() >= header_end + 4 + content_length {
+                return Some(text[header_end + 4..].to_string());
+            }
+        }
+        if read == 0 {
+            return None;
+        }
+    }
+}
@@ -421,6 +421,14 @@ impl AudioRecordingManager {
         )
     }
 
+    /// Whether the recording in progress was started from `binding_id`
+    pub fn is_recording_for(&self, binding_id: &str) -> bool {
+        matches!(
+            &*self.state.lock().unwrap(),
+            RecordingState::Recording { binding_id: active } if active == binding_id
+        )
+    }
+
     /// Cancel any ongoing recording without returning audio samples
     pub fn cancel_recording(&self) {
         let mut state = self.state.lock().unwrap();
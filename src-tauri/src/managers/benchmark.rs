@@ -0,0 +1,208 @@
+use anyhow::Result;
+use log::{debug, info, warn};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+use crate::managers::model::ModelManager;
+use crate::managers::transcription::TranscriptionManager;
+
+/// Sample rate of the audio fed to the engines (see `save_wav_file`)
+const SAMPLE_RATE: u64 = 16000;
+
+/// Measured performance of a model on this machine
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct ModelBenchmark {
+    pub model_id: String,
+    pub load_time_ms: u64,
+    pub audio_duration_ms: u64,
+    pub transcription_time_ms: u64,
+    /// Transcription time divided by audio duration; below 1.0 is faster than real time
+    pub real_time_factor: f32,
+    /// Peak resident memory growth while loading and running the model, if measurable
+    pub peak_memory_mb: Option<u64>,
+    pub timestamp: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct BenchmarkProgress {
+    pub model_id: String,
+    pub index: usize,
+    pub total: usize,
+    pub stage: String,
+    pub error: Option<String>,
+}
+
+pub fn load_benchmarks(path: &Path) -> HashMap<String, ModelBenchmark> {
+    if !path.exists() {
+        return HashMap::new();
+    }
+
+    match fs::read_to_string(path)
+        .map_err(anyhow::Error::from)
+        .and_then(|content| serde_json::from_str(&content).map_err(anyhow::Error::from))
+    {
+        Ok(benchmarks) => benchmarks,
+        Err(e) => {
+            warn!("Failed to read model benchmarks from {:?}: {}", path, e);
+            HashMap::new()
+        }
+    }
+}
+
+pub fn save_benchmarks(path: &Path, benchmarks: &HashMap<String, ModelBenchmark>) -> Result<()> {
+    let content = serde_json::to_string_pretty(benchmarks)?;
+    fs::write(path, content)?;
+    debug!("Saved {} model benchmarks to {:?}", benchmarks.len(), path);
+    Ok(())
+}
+
+/// Current resident set size of this process in bytes.
+#[cfg(target_os = "linux")]
+fn current_rss_bytes() -> Option<u64> {
+    let status = fs::read_to_string("/proc/self/status").ok()?;
+    status
+        .lines()
+        .find(|line| line.starts_with("VmRSS:"))
+        .and_then(|line| line.split_whitespace().nth(1))
+        .and_then(|kb| kb.parse::<u64>().ok())
+        .map(|kb| kb * 1024)
+}
+
+/// Memory sampling is only implemented on Linux for now.
+#[cfg(not(target_os = "linux"))]
+fn current_rss_bytes() -> Option<u64> {
+    None
+}
+
+/// Polls the process RSS on a background thread and keeps the highest value seen
+struct MemorySampler {
+    baseline: u64,
+    peak: Arc<AtomicU64>,
+    stop: Arc<AtomicBool>,
+    handle: Option<thread::JoinHandle<()>>,
+}
+
+impl MemorySampler {
+    fn start() -> Option<Self> {
+        let baseline = current_rss_bytes()?;
+        let peak = Arc::new(AtomicU64::new(baseline));
+        let stop = Arc::new(AtomicBool::new(false));
+
+        let handle = {
+            let peak = peak.clone();
+            let stop = stop.clone();
+            thread::spawn(move || {
+                while !stop.load(Ordering::Relaxed) {
+                    if let Some(rss) = current_rss_bytes() {
+                        peak.fetch_max(rss, Ordering::Relaxed);
+                    }
+                    thread::sleep(Duration::from_millis(50));
+                }
+            })
+        };
+
+        Some(Self {
+            baseline,
+            peak,
+            stop,
+            handle: Some(handle),
+        })
+    }
+
+    /// Stops sampling and returns the peak growth over the baseline in MB
+    fn finish(mut self) -> u64 {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+        if let Some(rss) = current_rss_bytes() {
+            self.peak.fetch_max(rss, Ordering::Relaxed);
+        }
+        self.peak
+            .load(Ordering::Relaxed)
+            .saturating_sub(self.baseline)
+            / (1024 * 1024)
+    }
+}
+
+/// Benchmarks each model over the reference audio, emitting `model-benchmark-progress`
+/// events as it goes. Models that fail are skipped; their error is reported in the
+/// progress event. Successful results are persisted through the model manager.
+pub fn run_benchmarks(
+    app_handle: &AppHandle,
+    model_manager: &ModelManager,
+    transcription_manager: &TranscriptionManager,
+    model_ids: &[String],
+    audio: &[f32],
+) -> Vec<ModelBenchmark> {
+    let audio_duration_ms = audio.len() as u64 * 1000 / SAMPLE_RATE;
+    let total = model_ids.len();
+    let mut results = Vec::new();
+
+    for (index, model_id) in model_ids.iter().enumerate() {
+        let emit_progress = |stage: &str, error: Option<String>| {
+            let _ = app_handle.emit(
+                "model-benchmark-progress",
+                BenchmarkProgress {
+                    model_id: model_id.clone(),
+                    index,
+                    total,
+                    stage: stage.to_string(),
+                    error,
+                },
+            );
+        };
+
+        emit_progress("started", None);
+        info!("Benchmarking model {} ({}/{})", model_id, index + 1, total);
+
+        let sampler = MemorySampler::start();
+        let timing = transcription_manager.benchmark_model(model_id, audio.to_vec());
+        let peak_memory_mb = sampler.map(|s| s.finish());
+
+        match timing {
+            Ok((load_time, transcription_time)) => {
+                let real_time_factor = if audio_duration_ms > 0 {
+                    transcription_time.as_millis() as f32 / audio_duration_ms as f32
+                } else {
+                    0.0
+                };
+
+                let benchmark = ModelBenchmark {
+                    model_id: model_id.clone(),
+                    load_time_ms: load_time.as_millis() as u64,
+                    audio_duration_ms,
+                    transcription_time_ms: transcription_time.as_millis() as u64,
+                    real_time_factor,
+                    peak_memory_mb,
+                    timestamp: chrono::Utc::now().timestamp(),
+                };
+
+                info!(
+                    "Benchmark for {}: load {}ms, RTF {:.3}",
+                    model_id, benchmark.load_time_ms, benchmark.real_time_factor
+                );
+
+                if let Err(e) = model_manager.record_benchmark(benchmark.clone()) {
+                    warn!("Failed to persist benchmark for {}: {}", model_id, e);
+                }
+                emit_progress("completed", None);
+                results.push(benchmark);
+            }
+            Err(e) => {
+                warn!("Benchmark failed for {}: {}", model_id, e);
+                emit_progress("failed", Some(e.to_string()));
+            }
+        }
+    }
+
+    results
+}
@@ -10,6 +10,7 @@ use std::path::PathBuf;
 use tauri::{AppHandle, Emitter, Manager};
 
 use crate::audio_toolkit::save_wav_file;
+use crate::llm_client::{AttemptOutcome, LlmAttempt, TokenUsage};
 
 /// Database migrations for transcription history.
 /// Each migration is applied in order. The library tracks which migrations
@@ -31,6 +32,46 @@ static MIGRATIONS: &[M] = &[
     ),
     M::up("ALTER TABLE transcription_history ADD COLUMN post_processed_text TEXT;"),
     M::up("ALTER TABLE transcription_history ADD COLUMN post_process_prompt TEXT;"),
+    M::up(
+        "CREATE TABLE IF NOT EXISTS transcription_revisions (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            history_id INTEGER NOT NULL,
+            timestamp INTEGER NOT NULL,
+            model_id TEXT,
+            language TEXT,
+            transcription_text TEXT NOT NULL,
+            post_processed_text TEXT,
+            post_process_prompt TEXT
+        );",
+    ),
+    M::up("ALTER TABLE transcription_history ADD COLUMN detected_language TEXT;"),
+    M::up("ALTER TABLE transcription_history ADD COLUMN translated_text TEXT;"),
+    M::up("ALTER TABLE transcription_history ADD COLUMN translation_language TEXT;"),
+    M::up("ALTER TABLE transcription_history ADD COLUMN post_process_attempts TEXT;"),
+    // LLM requests for the usage totals, kept when history entries are pruned.
+    // Starts out with the attempts still recorded in history.
+    M::up(
+        "CREATE TABLE IF NOT EXISTS llm_usage (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            timestamp INTEGER NOT NULL,
+            provider_id TEXT NOT NULL,
+            model TEXT NOT NULL,
+            duration_ms INTEGER NOT NULL,
+            outcome TEXT NOT NULL,
+            prompt_tokens INTEGER,
+            completion_tokens INTEGER
+        );
+        INSERT INTO llm_usage (timestamp, provider_id, model, duration_ms, outcome, prompt_tokens, completion_tokens)
+        SELECT h.timestamp,
+            json_extract(a.value, '$.provider_id'),
+            json_extract(a.value, '$.model'),
+            json_extract(a.value, '$.duration_ms'),
+            json_extract(a.value, '$.outcome'),
+            json_extract(a.value, '$.usage.prompt_tokens'),
+            json_extract(a.value, '$.usage.completion_tokens')
+        FROM transcription_history h, json_each(h.post_process_attempts) a
+        WHERE h.post_process_attempts IS NOT NULL;",
+    ),
 ];
 
 #[derive(Clone, Debug, Serialize, Deserialize, Type)]
@@ -43,6 +84,135 @@ pub struct HistoryEntry {
     pub transcription_text: String,
     pub post_processed_text: Option<String>,
     pub post_process_prompt: Option<String>,
+    /// Language identified automatically before transcription, if detection ran
+    pub detected_language: Option<String>,
+    /// Final text translated into `translation_language`, if translation ran
+    pub translated_text: Option<String>,
+    pub translation_language: Option<String>,
+    /// Providers asked during post-processing and translation, in order
+    pub post_process_attempts: Vec<LlmAttempt>,
+}
+
+impl HistoryEntry {
+    /// Text that was pasted for this entry
+    pub fn final_text(&self) -> &str {
+        self.translated_text
+            .as_deref()
+            .or(self.post_processed_text.as_deref())
+            .unwrap_or(&self.transcription_text)
+    }
+}
+
+/// Texts produced by one dictation, as saved alongside its recording
+#[derive(Clone, Debug, Default)]
+pub struct NewHistoryEntry {
+    pub transcription_text: String,
+    pub post_processed_text: Option<String>,
+    pub post_process_prompt: Option<String>,
+    pub detected_language: Option<String>,
+    pub translated_text: Option<String>,
+    pub translation_language: Option<String>,
+    pub post_process_attempts: Vec<LlmAttempt>,
+}
+
+/// A re-transcription of an existing history entry, e.g. with a different
+/// model or language. The original entry is left untouched.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Type)]
+pub struct HistoryRevision {
+    pub id: i64,
+    pub history_id: i64,
+    pub timestamp: i64,
+    pub model_id: Option<String>,
+    pub language: Option<String>,
+    pub transcription_text: String,
+    pub post_processed_text: Option<String>,
+    pub post_process_prompt: Option<String>,
+}
+
+/// Provider attempts are stored as JSON; entries without any store NULL
+fn attempts_to_column(attempts: &[LlmAttempt]) -> Result<Option<String>> {
+    if attempts.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(serde_json::to_string(attempts)?))
+}
+
+fn attempts_from_column(value: Option<String>) -> Vec<LlmAttempt> {
+    value
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+/// Reads an `llm_usage` row as its timestamp and attempt
+fn llm_usage_from_row(row: &rusqlite::Row) -> rusqlite::Result<(i64, LlmAttempt)> {
+    let prompt_tokens: Option<i64> = row.get("prompt_tokens")?;
+    let completion_tokens: Option<i64> = row.get("completion_tokens")?;
+    let usage = match (prompt_tokens, completion_tokens) {
+        (None, None) => None,
+        (prompt_tokens, completion_tokens) => Some(TokenUsage {
+            prompt_tokens: prompt_tokens.unwrap_or_default() as u64,
+            completion_tokens: completion_tokens.unwrap_or_default() as u64,
+        }),
+    };
+    let outcome: String = row.get("outcome")?;
+    Ok((
+        row.get("timestamp")?,
+        LlmAttempt {
+            provider_id: row.get("provider_id")?,
+            model: row.get("model")?,
+            duration_ms: row.get::<_, i64>("duration_ms")? as u64,
+            outcome: serde_json::from_str(&outcome).unwrap_or(AttemptOutcome::Succeeded),
+            usage,
+        },
+    ))
+}
+
+/// Inserts `revision` and returns it with the id it was stored under
+fn insert_revision(conn: &Connection, revision: HistoryRevision) -> Result<HistoryRevision> {
+    conn.execute(
+        "INSERT INTO transcription_revisions (history_id, timestamp, model_id, language, transcription_text, post_processed_text, post_process_prompt) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        params![
+            revision.history_id,
+            revision.timestamp,
+            revision.model_id,
+            revision.language,
+            revision.transcription_text,
+            revision.post_processed_text,
+            revision.post_process_prompt
+        ],
+    )?;
+    Ok(HistoryRevision {
+        id: conn.last_insert_rowid(),
+        ..revision
+    })
+}
+
+/// Revisions of an entry, newest first
+fn revisions_for(conn: &Connection, history_id: i64) -> Result<Vec<HistoryRevision>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, history_id, timestamp, model_id, language, transcription_text, post_processed_text, post_process_prompt
+         FROM transcription_revisions WHERE history_id = ?1 ORDER BY timestamp DESC, id DESC",
+    )?;
+
+    let rows = stmt.query_map([history_id], |row| {
+        Ok(HistoryRevision {
+            id: row.get("id")?,
+            history_id: row.get("history_id")?,
+            timestamp: row.get("timestamp")?,
+            model_id: row.get("model_id")?,
+            language: row.get("language")?,
+            transcription_text: row.get("transcription_text")?,
+            post_processed_text: row.get("post_processed_text")?,
+            post_process_prompt: row.get("post_process_prompt")?,
+        })
+    })?;
+
+    let mut revisions = Vec::new();
+    for row in rows {
+        revisions.push(row?);
+    }
+
+    Ok(revisions)
 }
 
 pub struct HistoryManager {
@@ -180,9 +350,7 @@ impl HistoryManager {
     pub async fn save_transcription(
         &self,
         audio_samples: Vec<f32>,
-        transcription_text: String,
-        post_processed_text: Option<String>,
-        post_process_prompt: Option<String>,
+        entry: NewHistoryEntry,
     ) -> Result<()> {
         let timestamp = Utc::now().timestamp();
         let file_name = format!("handy-{}.wav", timestamp);
@@ -193,14 +361,7 @@ impl HistoryManager {
         save_wav_file(file_path, &audio_samples).await?;
 
         // Save to database
-        self.save_to_database(
-            file_name,
-            timestamp,
-            title,
-            transcription_text,
-            post_processed_text,
-            post_process_prompt,
-        )?;
+        self.save_to_database(file_name, timestamp, title, entry)?;
 
         // Clean up old entries
         self.cleanup_old_entries()?;
@@ -218,14 +379,24 @@ impl HistoryManager {
         file_name: String,
         timestamp: i64,
         title: String,
-        transcription_text: String,
-        post_processed_text: Option<String>,
-        post_process_prompt: Option<String>,
+        entry: NewHistoryEntry,
     ) -> Result<()> {
         let conn = self.get_connection()?;
         conn.execute(
-            "INSERT INTO transcription_history (file_name, timestamp, saved, title, transcription_text, post_processed_text, post_process_prompt) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
-            params![file_name, timestamp, false, title, transcription_text, post_processed_text, post_process_prompt],
+            "INSERT INTO transcription_history (file_name, timestamp, saved, title, transcription_text, post_processed_text, post_process_prompt, detected_language, translated_text, translation_language, post_process_attempts) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            params![
+                file_name,
+                timestamp,
+                false,
+                title,
+                entry.transcription_text,
+                entry.post_processed_text,
+                entry.post_process_prompt,
+                entry.detected_language,
+                entry.translated_text,
+                entry.translation_language,
+                attempts_to_column(&entry.post_process_attempts)?
+            ],
         )?;
 
         debug!("Saved transcription to database");
@@ -261,11 +432,15 @@ impl HistoryManager {
         let mut deleted_count = 0;
 
         for (id, file_name) in entries {
-            // Delete database entry
+            // Delete database entry and its revisions
             conn.execute(
                 "DELETE FROM transcription_history WHERE id = ?1",
                 params![id],
             )?;
+            conn.execute(
+                "DELETE FROM transcription_revisions WHERE history_id = ?1",
+                params![id],
+            )?;
 
             // Delete WAV file
             let file_path = self.recordings_dir.join(file_name);
@@ -355,7 +530,7 @@ impl HistoryManager {
     pub async fn get_history_entries(&self) -> Result<Vec<HistoryEntry>> {
         let conn = self.get_connection()?;
         let mut stmt = conn.prepare(
-            "SELECT id, file_name, timestamp, saved, title, transcription_text, post_processed_text, post_process_prompt FROM transcription_history ORDER BY timestamp DESC"
+            "SELECT id, file_name, timestamp, saved, title, transcription_text, post_processed_text, post_process_prompt, detected_language, translated_text, translation_language, post_process_attempts FROM transcription_history ORDER BY timestamp DESC"
         )?;
 
         let rows = stmt.query_map([], |row| {
@@ -368,6 +543,10 @@ impl HistoryManager {
                 transcription_text: row.get("transcription_text")?,
                 post_processed_text: row.get("post_processed_text")?,
                 post_process_prompt: row.get("post_process_prompt")?,
+                detected_language: row.get("detected_language")?,
+                translated_text: row.get("translated_text")?,
+                translation_language: row.get("translation_language")?,
+                post_process_attempts: attempts_from_column(row.get("post_process_attempts")?),
             })
         })?;
 
@@ -413,7 +592,7 @@ impl HistoryManager {
     pub async fn get_entry_by_id(&self, id: i64) -> Result<Option<HistoryEntry>> {
         let conn = self.get_connection()?;
         let mut stmt = conn.prepare(
-            "SELECT id, file_name, timestamp, saved, title, transcription_text, post_processed_text, post_process_prompt
+            "SELECT id, file_name, timestamp, saved, title, transcription_text, post_processed_text, post_process_prompt, detected_language, translated_text, translation_language, post_process_attempts
              FROM transcription_history WHERE id = ?1",
         )?;
 
@@ -428,6 +607,10 @@ impl HistoryManager {
                     transcription_text: row.get("transcription_text")?,
                     post_processed_text: row.get("post_processed_text")?,
                     post_process_prompt: row.get("post_process_prompt")?,
+                    detected_language: row.get("detected_language")?,
+                    translated_text: row.get("translated_text")?,
+                    translation_language: row.get("translation_language")?,
+                    post_process_attempts: attempts_from_column(row.get("post_process_attempts")?),
                 })
             })
             .optional()?;
@@ -435,6 +618,56 @@ impl HistoryManager {
         Ok(entry)
     }
 
+    /// The most recently saved entry, if any
+    pub async fn get_latest_entry(&self) -> Result<Option<HistoryEntry>> {
+        let conn = self.get_connection()?;
+        let id: Option<i64> = conn
+            .query_row(
+                "SELECT id FROM transcription_history ORDER BY timestamp DESC, id DESC LIMIT 1",
+                [],
+                |row| row.get("id"),
+            )
+            .optional()?;
+
+        match id {
+            Some(id) => self.get_entry_by_id(id).await,
+            None => Ok(None),
+        }
+    }
+
+    /// Records one LLM request for the usage totals
+    pub fn record_llm_usage(&self, attempt: &LlmAttempt) -> Result<()> {
+        let conn = self.get_connection()?;
+        conn.execute(
+            "INSERT INTO llm_usage (timestamp, provider_id, model, duration_ms, outcome, prompt_tokens, completion_tokens) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                Utc::now().timestamp(),
+                attempt.provider_id,
+                attempt.model,
+                attempt.duration_ms as i64,
+                serde_json::to_string(&attempt.outcome)?,
+                attempt.usage.map(|usage| usage.prompt_tokens as i64),
+                attempt.usage.map(|usage| usage.completion_tokens as i64),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Every recorded LLM request with its timestamp, oldest first
+    pub async fn get_llm_usage(&self) -> Result<Vec<(i64, LlmAttempt)>> {
+        let conn = self.get_connection()?;
+        let mut stmt = conn.prepare(
+            "SELECT timestamp, provider_id, model, duration_ms, outcome, prompt_tokens, completion_tokens FROM llm_usage ORDER BY timestamp, id",
+        )?;
+        let rows = stmt.query_map([], llm_usage_from_row)?;
+
+        let mut records = Vec::new();
+        for row in rows {
+            records.push(row?);
+        }
+        Ok(records)
+    }
+
     pub async fn delete_entry(&self, id: i64) -> Result<()> {
         let conn = self.get_connection()?;
 
@@ -455,6 +688,10 @@ impl HistoryManager {
             "DELETE FROM transcription_history WHERE id = ?1",
             params![id],
         )?;
+        conn.execute(
+            "DELETE FROM transcription_revisions WHERE history_id = ?1",
+            params![id],
+        )?;
 
         debug!("Deleted history entry with id: {}", id);
 
@@ -466,6 +703,49 @@ impl HistoryManager {
         Ok(())
     }
 
+    /// Store a re-transcription of an existing entry as a new revision
+    pub async fn save_revision(
+        &self,
+        history_id: i64,
+        model_id: Option<String>,
+        language: Option<String>,
+        transcription_text: String,
+        post_processed_text: Option<String>,
+        post_process_prompt: Option<String>,
+    ) -> Result<HistoryRevision> {
+        let conn = self.get_connection()?;
+        let revision = insert_revision(
+            &conn,
+            HistoryRevision {
+                id: 0,
+                history_id,
+                timestamp: Utc::now().timestamp(),
+                model_id,
+                language,
+                transcription_text,
+                post_processed_text,
+                post_process_prompt,
+            },
+        )?;
+
+        debug!(
+            "Saved revision {} for history entry {}",
+            revision.id, history_id
+        );
+
+        // Emit history updated event
+        if let Err(e) = self.app_handle.emit("history-updated", ()) {
+            error!("Failed to emit history-updated event: {}", e);
+        }
+
+        Ok(revision)
+    }
+
+    pub async fn get_revisions(&self, history_id: i64) -> Result<Vec<HistoryRevision>> {
+        let conn = self.get_connection()?;
+        revisions_for(&conn, history_id)
+    }
+
     fn format_timestamp_title(&self, timestamp: i64) -> String {
         if let Some(utc_datetime) = DateTime::from_timestamp(timestamp, 0) {
             // Convert UTC to local timezone
@@ -476,3 +756,110 @@ impl HistoryManager {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_usage_table_starts_with_attempts_from_history() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        let migrations = Migrations::new(MIGRATIONS.to_vec());
+        migrations
+            .to_version(&mut conn, MIGRATIONS.len() - 1)
+            .unwrap();
+
+        let attempts = vec![
+            LlmAttempt {
+                provider_id: "openai".to_string(),
+                model: "gpt-4o".to_string(),
+                duration_ms: 900,
+                outcome: AttemptOutcome::Failed {
+                    error: "timeout".to_string(),
+                },
+                usage: None,
+            },
+            LlmAttempt {
+                provider_id: "groq".to_string(),
+                model: "llama".to_string(),
+                duration_ms: 300,
+                outcome: AttemptOutcome::Succeeded,
+                usage: Some(TokenUsage {
+                    prompt_tokens: 120,
+                    completion_tokens: 40,
+                }),
+            },
+        ];
+        conn.execute(
+            "INSERT INTO transcription_history (file_name, timestamp, saved, title, transcription_text, post_process_attempts) VALUES ('a.wav', 1714564800, 0, 'a', 'text', ?1)",
+            params![attempts_to_column(&attempts).unwrap()],
+        )
+        .unwrap();
+        migrations.to_latest(&mut conn).unwrap();
+
+        // Pruning history leaves the usage behind
+        conn.execute("DELETE FROM transcription_history", [])
+            .unwrap();
+        let mut stmt = conn.prepare("SELECT * FROM llm_usage ORDER BY id").unwrap();
+        let records: Vec<(i64, LlmAttempt)> = stmt
+            .query_map([], llm_usage_from_row)
+            .unwrap()
+            .collect::<rusqlite::Result<_>>()
+            .unwrap();
+        assert_eq!(
+            records,
+            attempts
+                .into_iter()
+                .map(|attempt| (1714564800, attempt))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_revisions_are_saved_and_read_back_newest_first() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        Migrations::new(MIGRATIONS.to_vec())
+            .to_latest(&mut conn)
+            .unwrap();
+        conn.execute(
+            "INSERT INTO transcription_history (file_name, timestamp, saved, title, transcription_text) VALUES ('a.wav', 1714564800, 0, 'a', 'hello word')",
+            [],
+        )
+        .unwrap();
+        let history_id = conn.last_insert_rowid();
+
+        let revision = |timestamp: i64, model_id: &str| HistoryRevision {
+            id: 0,
+            history_id,
+            timestamp,
+            model_id: Some(model_id.to_string()),
+            language: Some("en".to_string()),
+            transcription_text: "hello world".to_string(),
+            post_processed_text: None,
+            post_process_prompt: None,
+        };
+        let first = insert_revision(&conn, revision(1714564900, "small")).unwrap();
+        let second = HistoryRevision {
+            post_processed_text: Some("Hello, world.".to_string()),
+            post_process_prompt: Some("Clean ${output}".to_string()),
+            ..revision(1714565000, "large")
+        };
+        let second = insert_revision(&conn, second).unwrap();
+        assert_ne!(first.id, second.id);
+
+        assert_eq!(
+            revisions_for(&conn, history_id).unwrap(),
+            vec![second, first]
+        );
+        assert!(revisions_for(&conn, history_id + 1).unwrap().is_empty());
+        // The original entry is left untouched
+        let text: String = conn
+            .query_row(
+                "SELECT transcription_text FROM transcription_history WHERE id = ?1",
+                [history_id],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(text, "hello word");
+    }
+}
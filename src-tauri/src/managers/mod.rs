@@ -1,4 +1,6 @@
 pub mod audio;
+pub mod benchmark;
 pub mod history;
 pub mod model;
+pub mod secrets;
 pub mod transcription;
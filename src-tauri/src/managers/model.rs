@@ -1,3 +1,4 @@
+use crate::managers::benchmark::{load_benchmarks, save_benchmarks, ModelBenchmark};
 use crate::settings::{get_settings, write_settings};
 use anyhow::Result;
 use flate2::read::GzDecoder;
@@ -14,10 +15,18 @@ use std::sync::Mutex;
 use tar::Archive;
 use tauri::{AppHandle, Emitter, Manager};
 
-#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Type)]
 pub enum EngineType {
     Whisper,
     Parakeet,
+    OpenAiCompatible,
+}
+
+impl EngineType {
+    /// Remote engines run on a server and have no files to download
+    pub fn is_remote(&self) -> bool {
+        matches!(self, EngineType::OpenAiCompatible)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Type)]
@@ -35,6 +44,8 @@ pub struct ModelInfo {
     pub engine_type: EngineType,
     pub accuracy_score: f32, // 0.0 to 1.0, higher is more accurate
     pub speed_score: f32,    // 0.0 to 1.0, higher is faster
+    #[serde(default)]
+    pub benchmark: Option<ModelBenchmark>, // Measured on this machine, if benchmarked
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Type)]
@@ -49,16 +60,19 @@ pub struct ModelManager {
     app_handle: AppHandle,
     models_dir: PathBuf,
     available_models: Mutex<HashMap<String, ModelInfo>>,
+    benchmarks_path: PathBuf,
+    benchmarks: Mutex<HashMap<String, ModelBenchmark>>,
 }
 
 impl ModelManager {
     pub fn new(app_handle: &AppHandle) -> Result<Self> {
         // Create models directory in app data
-        let models_dir = app_handle
+        let app_data_dir = app_handle
             .path()
             .app_data_dir()
-            .map_err(|e| anyhow::anyhow!("Failed to get app data dir: {}", e))?
-            .join("models");
+            .map_err(|e| anyhow::anyhow!("Failed to get app data dir: {}", e))?;
+        let models_dir = app_data_dir.join("models");
+        let benchmarks_path = app_data_dir.join("model_benchmarks.json");
 
         if !models_dir.exists() {
             fs::create_dir_all(&models_dir)?;
@@ -83,6 +97,7 @@ impl ModelManager {
                 engine_type: EngineType::Whisper,
                 accuracy_score: 0.60,
                 speed_score: 0.85,
+                benchmark: None,
             },
         );
 
@@ -103,6 +118,7 @@ impl ModelManager {
                 engine_type: EngineType::Whisper,
                 accuracy_score: 0.75,
                 speed_score: 0.60,
+                benchmark: None,
             },
         );
 
@@ -122,6 +138,7 @@ impl ModelManager {
                 engine_type: EngineType::Whisper,
                 accuracy_score: 0.80,
                 speed_score: 0.40,
+                benchmark: None,
             },
         );
 
@@ -141,6 +158,7 @@ impl ModelManager {
                 engine_type: EngineType::Whisper,
                 accuracy_score: 0.85,
                 speed_score: 0.30,
+                benchmark: None,
             },
         );
 
@@ -161,6 +179,7 @@ impl ModelManager {
                 engine_type: EngineType::Parakeet,
                 accuracy_score: 0.85,
                 speed_score: 0.85,
+                benchmark: None,
             },
         );
 
@@ -180,6 +199,29 @@ impl ModelManager {
                 engine_type: EngineType::Parakeet,
                 accuracy_score: 0.80,
                 speed_score: 0.85,
+                benchmark: None,
+            },
+        );
+
+        // Self-hosted or hosted server, configured in settings
+        available_models.insert(
+            "remote-openai".to_string(),
+            ModelInfo {
+                id: "remote-openai".to_string(),
+                name: "Remote Server".to_string(),
+                description: "Sends audio to an OpenAI-compatible transcription server."
+                    .to_string(),
+                filename: String::new(),
+                url: None,
+                size_mb: 0,
+                is_downloaded: true,
+                is_downloading: false,
+                partial_size: 0,
+                is_directory: false,
+                engine_type: EngineType::OpenAiCompatible,
+                accuracy_score: 0.0,
+                speed_score: 0.0,
+                benchmark: None,
             },
         );
 
@@ -187,6 +229,8 @@ impl ModelManager {
             app_handle: app_handle.clone(),
             models_dir,
             available_models: Mutex::new(available_models),
+            benchmarks: Mutex::new(load_benchmarks(&benchmarks_path)),
+            benchmarks_path,
         };
 
         // Migrate any bundled models to user directory
@@ -203,12 +247,36 @@ impl ModelManager {
 
     pub fn get_available_models(&self) -> Vec<ModelInfo> {
         let models = self.available_models.lock().unwrap();
-        models.values().cloned().collect()
+        let benchmarks = self.benchmarks.lock().unwrap();
+        models
+            .values()
+            .cloned()
+            .map(|mut model| {
+                model.benchmark = benchmarks.get(&model.id).cloned();
+                model
+            })
+            .collect()
     }
 
     pub fn get_model_info(&self, model_id: &str) -> Option<ModelInfo> {
         let models = self.available_models.lock().unwrap();
-        models.get(model_id).cloned()
+        let benchmarks = self.benchmarks.lock().unwrap();
+        models.get(model_id).cloned().map(|mut model| {
+            model.benchmark = benchmarks.get(model_id).cloned();
+            model
+        })
+    }
+
+    pub fn get_benchmarks(&self) -> Vec<ModelBenchmark> {
+        let benchmarks = self.benchmarks.lock().unwrap();
+        benchmarks.values().cloned().collect()
+    }
+
+    /// Store a benchmark result, replacing any previous result for the model
+    pub fn record_benchmark(&self, benchmark: ModelBenchmark) -> Result<()> {
+        let mut benchmarks = self.benchmarks.lock().unwrap();
+        benchmarks.insert(benchmark.model_id.clone(), benchmark);
+        save_benchmarks(&self.benchmarks_path, &benchmarks)
     }
 
     fn migrate_bundled_models(&self) -> Result<()> {
@@ -242,7 +310,12 @@ impl ModelManager {
         let mut models = self.available_models.lock().unwrap();
 
         for model in models.values_mut() {
-            if model.is_directory {
+            if model.engine_type.is_remote() {
+                // Always available, nothing on disk
+                model.is_downloaded = true;
+                model.is_downloading = false;
+                model.partial_size = 0;
+            } else if model.is_directory {
                 // For directory-based models, check if the directory exists
                 let model_path = self.models_dir.join(&model.filename);
                 let partial_path = self.models_dir.join(format!("{}.partial", &model.filename));
@@ -293,7 +366,10 @@ impl ModelManager {
         if settings.selected_model.is_empty() {
             // Find the first available (downloaded) model
             let models = self.available_models.lock().unwrap();
-            if let Some(available_model) = models.values().find(|model| model.is_downloaded) {
+            if let Some(available_model) = models
+                .values()
+                .find(|model| model.is_downloaded && !model.engine_type.is_remote())
+            {
                 info!(
                     "Auto-selecting model: {} ({})",
                     available_model.id, available_model.name
@@ -600,6 +676,10 @@ impl ModelManager {
 
         debug!("ModelManager: Found model info: {:?}", model_info);
 
+        if model_info.engine_type.is_remote() {
+            return Err(anyhow::anyhow!("Remote models have no files to delete"));
+        }
+
         let model_path = self.models_dir.join(&model_info.filename);
         let partial_path = self
             .models_dir
@@ -0,0 +1,409 @@
+use anyhow::{anyhow, Result};
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use log::{debug, info, warn};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+
+const KEY_FILE: &str = "secrets.key";
+const SECRETS_FILE: &str = "secrets.enc";
+/// Records which backend holds the secrets
+const BACKEND_FILE: &str = "secrets.backend";
+const NONCE_LEN: usize = 12;
+
+/// Where secrets are kept. Entries are addressed by name, e.g.
+/// `post_process_api_key.openai`.
+pub trait SecretBackend: Send + Sync {
+    fn name(&self) -> &'static str;
+    fn get(&self, name: &str) -> Result<Option<String>>;
+    fn set(&self, name: &str, secret: &str) -> Result<()>;
+    fn delete(&self, name: &str) -> Result<()>;
+}
+
+/// The OS credential store: Keychain, Windows Credential Manager or the
+/// Secret Service on Linux
+pub struct KeyringBackend {
+    service: String,
+}
+
+impl KeyringBackend {
+    pub fn new(service: &str) -> Self {
+        Self {
+            service: service.to_string(),
+        }
+    }
+
+    /// False when no credential store can be reached, which is common on Linux
+    /// systems without a Secret Service daemon or before the keyring is
+    /// unlocked
+    pub fn is_available(service: &str) -> bool {
+        let Ok(entry) = keyring::Entry::new(service, "availability-check") else {
+            return false;
+        };
+        match entry.get_password() {
+            Ok(_) | Err(keyring::Error::NoEntry) => true,
+            Err(e) => {
+                debug!("OS keyring is unavailable: {}", e);
+                false
+            }
+        }
+    }
+
+    fn entry(&self, name: &str) -> Result<keyring::Entry> {
+        Ok(keyring::Entry::new(&self.service, name)?)
+    }
+}
+
+impl SecretBackend for KeyringBackend {
+    fn name(&self) -> &'static str {
+        "OS keyring"
+    }
+
+    fn get(&self, name: &str) -> Result<Option<String>> {
+        match self.entry(name)?.get_password() {
+            Ok(secret) => Ok(Some(secret)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn set(&self, name: &str, secret: &str) -> Result<()> {
+        Ok(self.entry(name)?.set_password(secret)?)
+    }
+
+    fn delete(&self, name: &str) -> Result<()> {
+        match self.entry(name)?.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+/// Secrets encrypted with ChaCha20-Poly1305 under a random key that is kept
+/// in a separate file readable only by the current user
+pub struct EncryptedFileBackend {
+    path: PathBuf,
+    cipher: ChaCha20Poly1305,
+    secrets: Mutex<HashMap<String, String>>,
+}
+
+impl EncryptedFileBackend {
+    pub fn open(dir: &Path) -> Result<Self> {
+        fs::create_dir_all(dir)?;
+        let cipher = ChaCha20Poly1305::new(&load_or_create_key(&dir.join(KEY_FILE))?);
+        let path = dir.join(SECRETS_FILE);
+
+        let secrets = if path.exists() {
+            let bytes = fs::read(&path)?;
+            if bytes.len() < NONCE_LEN {
+                return Err(anyhow!("Secrets file {:?} is truncated", path));
+            }
+            let (nonce, ciphertext) = bytes.split_at(NONCE_LEN);
+            let plaintext = cipher
+                .decrypt(Nonce::from_slice(nonce), ciphertext)
+                .map_err(|_| anyhow!("Failed to decrypt secrets file {:?}", path))?;
+            serde_json::from_slice(&plaintext)?
+        } else {
+            HashMap::new()
+        };
+
+        Ok(Self {
+            path,
+            cipher,
+            secrets: Mutex::new(secrets),
+        })
+    }
+
+    fn save(&self, secrets: &HashMap<String, String>) -> Result<()> {
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .cipher
+            .encrypt(&nonce, serde_json::to_vec(secrets)?.as_slice())
+            .map_err(|_| anyhow!("Failed to encrypt secrets"))?;
+
+        let mut bytes = nonce.to_vec();
+        bytes.extend_from_slice(&ciphertext);
+        // Write then rename so a crash can't leave a half-written file
+        let temp_path = self.path.with_extension("tmp");
+        fs::write(&temp_path, bytes)?;
+        fs::rename(&temp_path, &self.path)?;
+        Ok(())
+    }
+}
+
+impl SecretBackend for EncryptedFileBackend {
+    fn name(&self) -> &'static str {
+        "encrypted file"
+    }
+
+    fn get(&self, name: &str) -> Result<Option<String>> {
+        Ok(self.secrets.lock().unwrap().get(name).cloned())
+    }
+
+    fn set(&self, name: &str, secret: &str) -> Result<()> {
+        let mut secrets = self.secrets.lock().unwrap();
+        secrets.insert(name.to_string(), secret.to_string());
+        self.save(&secrets)
+    }
+
+    fn delete(&self, name: &str) -> Result<()> {
+        let mut secrets = self.secrets.lock().unwrap();
+        if secrets.remove(name).is_some() {
+            self.save(&secrets)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BackendKind {
+    Keyring,
+    EncryptedFile,
+}
+
+impl BackendKind {
+    fn id(self) -> &'static str {
+        match self {
+            BackendKind::Keyring => "keyring",
+            BackendKind::EncryptedFile => "encrypted_file",
+        }
+    }
+
+    fn from_id(id: &str) -> Option<Self> {
+        match id.trim() {
+            "keyring" => Some(BackendKind::Keyring),
+            "encrypted_file" => Some(BackendKind::EncryptedFile),
+            _ => None,
+        }
+    }
+}
+
+/// Keeps the backend that already holds the secrets, even when the keyring
+/// can't be reached at the moment, e.g. on autostart before it is unlocked.
+/// Only the first launch picks one.
+fn choose_backend(
+    recorded: Option<BackendKind>,
+    has_secrets_file: bool,
+    keyring_available: impl FnOnce() -> bool,
+) -> BackendKind {
+    match recorded {
+        Some(kind) => kind,
+        // Secrets stored before the backend was recorded
+        None if has_secrets_file => BackendKind::EncryptedFile,
+        None if keyring_available() => BackendKind::Keyring,
+        None => BackendKind::EncryptedFile,
+    }
+}
+
+fn load_or_create_key(path: &Path) -> Result<Key> {
+    if path.exists() {
+        let bytes = fs::read(path)?;
+        if bytes.len() != 32 {
+            return Err(anyhow!("Secret key file {:?} is corrupt", path));
+        }
+        return Ok(Key::clone_from_slice(&bytes));
+    }
+
+    let key = ChaCha20Poly1305::generate_key(&mut OsRng);
+    fs::write(path, key.as_slice())?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(path, fs::Permissions::from_mode(0o600))?;
+    }
+    Ok(key)
+}
+
+/// Keeps provider API keys out of the settings store. Reads are cached since
+/// the settings are loaded for nearly every action.
+pub struct SecretManager {
+    backend: Box<dyn SecretBackend>,
+    cache: Mutex<HashMap<String, Option<String>>>,
+}
+
+impl SecretManager {
+    pub fn new(app_handle: &AppHandle) -> Result<Self> {
+        let dir = app_handle.path().app_data_dir()?;
+        fs::create_dir_all(&dir)?;
+        let service = &app_handle.config().identifier;
+
+        let recorded = fs::read_to_string(dir.join(BACKEND_FILE))
+            .ok()
+            .and_then(|id| BackendKind::from_id(&id));
+        let kind = choose_backend(recorded, dir.join(SECRETS_FILE).exists(), || {
+            KeyringBackend::is_available(service)
+        });
+
+        let backend: Box<dyn SecretBackend> = match kind {
+            BackendKind::Keyring => {
+                if recorded.is_some() && !KeyringBackend::is_available(service) {
+                    // Reads that fail aren't cached, so they are retried once
+                    // the keyring is unlocked
+                    warn!("The OS keyring holding the API keys is unavailable for now");
+                }
+                Box::new(KeyringBackend::new(service))
+            }
+            BackendKind::EncryptedFile => Box::new(EncryptedFileBackend::open(&dir)?),
+        };
+        if recorded != Some(kind) {
+            fs::write(dir.join(BACKEND_FILE), kind.id())?;
+        }
+
+        info!("Storing API keys in the {}", backend.name());
+        Ok(Self::with_backend(backend))
+    }
+
+    pub fn with_backend(backend: Box<dyn SecretBackend>) -> Self {
+        Self {
+            backend,
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<String> {
+        let mut cache = self.cache.lock().unwrap();
+        if let Some(secret) = cache.get(name) {
+            return secret.clone();
+        }
+
+        match self.backend.get(name) {
+            Ok(secret) => {
+                cache.insert(name.to_string(), secret.clone());
+                secret
+            }
+            Err(e) => {
+                warn!("Failed to read secret '{}': {}", name, e);
+                None
+            }
+        }
+    }
+
+    /// Stores a secret, or removes it when `secret` is empty
+    pub fn set(&self, name: &str, secret: &str) -> Result<()> {
+        if self.get(name).unwrap_or_default() == secret {
+            return Ok(());
+        }
+
+        if secret.is_empty() {
+            self.backend.delete(name)?;
+        } else {
+            self.backend.set(name, secret)?;
+        }
+        let stored = (!secret.is_empty()).then(|| secret.to_string());
+        self.cache.lock().unwrap().insert(name.to_string(), stored);
+        Ok(())
+    }
+}
+
+pub fn post_process_api_key_name(provider_id: &str) -> String {
+    format!("post_process_api_key.{}", provider_id)
+}
+
+/// What the UI is shown instead of a secret: enough to recognise which key is
+/// set without revealing it
+pub fn mask_secret(secret: &str) -> String {
+    let chars: Vec<char> = secret.chars().collect();
+    match chars.len() {
+        0 => String::new(),
+        len if len <= 8 => "•".repeat(8),
+        len => format!(
+            "{}{}",
+            "•".repeat(8),
+            chars[len - 4..].iter().collect::<String>()
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("handy-secrets-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn test_encrypted_file_round_trip() {
+        let dir = temp_dir("round-trip");
+        let backend = EncryptedFileBackend::open(&dir).unwrap();
+        backend
+            .set("post_process_api_key.openai", "sk-secret")
+            .unwrap();
+        backend
+            .set("post_process_api_key.groq", "gsk-other")
+            .unwrap();
+        backend.delete("post_process_api_key.groq").unwrap();
+
+        let contents = fs::read(dir.join(SECRETS_FILE)).unwrap();
+        assert!(!String::from_utf8_lossy(&contents).contains("sk-secret"));
+
+        let reopened = EncryptedFileBackend::open(&dir).unwrap();
+        assert_eq!(
+            reopened
+                .get("post_process_api_key.openai")
+                .unwrap()
+                .as_deref(),
+            Some("sk-secret")
+        );
+        assert_eq!(reopened.get("post_process_api_key.groq").unwrap(), None);
+
+        // Without the matching key the file can't be read
+        fs::write(dir.join(KEY_FILE), [7u8; 32]).unwrap();
+        assert!(EncryptedFileBackend::open(&dir).is_err());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_manager_removes_empty_secrets() {
+        let dir = temp_dir("manager");
+        let manager =
+            SecretManager::with_backend(Box::new(EncryptedFileBackend::open(&dir).unwrap()));
+        let name = post_process_api_key_name("anthropic");
+        assert_eq!(manager.get(&name), None);
+
+        manager.set(&name, "sk-ant-123").unwrap();
+        assert_eq!(manager.get(&name).as_deref(), Some("sk-ant-123"));
+        manager.set(&name, "").unwrap();
+        assert_eq!(manager.get(&name), None);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_backend_choice_sticks() {
+        let unavailable = || false;
+        assert_eq!(
+            choose_backend(Some(BackendKind::Keyring), false, unavailable),
+            BackendKind::Keyring
+        );
+        assert_eq!(
+            choose_backend(Some(BackendKind::EncryptedFile), false, || true),
+            BackendKind::EncryptedFile
+        );
+        assert_eq!(
+            choose_backend(None, true, || true),
+            BackendKind::EncryptedFile
+        );
+        assert_eq!(choose_backend(None, false, || true), BackendKind::Keyring);
+        assert_eq!(
+            choose_backend(None, false, unavailable),
+            BackendKind::EncryptedFile
+        );
+        assert_eq!(
+            BackendKind::from_id("keyring\n"),
+            Some(BackendKind::Keyring)
+        );
+    }
+
+    #[test]
+    fn test_mask_secret() {
+        assert_eq!(mask_secret(""), "");
+        assert_eq!(mask_secret("short"), "••••••••");
+        assert_eq!(mask_secret("sk-proj-abcdef1234"), "••••••••1234");
+    }
+}
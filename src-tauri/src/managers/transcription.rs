@@ -1,23 +1,18 @@
-use crate::audio_toolkit::apply_custom_words;
-use crate::managers::model::{EngineType, ModelManager};
-use crate::settings::{get_settings, ModelUnloadTimeout};
+use crate::managers::model::{EngineType, ModelInfo, ModelManager};
+use crate::settings::{get_settings, AppSettings, ModelUnloadTimeout};
+use crate::transcription_backend::{
+    choose_language, BackendContext, BackendRegistry, LanguageDetector, TranscribeOptions,
+    TranscriptionBackend,
+};
 use anyhow::Result;
 use log::{debug, error, info, warn};
 use serde::Serialize;
+use std::path::PathBuf;
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::{Arc, Condvar, Mutex};
 use std::thread;
 use std::time::{Duration, SystemTime};
 use tauri::{AppHandle, Emitter};
-use transcribe_rs::{
-    engines::{
-        parakeet::{
-            ParakeetEngine, ParakeetInferenceParams, ParakeetModelParams, TimestampGranularity,
-        },
-        whisper::{WhisperEngine, WhisperInferenceParams},
-    },
-    TranscriptionEngine,
-};
 
 #[derive(Clone, Debug, Serialize)]
 pub struct ModelStateEvent {
@@ -27,14 +22,20 @@ pub struct ModelStateEvent {
     pub error: Option<String>,
 }
 
-enum LoadedEngine {
-    Whisper(WhisperEngine),
-    Parakeet(ParakeetEngine),
+type LoadedEngine = Box<dyn TranscriptionBackend>;
+
+#[derive(Clone, Debug, Default)]
+pub struct TranscriptionOutput {
+    pub text: String,
+    /// Language identified before transcribing, when detection ran
+    pub detected_language: Option<String>,
 }
 
 #[derive(Clone)]
 pub struct TranscriptionManager {
     engine: Arc<Mutex<Option<LoadedEngine>>>,
+    backends: Arc<BackendRegistry>,
+    language_detector: Arc<Mutex<Option<LanguageDetector>>>,
     model_manager: Arc<ModelManager>,
     app_handle: AppHandle,
     current_model_id: Arc<Mutex<Option<String>>>,
@@ -49,6 +50,8 @@ impl TranscriptionManager {
     pub fn new(app_handle: &AppHandle, model_manager: Arc<ModelManager>) -> Result<Self> {
         let manager = Self {
             engine: Arc::new(Mutex::new(None)),
+            backends: Arc::new(BackendRegistry::new()),
+            language_detector: Arc::new(Mutex::new(None)),
             model_manager,
             app_handle: app_handle.clone(),
             current_model_id: Arc::new(Mutex::new(None)),
@@ -139,13 +142,14 @@ impl TranscriptionManager {
         {
             let mut engine = self.engine.lock().unwrap();
             if let Some(ref mut loaded_engine) = *engine {
-                match loaded_engine {
-                    LoadedEngine::Whisper(ref mut whisper) => whisper.unload_model(),
-                    LoadedEngine::Parakeet(ref mut parakeet) => parakeet.unload_model(),
-                }
+                loaded_engine.unload();
             }
             *engine = None; // Drop the engine to free memory
         }
+        {
+            let mut detector = self.language_detector.lock().unwrap();
+            *detector = None;
+        }
         {
             let mut current_model = self.current_model_id.lock().unwrap();
             *current_model = None;
@@ -217,48 +221,19 @@ impl TranscriptionManager {
             return Err(anyhow::anyhow!(error_msg));
         }
 
-        let model_path = self.model_manager.get_model_path(model_id)?;
-
-        // Create appropriate engine based on model type
-        let loaded_engine = match model_info.engine_type {
-            EngineType::Whisper => {
-                let mut engine = WhisperEngine::new();
-                engine.load_model(&model_path).map_err(|e| {
-                    let error_msg = format!("Failed to load whisper model {}: {}", model_id, e);
-                    let _ = self.app_handle.emit(
-                        "model-state-changed",
-                        ModelStateEvent {
-                            event_type: "loading_failed".to_string(),
-                            model_id: Some(model_id.to_string()),
-                            model_name: Some(model_info.name.clone()),
-                            error: Some(error_msg.clone()),
-                        },
-                    );
-                    anyhow::anyhow!(error_msg)
-                })?;
-                LoadedEngine::Whisper(engine)
-            }
-            EngineType::Parakeet => {
-                let mut engine = ParakeetEngine::new();
-                engine
-                    .load_model_with_params(&model_path, ParakeetModelParams::int8())
-                    .map_err(|e| {
-                        let error_msg =
-                            format!("Failed to load parakeet model {}: {}", model_id, e);
-                        let _ = self.app_handle.emit(
-                            "model-state-changed",
-                            ModelStateEvent {
-                                event_type: "loading_failed".to_string(),
-                                model_id: Some(model_id.to_string()),
-                                model_name: Some(model_info.name.clone()),
-                                error: Some(error_msg.clone()),
-                            },
-                        );
-                        anyhow::anyhow!(error_msg)
-                    })?;
-                LoadedEngine::Parakeet(engine)
-            }
-        };
+        let loaded_engine = self.create_engine(model_id, &model_info).map_err(|e| {
+            let error_msg = e.to_string();
+            let _ = self.app_handle.emit(
+                "model-state-changed",
+                ModelStateEvent {
+                    event_type: "loading_failed".to_string(),
+                    model_id: Some(model_id.to_string()),
+                    model_name: Some(model_info.name.clone()),
+                    error: Some(error_msg.clone()),
+                },
+            );
+            anyhow::anyhow!(error_msg)
+        })?;
 
         // Update the current engine and model ID
         {
@@ -290,6 +265,23 @@ impl TranscriptionManager {
         Ok(())
     }
 
+    /// Creates and loads an engine for the given model without touching the
+    /// currently loaded engine or emitting any state events.
+    fn create_engine(&self, model_id: &str, model_info: &ModelInfo) -> Result<LoadedEngine> {
+        let model_path = if model_info.engine_type.is_remote() {
+            None
+        } else {
+            Some(self.model_manager.get_model_path(model_id)?)
+        };
+        let settings = get_settings(&self.app_handle);
+
+        self.backends.create(&BackendContext {
+            model_info,
+            model_path,
+            settings: &settings,
+        })
+    }
+
     /// Kicks off the model loading in a background thread if it's not already loaded
     pub fn initiate_model_load(&self) {
         let mut is_loading = self.is_loading.lock().unwrap();
@@ -315,7 +307,7 @@ impl TranscriptionManager {
         current_model.clone()
     }
 
-    pub fn transcribe(&self, audio: Vec<f32>) -> Result<String> {
+    pub fn transcribe(&self, audio: Vec<f32>) -> Result<TranscriptionOutput> {
         // Update last activity timestamp
         self.last_activity.store(
             SystemTime::now()
@@ -332,7 +324,7 @@ impl TranscriptionManager {
         if audio.is_empty() {
             debug!("Empty audio vector");
             self.maybe_unload_immediately("empty audio");
-            return Ok(String::new());
+            return Ok(TranscriptionOutput::default());
         }
 
         // Check if model is loaded, if not try to load it
@@ -352,6 +344,30 @@ impl TranscriptionManager {
         // Get current settings for configuration
         let settings = get_settings(&self.app_handle);
 
+        // Identify the language up front when it picks the model or is limited to
+        // the languages the user speaks
+        let detected_language = if settings.selected_language == "auto"
+            && (!settings.language_model_routes.is_empty() || !settings.spoken_languages.is_empty())
+        {
+            self.detect_language(&audio, &settings.spoken_languages)
+        } else {
+            None
+        };
+        let language = detected_language
+            .clone()
+            .unwrap_or_else(|| settings.selected_language.clone());
+
+        // Languages without a route go back to the selected model, which an
+        // earlier routed dictation may have replaced
+        let model = settings.model_for_transcription(&language);
+        if !model.is_empty() && self.get_current_model().as_deref() != Some(model) {
+            info!("Using model {} for '{}' speech", model, language);
+            // On failure the previous engine stays loaded and is used instead
+            if let Err(e) = self.load_model(model) {
+                warn!("Failed to load model {}: {}", model, e);
+            }
+        }
+
         // Perform transcription with the appropriate engine
         let result = {
             let mut engine_guard = self.engine.lock().unwrap();
@@ -361,83 +377,218 @@ impl TranscriptionManager {
                 )
             })?;
 
-            match engine {
-                LoadedEngine::Whisper(whisper_engine) => {
-                    // Normalize language code for Whisper
-                    // Convert zh-Hans and zh-Hant to zh since Whisper uses ISO 639-1 codes
-                    let whisper_language = if settings.selected_language == "auto" {
-                        None
-                    } else {
-                        let normalized = if settings.selected_language == "zh-Hans"
-                            || settings.selected_language == "zh-Hant"
-                        {
-                            "zh".to_string()
-                        } else {
-                            settings.selected_language.clone()
-                        };
-                        Some(normalized)
-                    };
-
-                    let params = WhisperInferenceParams {
-                        language: whisper_language,
-                        translate: settings.translate_to_english,
-                        ..Default::default()
-                    };
-
-                    whisper_engine
-                        .transcribe_samples(audio, Some(params))
-                        .map_err(|e| anyhow::anyhow!("Whisper transcription failed: {}", e))?
-                }
-                LoadedEngine::Parakeet(parakeet_engine) => {
-                    let params = ParakeetInferenceParams {
-                        timestamp_granularity: TimestampGranularity::Segment,
-                        ..Default::default()
-                    };
-
-                    parakeet_engine
-                        .transcribe_samples(audio, Some(params))
-                        .map_err(|e| anyhow::anyhow!("Parakeet transcription failed: {}", e))?
+            engine.transcribe(
+                audio,
+                &TranscribeOptions {
+                    language: &language,
+                    translate: settings.translate_to_english,
+                },
+            )?
+        };
+
+        let final_result = finish_transcription(result, &settings, st);
+
+        self.maybe_unload_immediately("transcription");
+
+        Ok(TranscriptionOutput {
+            text: final_result,
+            detected_language,
+        })
+    }
+
+    /// Identifies the spoken language from the start of the audio, restricted to
+    /// `candidates` when given. Falls back to the first candidate if detection is
+    /// unavailable. The detection model is loaded on first use and released
+    /// together with the active model.
+    fn detect_language(&self, audio: &[f32], candidates: &[String]) -> Option<String> {
+        // Nothing to decide between
+        if candidates.len() == 1 {
+            return candidates.first().cloned();
+        }
+
+        let st = std::time::Instant::now();
+        let mut detector = self.language_detector.lock().unwrap();
+
+        if detector.is_none() {
+            let Some(model_path) = self.language_detection_model_path() else {
+                warn!("Language detection needs a downloaded Whisper model");
+                return candidates.first().cloned();
+            };
+            match LanguageDetector::load(&model_path) {
+                Ok(loaded) => *detector = Some(loaded),
+                Err(e) => {
+                    warn!("Failed to load language detection model: {}", e);
+                    return candidates.first().cloned();
                 }
             }
+        }
+
+        let languages = match detector.as_ref()?.detect(audio) {
+            Ok(languages) => languages,
+            Err(e) => {
+                warn!("Language detection failed: {}", e);
+                return candidates.first().cloned();
+            }
         };
 
-        // Apply word correction if custom words are configured
-        let corrected_result = if !settings.custom_words.is_empty() {
-            apply_custom_words(
-                &result.text,
-                &settings.custom_words,
-                settings.word_correction_threshold,
-            )
+        let language = if candidates.is_empty() {
+            languages.first().map(|(code, _)| code.clone())
         } else {
-            result.text
+            choose_language(&languages, candidates)
         };
+        debug!(
+            "Detected language {:?} in {}ms (top: {:?})",
+            language,
+            st.elapsed().as_millis(),
+            languages.first()
+        );
+        language
+    }
 
-        let et = std::time::Instant::now();
-        let translation_note = if settings.translate_to_english {
-            " (translated)"
-        } else {
-            ""
+    /// The fastest downloaded Whisper model, used only for language detection
+    fn language_detection_model_path(&self) -> Option<PathBuf> {
+        let model = self
+            .model_manager
+            .get_available_models()
+            .into_iter()
+            .filter(|m| m.is_downloaded && matches!(m.engine_type, EngineType::Whisper))
+            .max_by(|a, b| a.speed_score.total_cmp(&b.speed_score))?;
+        self.model_manager.get_model_path(&model.id).ok()
+    }
+
+    /// Transcribes audio with an explicit model and language, independent of the
+    /// active selection. If `model_id` differs from the currently loaded model, a
+    /// temporary engine is loaded for this call only and dropped afterwards, so the
+    /// active model stays in place for regular dictation.
+    pub fn transcribe_with_model(
+        &self,
+        audio: Vec<f32>,
+        model_id: Option<&str>,
+        language: Option<&str>,
+    ) -> Result<String> {
+        let settings = get_settings(&self.app_handle);
+        let language = language
+            .unwrap_or(settings.selected_language.as_str())
+            .to_string();
+
+        let model_id = match model_id {
+            Some(id) if Some(id) != self.get_current_model().as_deref() => id.to_string(),
+            _ => {
+                // Same model as the active one: reuse the loaded engine
+                self.initiate_model_load();
+                let mut is_loading = self.is_loading.lock().unwrap();
+                while *is_loading {
+                    is_loading = self.loading_condvar.wait(is_loading).unwrap();
+                }
+                drop(is_loading);
+
+                let st = std::time::Instant::now();
+                let result = {
+                    let mut engine_guard = self.engine.lock().unwrap();
+                    let engine = engine_guard
+                        .as_mut()
+                        .ok_or_else(|| anyhow::anyhow!("Model is not loaded for transcription."))?;
+                    engine.transcribe(
+                        audio,
+                        &TranscribeOptions {
+                            language: &language,
+                            translate: settings.translate_to_english,
+                        },
+                    )?
+                };
+                return Ok(finish_transcription(result, &settings, st));
+            }
         };
-        info!(
-            "Transcription completed in {}ms{}",
-            (et - st).as_millis(),
-            translation_note
+
+        let model_info = self
+            .model_manager
+            .get_model_info(&model_id)
+            .ok_or_else(|| anyhow::anyhow!("Model not found: {}", model_id))?;
+
+        if !model_info.is_downloaded {
+            return Err(anyhow::anyhow!("Model not downloaded: {}", model_id));
+        }
+
+        let load_start = std::time::Instant::now();
+        let mut engine = self.create_engine(&model_id, &model_info)?;
+        debug!(
+            "Loaded temporary model {} in {}ms",
+            model_id,
+            load_start.elapsed().as_millis()
         );
 
-        let final_result = corrected_result.trim().to_string();
+        let st = std::time::Instant::now();
+        let result = engine.transcribe(
+            audio,
+            &TranscribeOptions {
+                language: &language,
+                translate: settings.translate_to_english,
+            },
+        );
+        engine.unload();
 
-        if final_result.is_empty() {
-            info!("Transcription result is empty");
-        } else {
-            info!("Transcription result: {}", final_result);
+        Ok(finish_transcription(result?, &settings, st))
+    }
+
+    /// Loads a fresh engine for the model and runs it over the audio, returning the
+    /// load and transcription durations. The active engine is left untouched.
+    pub fn benchmark_model(&self, model_id: &str, audio: Vec<f32>) -> Result<(Duration, Duration)> {
+        let model_info = self
+            .model_manager
+            .get_model_info(model_id)
+            .ok_or_else(|| anyhow::anyhow!("Model not found: {}", model_id))?;
+
+        if !model_info.is_downloaded {
+            return Err(anyhow::anyhow!("Model not downloaded: {}", model_id));
         }
 
-        self.maybe_unload_immediately("transcription");
+        let load_start = std::time::Instant::now();
+        let mut engine = self.create_engine(model_id, &model_info)?;
+        let load_time = load_start.elapsed();
+
+        // Benchmark the engine itself, independent of the user's language choice
+        let transcribe_start = std::time::Instant::now();
+        let result = engine.transcribe(
+            audio,
+            &TranscribeOptions {
+                language: "auto",
+                translate: false,
+            },
+        );
+        let transcription_time = transcribe_start.elapsed();
+        engine.unload();
 
-        Ok(final_result)
+        result?;
+        Ok((load_time, transcription_time))
     }
 }
 
+/// Logs the raw engine output and trims it. Text processing happens later in
+/// the configurable pipeline (see `text_pipeline`).
+fn finish_transcription(text: String, settings: &AppSettings, st: std::time::Instant) -> String {
+    let et = std::time::Instant::now();
+    let translation_note = if settings.translate_to_english {
+        " (translated)"
+    } else {
+        ""
+    };
+    info!(
+        "Transcription completed in {}ms{}",
+        (et - st).as_millis(),
+        translation_note
+    );
+
+    let final_result = text.trim().to_string();
+
+    if final_result.is_empty() {
+        info!("Transcription result is empty");
+    } else {
+        info!("Transcription result: {}", final_result);
+    }
+
+    final_result
+}
+
 impl Drop for TranscriptionManager {
     fn drop(&mut self) {
         debug!("Shutting down TranscriptionManager");
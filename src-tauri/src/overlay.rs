@@ -278,6 +278,13 @@ pub fn hide_recording_overlay(app_handle: &AppHandle) {
     }
 }
 
+/// Shows text streamed in from the LLM in the overlay while it is generated
+pub fn show_overlay_text(app_handle: &AppHandle, text: &str) {
+    if let Some(overlay_window) = app_handle.get_webview_window("recording_overlay") {
+        let _ = overlay_window.emit("overlay-text", text);
+    }
+}
+
 pub fn emit_levels(app_handle: &AppHandle, levels: &Vec<f32>) {
     // emit levels to main app
     let _ = app_handle.emit("mic-level", levels);
@@ -0,0 +1,189 @@
+use chrono::Local;
+
+/// Every variable an LLM prompt may reference as `${name}`
+pub const PROMPT_VARIABLES: &[&str] = &[
+    "output",
+    "language",
+    "date",
+    "time",
+    "previous_output",
+    "clipboard",
+    "selection",
+    "custom_words",
+];
+
+/// Values read from outside the dictated text. They are only gathered when a
+/// prompt uses them, since reading the selection sends a copy shortcut.
+#[derive(Debug, Clone, Default)]
+pub struct PromptInputs {
+    /// Final text of the most recent history entry
+    pub previous_output: Option<String>,
+    pub clipboard: Option<String>,
+    /// Text selected in the focused app
+    pub selection: Option<String>,
+}
+
+/// Everything a prompt's variables are filled in from
+pub struct PromptValues<'a> {
+    pub output: &'a str,
+    pub language: &'a str,
+    pub custom_words: &'a [String],
+    pub inputs: &'a PromptInputs,
+}
+
+impl PromptValues<'_> {
+    fn value(&self, name: &str) -> Option<String> {
+        let value = match name {
+            "output" => self.output.to_string(),
+            "language" => self.language.to_string(),
+            "date" => Local::now().format("%Y-%m-%d").to_string(),
+            "time" => Local::now().format("%H:%M").to_string(),
+            "previous_output" => self.inputs.previous_output.clone().unwrap_or_default(),
+            "clipboard" => self.inputs.clipboard.clone().unwrap_or_default(),
+            "selection" => self.inputs.selection.clone().unwrap_or_default(),
+            "custom_words" => self.custom_words.join(", "),
+            _ => return None,
+        };
+        Some(value)
+    }
+}
+
+/// Splits a template into literal text and `${name}` references
+fn for_each_part<'a>(template: &'a str, mut on_part: impl FnMut(&'a str, Option<&'a str>)) {
+    let mut rest = template;
+    while let Some(start) = rest.find("${") {
+        let Some(end) = rest[start + 2..].find('}') else {
+            break;
+        };
+        let name = &rest[start + 2..start + 2 + end];
+        on_part(&rest[..start], Some(name));
+        rest = &rest[start + 3 + end..];
+    }
+    on_part(rest, None);
+}
+
+/// Names of the variables a template references, in order of appearance
+pub fn template_variables(template: &str) -> Vec<&str> {
+    let mut names = Vec::new();
+    for_each_part(template, |_, name| names.extend(name));
+    names
+}
+
+pub fn uses_variable(template: &str, variable: &str) -> bool {
+    template_variables(template).contains(&variable)
+}
+
+/// Rejects templates that reference variables no prompt can fill in
+pub fn validate_prompt_template(template: &str) -> Result<(), String> {
+    let mut unknown: Vec<&str> = Vec::new();
+    for name in template_variables(template) {
+        if !PROMPT_VARIABLES.contains(&name) && !unknown.contains(&name) {
+            unknown.push(name);
+        }
+    }
+    if unknown.is_empty() {
+        return Ok(());
+    }
+
+    let list = |names: &[&str]| {
+        names
+            .iter()
+            .map(|name| format!("${{{}}}", name))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    Err(format!(
+        "Unknown prompt variable{} {}. Available variables: {}",
+        if unknown.len() == 1 { "" } else { "s" },
+        list(&unknown),
+        list(PROMPT_VARIABLES)
+    ))
+}
+
+/// Fills in every variable in a single pass, so substituted text that itself
+/// contains `${...}` is left alone. Unknown variables are kept as written.
+pub fn render_prompt_template(template: &str, values: &PromptValues<'_>) -> String {
+    let mut rendered = String::with_capacity(template.len());
+    for_each_part(template, |literal, name| {
+        rendered.push_str(literal);
+        if let Some(name) = name {
+            match values.value(name) {
+                Some(value) => rendered.push_str(&value),
+                None => {
+                    rendered.push_str("${");
+                    rendered.push_str(name);
+                    rendered.push('}');
+                }
+            }
+        }
+    });
+    rendered
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(template: &str, inputs: &PromptInputs) -> String {
+        let custom_words = vec!["Handy".to_string(), "Tauri".to_string()];
+        render_prompt_template(
+            template,
+            &PromptValues {
+                output: "dictated ${clipboard}",
+                language: "de",
+                custom_words: &custom_words,
+                inputs,
+            },
+        )
+    }
+
+    #[test]
+    fn test_render_fills_in_variables_once() {
+        let inputs = PromptInputs {
+            previous_output: Some("Earlier.".to_string()),
+            clipboard: Some("copied".to_string()),
+            selection: None,
+        };
+
+        assert_eq!(
+            render(
+                "[${language}] ${output} | ${previous_output} | ${clipboard} | ${selection} | ${custom_words}",
+                &inputs
+            ),
+            "[de] dictated ${clipboard} | Earlier. | copied |  | Handy, Tauri"
+        );
+        assert_eq!(render("${unknown} and ${", &inputs), "${unknown} and ${");
+    }
+
+    #[test]
+    fn test_render_date_and_time() {
+        let rendered = render("${date} ${time}", &PromptInputs::default());
+        let (date, time) = rendered.split_once(' ').unwrap();
+        assert!(chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").is_ok());
+        assert!(chrono::NaiveTime::parse_from_str(time, "%H:%M").is_ok());
+    }
+
+    #[test]
+    fn test_template_variables() {
+        assert_eq!(
+            template_variables("Fix ${output} in ${language}, ${output}"),
+            vec!["output", "language", "output"]
+        );
+        assert!(uses_variable("Use ${selection}", "selection"));
+        assert!(!uses_variable("Use ${selection}", "clipboard"));
+    }
+
+    #[test]
+    fn test_validate_prompt_template() {
+        assert!(validate_prompt_template("Clean ${output} on ${date} at ${time}").is_ok());
+        assert!(validate_prompt_template("No variables at all").is_ok());
+
+        let error = validate_prompt_template("${outptu} ${foo} ${foo}").unwrap_err();
+        assert!(
+            error.starts_with("Unknown prompt variables ${outptu}, ${foo}."),
+            "{}",
+            error
+        );
+        assert!(error.contains("${previous_output}"), "{}", error);
+    }
+}
@@ -0,0 +1,361 @@
+use once_cell::sync::Lazy;
+use regex::{Captures, Regex, RegexBuilder};
+
+static EMAIL: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"[A-Za-z0-9._%+-]+@[A-Za-z0-9-]+(?:\.[A-Za-z0-9-]+)*\.[A-Za-z]{2,}").unwrap()
+});
+static CARD: Lazy<Regex> = Lazy::new(|| Regex::new(r"\d(?:[ -]?\d){12,18}").unwrap());
+static PHONE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?:\+\d{1,3}[ .-]?)?(?:\(\d{1,5}\)[ .-]?)?\d{2,5}(?:[ .-]?\d{2,5}){1,5}").unwrap()
+});
+static PLACEHOLDER: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\[(EMAIL|PHONE|CARD|NAME)_(\d+)\]").unwrap());
+
+/// Longest placeholder a stream may still be in the middle of
+const MAX_PLACEHOLDER_LEN: usize = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum EntityKind {
+    Email,
+    Card,
+    Phone,
+    Name,
+}
+
+impl EntityKind {
+    fn label(self) -> &'static str {
+        match self {
+            EntityKind::Email => "EMAIL",
+            EntityKind::Card => "CARD",
+            EntityKind::Phone => "PHONE",
+            EntityKind::Name => "NAME",
+        }
+    }
+}
+
+struct Entity {
+    placeholder: String,
+    kind: EntityKind,
+    original: String,
+}
+
+/// Replaces personal data with placeholders such as `[EMAIL_1]` before text
+/// leaves the machine, and puts the originals back into the reply. The same
+/// value always gets the same placeholder, so one redactor should be used for
+/// every part of a request.
+pub struct Redactor {
+    names: Option<Regex>,
+    entities: Vec<Entity>,
+}
+
+impl Redactor {
+    /// `names` are people and other terms that are always redacted, since
+    /// names can't be recognised reliably from their spelling alone
+    pub fn new(names: &[String]) -> Self {
+        let mut names: Vec<&str> = names
+            .iter()
+            .map(|name| name.trim())
+            .filter(|name| !name.is_empty())
+            .collect();
+        // Longer names first so "Anna Lena" wins over "Anna"
+        names.sort_by_key(|name| std::cmp::Reverse(name.len()));
+        let names = (!names.is_empty()).then(|| {
+            let alternatives: Vec<String> = names.iter().map(|name| regex::escape(name)).collect();
+            // Half boundaries so names that start or end in punctuation, like
+            // "Dr. Smith" or "Dr.", still match before a space or the end
+            RegexBuilder::new(&format!(
+                r"\b{{start-half}}(?:{})\b{{end-half}}",
+                alternatives.join("|")
+            ))
+            .case_insensitive(true)
+            .build()
+            .unwrap()
+        });
+
+        Self {
+            names,
+            entities: Vec::new(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    pub fn redact(&mut self, text: &str) -> String {
+        let mut spans: Vec<(usize, usize, EntityKind)> = Vec::new();
+        let mut take = |start: usize, end: usize, kind: EntityKind| {
+            if spans.iter().all(|&(s, e, _)| end <= s || start >= e) {
+                spans.push((start, end, kind));
+            }
+        };
+
+        for m in EMAIL.find_iter(text) {
+            take(m.start(), m.end(), EntityKind::Email);
+        }
+        for m in CARD.find_iter(text) {
+            if is_standalone_number(text, m.start(), m.end()) && is_card_number(m.as_str()) {
+                take(m.start(), m.end(), EntityKind::Card);
+            }
+        }
+        for m in PHONE.find_iter(text) {
+            if is_standalone_number(text, m.start(), m.end()) && is_phone_number(m.as_str()) {
+                take(m.start(), m.end(), EntityKind::Phone);
+            }
+        }
+        if let Some(names) = &self.names {
+            for m in names.find_iter(text) {
+                take(m.start(), m.end(), EntityKind::Name);
+            }
+        }
+
+        spans.sort_by_key(|&(start, _, _)| start);
+        let mut redacted = String::with_capacity(text.len());
+        let mut last = 0;
+        for (start, end, kind) in spans {
+            redacted.push_str(&text[last..start]);
+            redacted.push_str(self.placeholder_for(kind, &text[start..end]));
+            last = end;
+        }
+        redacted.push_str(&text[last..]);
+        redacted
+    }
+
+    fn placeholder_for(&mut self, kind: EntityKind, original: &str) -> &str {
+        let index = match self
+            .entities
+            .iter()
+            .position(|entity| entity.kind == kind && entity.original == original)
+        {
+            Some(index) => index,
+            None => {
+                let number = self.entities.iter().filter(|e| e.kind == kind).count() + 1;
+                self.entities.push(Entity {
+                    placeholder: format!("[{}_{}]", kind.label(), number),
+                    kind,
+                    original: original.to_string(),
+                });
+                self.entities.len() - 1
+            }
+        };
+        &self.entities[index].placeholder
+    }
+
+    /// Puts the original values back. Placeholders this redactor didn't hand
+    /// out are kept as written.
+    pub fn restore(&self, text: &str) -> String {
+        PLACEHOLDER
+            .replace_all(text, |caps: &Captures| {
+                let placeholder = &caps[0];
+                self.entities
+                    .iter()
+                    .find(|entity| entity.placeholder == placeholder)
+                    .map_or_else(|| placeholder.to_string(), |entity| entity.original.clone())
+            })
+            .into_owned()
+    }
+
+    pub fn stream_restorer(&self) -> StreamRestorer<'_> {
+        StreamRestorer {
+            redactor: self,
+            pending: String::new(),
+        }
+    }
+}
+
+/// Restores streamed deltas, holding back the end of a delta while it could
+/// be the start of a placeholder split across chunks
+pub struct StreamRestorer<'a> {
+    redactor: &'a Redactor,
+    pending: String,
+}
+
+impl StreamRestorer<'_> {
+    pub fn push(&mut self, delta: &str) -> String {
+        self.pending.push_str(delta);
+        let split = match self.pending.rfind('[') {
+            Some(start) if could_be_placeholder(&self.pending[start..]) => start,
+            _ => self.pending.len(),
+        };
+        let ready: String = self.pending.drain(..split).collect();
+        self.redactor.restore(&ready)
+    }
+
+    pub fn finish(self) -> String {
+        self.redactor.restore(&self.pending)
+    }
+}
+
+fn could_be_placeholder(tail: &str) -> bool {
+    tail.len() < MAX_PLACEHOLDER_LEN
+        && !tail.contains(']')
+        && tail[1..]
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+}
+
+/// Numbers glued to letters, digits or further digit groups are part of
+/// something longer, like "1 000 000 000"
+fn is_standalone_number(text: &str, start: usize, end: usize) -> bool {
+    let mut before = text[..start].chars().rev();
+    let mut after = text[end..].chars();
+    let is_separator = |c: char| matches!(c, ' ' | '.' | '-');
+    match before.next() {
+        Some(c) if c.is_alphanumeric() || c == '+' => return false,
+        Some(c) if is_separator(c) && before.next().is_some_and(|c| c.is_ascii_digit()) => {
+            return false
+        }
+        _ => {}
+    }
+    match after.next() {
+        Some(c) if c.is_alphanumeric() => false,
+        Some(c) if is_separator(c) => !after.next().is_some_and(|c| c.is_ascii_digit()),
+        _ => true,
+    }
+}
+
+fn digits(text: &str) -> Vec<u32> {
+    text.chars().filter_map(|c| c.to_digit(10)).collect()
+}
+
+/// 13 to 19 digits that pass the Luhn checksum
+fn is_card_number(text: &str) -> bool {
+    let digits = digits(text);
+    if !(13..=19).contains(&digits.len()) {
+        return false;
+    }
+    let sum: u32 = digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, &d)| match (i % 2 == 1, d * 2) {
+            (true, doubled) if doubled > 9 => doubled - 9,
+            (true, doubled) => doubled,
+            (false, _) => d,
+        })
+        .sum();
+    sum.is_multiple_of(10)
+}
+
+/// International numbers need 7 digits; without a country code 9 are needed
+/// so that dates and amounts aren't mistaken for phone numbers
+fn is_phone_number(text: &str) -> bool {
+    let count = digits(text).len();
+    let min = if text.starts_with('+') { 7 } else { 9 };
+    (min..=15).contains(&count) && !starts_with_date(text)
+}
+
+/// A year followed by a month, like "2023.04.01.12"
+fn starts_with_date(text: &str) -> bool {
+    let mut groups = text.split([' ', '.', '-']);
+    let year = groups.next().and_then(|group| group.parse::<u32>().ok());
+    let month = groups
+        .next()
+        .filter(|group| group.len() == 2)
+        .and_then(|group| group.parse::<u32>().ok());
+    matches!((year, month), (Some(1900..=2099), Some(1..=12)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn redact(text: &str) -> String {
+        Redactor::new(&[]).redact(text)
+    }
+
+    #[test]
+    fn test_detects_emails() {
+        assert_eq!(
+            redact("Write to jane.doe+work@mail.example.co.uk today."),
+            "Write to [EMAIL_1] today."
+        );
+        assert_eq!(redact("Meet @ noon"), "Meet @ noon");
+    }
+
+    #[test]
+    fn test_detects_card_numbers_by_checksum() {
+        assert_eq!(
+            redact("Card 4111 1111 1111 1111, expiry soon"),
+            "Card [CARD_1], expiry soon"
+        );
+        assert_eq!(
+            redact("Order 4111-1111-1111-1112"),
+            "Order 4111-1111-1111-1112"
+        );
+    }
+
+    #[test]
+    fn test_detects_phone_numbers() {
+        assert_eq!(redact("Call +49 30 1234567."), "Call [PHONE_1].");
+        assert_eq!(redact("Call (555) 123-4567 now"), "Call [PHONE_1] now");
+        assert_eq!(redact("Or 555.123.4567"), "Or [PHONE_1]");
+        assert_eq!(
+            redact("On 2024-05-01 we sold 1500 units for 12.50"),
+            "On 2024-05-01 we sold 1500 units for 12.50"
+        );
+        assert_eq!(redact("Ticket ABC1234567890"), "Ticket ABC1234567890");
+        // Only the whole run of digits counts
+        assert_eq!(
+            redact("Paid 1 000 000 000 today"),
+            "Paid 1 000 000 000 today"
+        );
+        assert_eq!(
+            redact("Build 2023.04.01.12 is out"),
+            "Build 2023.04.01.12 is out"
+        );
+    }
+
+    #[test]
+    fn test_detects_configured_names() {
+        let names = vec!["Anna".to_string(), "Anna Lena".to_string(), " ".to_string()];
+        let mut redactor = Redactor::new(&names);
+        assert_eq!(
+            redactor.redact("anna lena met Anna, not Annabel."),
+            "[NAME_1] met [NAME_2], not Annabel."
+        );
+    }
+
+    #[test]
+    fn test_detects_names_ending_in_punctuation() {
+        let mut redactor = Redactor::new(&["Dr. Smith".to_string(), "J.".to_string()]);
+        assert_eq!(
+            redactor.redact("Ask Dr. Smith. Then J. will know, not J.R."),
+            "Ask [NAME_1]. Then [NAME_2] will know, not J.R."
+        );
+    }
+
+    #[test]
+    fn test_placeholders_are_stable_and_restored() {
+        let mut redactor = Redactor::new(&["Bob".to_string()]);
+        let prompt = redactor.redact("Bob (bob@example.com) said bob@example.com twice");
+        let system = redactor.redact("Reply to a@example.com and Bob");
+        assert_eq!(prompt, "[NAME_1] ([EMAIL_1]) said [EMAIL_1] twice");
+        assert_eq!(system, "Reply to [EMAIL_2] and [NAME_1]");
+        assert_eq!(redactor.len(), 3);
+
+        assert_eq!(
+            redactor.restore("Hi [NAME_1], mail [EMAIL_2] or [EMAIL_1]. [PHONE_1] [x]"),
+            "Hi Bob, mail a@example.com or bob@example.com. [PHONE_1] [x]"
+        );
+    }
+
+    #[test]
+    fn test_stream_restorer_handles_split_placeholders() {
+        let mut redactor = Redactor::new(&[]);
+        redactor.redact("mail bob@example.com");
+
+        let mut restorer = redactor.stream_restorer();
+        let mut output = String::new();
+        for delta in ["Write [EM", "AIL_", "1] now [", "see] [EMAIL_1"] {
+            output.push_str(&restorer.push(delta));
+        }
+        assert_eq!(output, "Write bob@example.com now [see] ");
+        output.push_str(&restorer.finish());
+        assert_eq!(output, "Write bob@example.com now [see] [EMAIL_1");
+    }
+}
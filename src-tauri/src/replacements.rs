@@ -0,0 +1,287 @@
+use log::warn;
+use regex::{Captures, Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// A user-defined replacement applied to the transcription, e.g. expanding
+/// "sig block" into a signature or joining "h t t p s" into "https"
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Type)]
+pub struct ReplacementRule {
+    pub pattern: String,
+    /// Replacement text; for regex rules `$1`, `${name}` refer to capture groups
+    pub replacement: String,
+    #[serde(default)]
+    pub is_regex: bool,
+    #[serde(default)]
+    pub case_sensitive: bool,
+    /// Only match at word boundaries, so "cat" doesn't match inside "concatenate"
+    #[serde(default = "default_true")]
+    pub whole_word: bool,
+    /// Upper-case or capitalize the replacement like the matched text
+    #[serde(default)]
+    pub preserve_case: bool,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Compiles a rule into a regex, honouring its literal, case and word options
+pub fn build_rule_regex(rule: &ReplacementRule) -> Result<Regex, String> {
+    if rule.pattern.is_empty() {
+        return Err("Pattern cannot be empty".to_string());
+    }
+
+    let mut pattern = if rule.is_regex {
+        format!("(?:{})", rule.pattern)
+    } else {
+        regex::escape(&rule.pattern)
+    };
+
+    if rule.whole_word && rule.is_regex {
+        // A regex may start or end with a symbol, as in `\$\d+` or `:\)`, where
+        // `\b` would need a word character next to it. Half boundaries only
+        // require that no word character sits outside the match.
+        pattern = format!(r"\b{{start-half}}{}\b{{end-half}}", pattern);
+    } else if rule.whole_word {
+        // A boundary only makes sense next to a word character
+        let starts_with_word = rule.pattern.chars().next().is_some_and(is_word_char);
+        let ends_with_word = rule.pattern.chars().next_back().is_some_and(is_word_char);
+        if starts_with_word {
+            pattern = format!(r"\b{}", pattern);
+        }
+        if ends_with_word {
+            pattern = format!(r"{}\b", pattern);
+        }
+    }
+
+    RegexBuilder::new(&pattern)
+        .case_insensitive(!rule.case_sensitive)
+        .build()
+        .map_err(|e| format!("Invalid pattern '{}': {}", rule.pattern, e))
+}
+
+/// Applies the case pattern of the matched text (ALL CAPS or Capitalized) to
+/// the replacement
+fn match_case(matched: &str, replacement: String) -> String {
+    let letters: Vec<char> = matched.chars().filter(|c| c.is_alphabetic()).collect();
+    if letters.len() > 1 && letters.iter().all(|c| c.is_uppercase()) {
+        replacement.to_uppercase()
+    } else if letters.first().is_some_and(|c| c.is_uppercase()) {
+        let mut chars = replacement.chars();
+        match chars.next() {
+            Some(first) => first.to_uppercase().chain(chars).collect(),
+            None => replacement,
+        }
+    } else {
+        replacement
+    }
+}
+
+/// Applies the enabled rules to the text, in order. Each rule sees the output
+/// of the previous one. Rules with an invalid pattern are skipped.
+pub fn apply_replacements(text: &str, rules: &[ReplacementRule]) -> String {
+    let mut result = text.to_string();
+
+    for rule in rules.iter().filter(|rule| rule.enabled) {
+        let regex = match build_rule_regex(rule) {
+            Ok(regex) => regex,
+            Err(e) => {
+                warn!("Skipping replacement rule: {}", e);
+                continue;
+            }
+        };
+
+        result = regex
+            .replace_all(&result, |caps: &Captures| {
+                let mut replacement = String::new();
+                if rule.is_regex {
+                    caps.expand(&rule.replacement, &mut replacement);
+                } else {
+                    replacement.push_str(&rule.replacement);
+                }
+
+                if rule.preserve_case {
+                    match_case(&caps[0], replacement)
+                } else {
+                    replacement
+                }
+            })
+            .into_owned();
+    }
+
+    result
+}
+
+/// Checks that every rule compiles, reporting the first problem with its position
+pub fn validate_rules(rules: &[ReplacementRule]) -> Result<(), String> {
+    for (index, rule) in rules.iter().enumerate() {
+        build_rule_regex(rule).map_err(|e| format!("Rule {}: {}", index + 1, e))?;
+    }
+    Ok(())
+}
+
+pub fn export_rules_json(rules: &[ReplacementRule]) -> Result<String, String> {
+    serde_json::to_string_pretty(rules)
+        .map_err(|e| format!("Failed to serialize replacement rules: {}", e))
+}
+
+/// Parses and validates rules previously written by [`export_rules_json`]
+pub fn import_rules_json(json: &str) -> Result<Vec<ReplacementRule>, String> {
+    let rules: Vec<ReplacementRule> =
+        serde_json::from_str(json).map_err(|e| format!("Invalid replacement rules file: {}", e))?;
+    validate_rules(&rules)?;
+    Ok(rules)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn literal(pattern: &str, replacement: &str) -> ReplacementRule {
+        ReplacementRule {
+            pattern: pattern.to_string(),
+            replacement: replacement.to_string(),
+            is_regex: false,
+            case_sensitive: false,
+            whole_word: true,
+            preserve_case: false,
+            enabled: true,
+        }
+    }
+
+    fn regex(pattern: &str, replacement: &str) -> ReplacementRule {
+        ReplacementRule {
+            is_regex: true,
+            ..literal(pattern, replacement)
+        }
+    }
+
+    #[test]
+    fn test_literal_expansion() {
+        let rules = vec![literal("my work email", "jane@example.com")];
+        assert_eq!(
+            apply_replacements("send it to My Work Email please", &rules),
+            "send it to jane@example.com please"
+        );
+    }
+
+    #[test]
+    fn test_multi_line_expansion() {
+        let rules = vec![literal("sig block", "Best,\nJane\nExample Inc.")];
+        assert_eq!(
+            apply_replacements("thanks sig block", &rules),
+            "thanks Best,\nJane\nExample Inc."
+        );
+    }
+
+    #[test]
+    fn test_joins_spelled_out_letters() {
+        let rules = vec![literal("h t t p s", "https")];
+        assert_eq!(apply_replacements("H T T P S colon", &rules), "https colon");
+    }
+
+    #[test]
+    fn test_case_sensitive() {
+        let mut rule = literal("Go", "Golang");
+        rule.case_sensitive = true;
+        assert_eq!(
+            apply_replacements("Go is fun, let's go", &[rule]),
+            "Golang is fun, let's go"
+        );
+    }
+
+    #[test]
+    fn test_preserve_case() {
+        let mut rule = literal("asap", "as soon as possible");
+        rule.preserve_case = true;
+        assert_eq!(
+            apply_replacements("ASAP. Asap, asap", &[rule]),
+            "AS SOON AS POSSIBLE. As soon as possible, as soon as possible"
+        );
+    }
+
+    #[test]
+    fn test_whole_word() {
+        let rules = vec![literal("cat", "dog")];
+        assert_eq!(
+            apply_replacements("cat concatenate cat.", &rules),
+            "dog concatenate dog."
+        );
+
+        let mut rule = literal("cat", "dog");
+        rule.whole_word = false;
+        assert_eq!(apply_replacements("concatenate", &[rule]), "condogenate");
+    }
+
+    #[test]
+    fn test_regex_with_capture_groups() {
+        let rules = vec![regex(r"(\d+) percent", "$1%")];
+        assert_eq!(
+            apply_replacements("up 25 percent today", &rules),
+            "up 25% today"
+        );
+    }
+
+    #[test]
+    fn test_whole_word_regex_starting_with_symbols() {
+        let rules = vec![regex(r"\$(\d+)", "$1 dollars"), regex(r":\)", "🙂")];
+        assert_eq!(
+            apply_replacements("it costs $25 :) but not US$5", &rules),
+            "it costs 25 dollars 🙂 but not US$5"
+        );
+    }
+
+    #[test]
+    fn test_literal_replacement_is_not_expanded() {
+        let rules = vec![literal("price tag", "$1 each")];
+        assert_eq!(apply_replacements("price tag", &rules), "$1 each");
+    }
+
+    #[test]
+    fn test_rules_apply_in_order() {
+        let rules = vec![literal("teh", "the"), literal("the end", "FIN")];
+        assert_eq!(apply_replacements("teh end", &rules), "FIN");
+
+        let reversed: Vec<ReplacementRule> = rules.into_iter().rev().collect();
+        assert_eq!(apply_replacements("teh end", &reversed), "the end");
+    }
+
+    #[test]
+    fn test_disabled_and_invalid_rules_are_skipped() {
+        let mut disabled = literal("hello", "bye");
+        disabled.enabled = false;
+        let rules = vec![disabled, regex("(unclosed", "x"), literal("world", "earth")];
+        assert_eq!(apply_replacements("hello world", &rules), "hello earth");
+        assert!(validate_rules(&rules).unwrap_err().starts_with("Rule 2:"));
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let rules = vec![
+            literal("sig block", "Jane\nDoe"),
+            regex(r"(\d+) pct", "$1%"),
+        ];
+        let json = export_rules_json(&rules).unwrap();
+        assert_eq!(import_rules_json(&json).unwrap(), rules);
+    }
+
+    #[test]
+    fn test_import_applies_defaults_and_validates() {
+        let rules =
+            import_rules_json(r#"[{"pattern": "brb", "replacement": "be right back"}]"#).unwrap();
+        assert!(rules[0].whole_word && rules[0].enabled && !rules[0].is_regex);
+
+        assert!(
+            import_rules_json(r#"[{"pattern": "(", "replacement": "", "is_regex": true}]"#)
+                .is_err()
+        );
+        assert!(import_rules_json("not json").is_err());
+    }
+}
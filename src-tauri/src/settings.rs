@@ -1,9 +1,14 @@
-use log::{debug, warn};
+use crate::audio_toolkit::PhoneticAlgorithm;
+use crate::managers::secrets::{mask_secret, post_process_api_key_name, SecretManager};
+use crate::replacements::ReplacementRule;
+use crate::text_pipeline::{default_text_pipeline, TextCasing, TextStage};
+use log::{debug, error, info, warn};
 use serde::de::{self, Visitor};
 use serde::{Deserialize, Deserializer, Serialize};
 use specta::Type;
 use std::collections::HashMap;
-use tauri::AppHandle;
+use std::sync::Arc;
+use tauri::{AppHandle, Manager};
 use tauri_plugin_store::StoreExt;
 
 pub const APPLE_INTELLIGENCE_PROVIDER_ID: &str = "apple_intelligence";
@@ -83,13 +88,96 @@ pub struct ShortcutBinding {
     pub description: String,
     pub default_binding: String,
     pub current_binding: String,
+    /// Prompt used to post-process dictations started from an extra
+    /// transcribe binding; `None` pastes them unprocessed
+    #[serde(default)]
+    pub prompt_id: Option<String>,
+}
+
+/// Bindings the user adds to dictate with a prompt of their choice have ids
+/// starting with this
+pub const EXTRA_TRANSCRIBE_BINDING_PREFIX: &str = "transcribe_";
+
+pub fn is_extra_transcribe_binding(id: &str) -> bool {
+    id.starts_with(EXTRA_TRANSCRIBE_BINDING_PREFIX)
+}
+
+/// Connection details for a server speaking the OpenAI `/v1/audio/transcriptions` protocol
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Type)]
+pub struct RemoteTranscriptionSettings {
+    pub base_url: String,
+    #[serde(default)]
+    pub api_key: String,
+    pub model: String,
+    pub timeout_seconds: u64,
+}
+
+impl Default for RemoteTranscriptionSettings {
+    fn default() -> Self {
+        Self {
+            base_url: "http://localhost:8000/v1".to_string(),
+            api_key: String::new(),
+            model: "whisper-1".to_string(),
+            timeout_seconds: 60,
+        }
+    }
+}
+
+/// Local cleanup of fillers, repetitions and self-corrections
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Type)]
+pub struct DisfluencyRemovalSettings {
+    pub enabled: bool,
+    pub remove_fillers: bool,
+    pub remove_repetitions: bool,
+    pub remove_self_corrections: bool,
+    /// Extra words the user wants removed as fillers
+    #[serde(default)]
+    pub extra_fillers: Vec<String>,
+}
+
+impl Default for DisfluencyRemovalSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            remove_fillers: true,
+            remove_repetitions: true,
+            remove_self_corrections: true,
+            extra_fillers: Vec::new(),
+        }
+    }
+}
+
+/// What a provider charges for tokens, in US dollars per million tokens
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Type)]
+pub struct TokenPrice {
+    pub provider_id: String,
+    /// Model the price applies to. Without one it covers every model of the
+    /// provider that has no price of its own.
+    #[serde(default)]
+    pub model: Option<String>,
+    pub input_per_million: f64,
+    pub output_per_million: f64,
+}
+
+/// Redaction of personal data before text is sent to a cloud LLM provider
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Type)]
+pub struct PiiRedactionSettings {
+    pub enabled: bool,
+    /// Names and other terms that are always redacted, on top of the emails,
+    /// phone numbers and card numbers found automatically
+    #[serde(default)]
+    pub names: Vec<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Type)]
 pub struct LLMPrompt {
     pub id: String,
     pub name: String,
+    /// Sent as the user message
     pub prompt: String,
+    /// Sent as the system message; empty sends none
+    #[serde(default)]
+    pub system_prompt: String,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Type)]
@@ -101,6 +189,31 @@ pub struct PostProcessProvider {
     pub allow_base_url_edit: bool,
     #[serde(default)]
     pub models_endpoint: Option<String>,
+    #[serde(default)]
+    pub protocol: ProviderProtocol,
+    /// Runs on this machine, so needs no API key
+    #[serde(default)]
+    pub local: bool,
+    /// How long to wait for the provider to send anything before giving up
+    #[serde(default = "default_provider_timeout_secs")]
+    pub timeout_secs: u64,
+    /// Extra attempts after a rate limit or server error
+    #[serde(default = "default_provider_max_retries")]
+    pub max_retries: u32,
+}
+
+/// Wire format used to talk to a post-processing provider
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum ProviderProtocol {
+    /// OpenAI Chat Completions, also spoken by most hosted and local servers
+    #[default]
+    #[serde(rename = "openai_chat")]
+    OpenAiChat,
+    /// Anthropic Messages API
+    AnthropicMessages,
+    /// Ollama's native `/api/chat`
+    OllamaChat,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Type)]
@@ -134,6 +247,20 @@ pub enum PasteMethod {
     CtrlShiftV,
 }
 
+/// How LLM post-processing output is shown while it streams in
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum LlmStreamingMode {
+    /// Wait for the whole reply, then paste it
+    #[default]
+    Disabled,
+    /// Show the reply in the overlay as it arrives, then paste it
+    Overlay,
+    /// Type the reply into the focused app as it arrives. Falls back to the
+    /// overlay when a later pipeline stage would still change the text.
+    Type,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Type)]
 #[serde(rename_all = "snake_case")]
 pub enum ClipboardHandling {
@@ -279,6 +406,13 @@ pub struct AppSettings {
     pub post_process_provider_id: String,
     #[serde(default = "default_post_process_providers")]
     pub post_process_providers: Vec<PostProcessProvider>,
+    /// Providers tried in order when the selected one fails, before falling
+    /// back to the unprocessed text
+    #[serde(default)]
+    pub post_process_fallback_provider_ids: Vec<String>,
+    /// Used to estimate what post-processing costs
+    #[serde(default)]
+    pub post_process_prices: Vec<TokenPrice>,
     #[serde(default = "default_post_process_api_keys")]
     pub post_process_api_keys: HashMap<String, String>,
     #[serde(default = "default_post_process_models")]
@@ -288,11 +422,54 @@ pub struct AppSettings {
     #[serde(default)]
     pub post_process_selected_prompt_id: Option<String>,
     #[serde(default)]
+    pub post_process_streaming: LlmStreamingMode,
+    /// Local providers always receive the unredacted text
+    #[serde(default)]
+    pub pii_redaction: PiiRedactionSettings,
+    #[serde(default)]
     pub mute_while_recording: bool,
     #[serde(default)]
     pub append_trailing_space: bool,
+    /// Match capitalisation, spacing and the final full stop to the text
+    /// before the cursor
+    #[serde(default)]
+    pub context_aware_insertion: bool,
     #[serde(default = "default_app_language")]
     pub app_language: String,
+    #[serde(default)]
+    pub remote_transcription: RemoteTranscriptionSettings,
+    #[serde(default)]
+    pub language_model_routes: HashMap<String, String>,
+    /// Languages the user speaks; automatic detection only chooses among these
+    #[serde(default)]
+    pub spoken_languages: Vec<String>,
+    #[serde(default)]
+    pub translation_enabled: bool,
+    /// Language to translate dictation into, e.g. "German" or "de"
+    #[serde(default = "default_translation_target_language")]
+    pub translation_target_language: String,
+    #[serde(default = "default_translation_prompt")]
+    pub translation_prompt: String,
+    /// Convert dictated "comma", "new line", etc. into punctuation and formatting
+    #[serde(default)]
+    pub spoken_commands_enabled: bool,
+    /// Write spoken numbers, dates, times, currency and units in written form
+    #[serde(default)]
+    pub inverse_text_normalization_enabled: bool,
+    #[serde(default)]
+    pub disfluency_removal: DisfluencyRemovalSettings,
+    /// User replacement rules, applied in order after transcription
+    #[serde(default)]
+    pub replacement_rules: Vec<ReplacementRule>,
+    /// Per-language overrides of the phonetic algorithm used for custom words;
+    /// languages without an entry use a default suited to the language
+    #[serde(default)]
+    pub phonetic_algorithms: HashMap<String, PhoneticAlgorithm>,
+    /// Text-processing stages in the order they run after transcription
+    #[serde(default = "default_text_pipeline")]
+    pub text_pipeline: Vec<TextStage>,
+    #[serde(default)]
+    pub text_casing: TextCasing,
 }
 
 fn default_model() -> String {
@@ -372,6 +549,17 @@ fn default_post_process_provider_id() -> String {
     "openai".to_string()
 }
 
+fn default_provider_timeout_secs() -> u64 {
+    30
+}
+
+/// Local models can take a while to load and run
+const LOCAL_PROVIDER_TIMEOUT_SECS: u64 = 120;
+
+fn default_provider_max_retries() -> u32 {
+    2
+}
+
 fn default_post_process_providers() -> Vec<PostProcessProvider> {
     let mut providers = vec![
         PostProcessProvider {
@@ -380,6 +568,10 @@ fn default_post_process_providers() -> Vec<PostProcessProvider> {
             base_url: "https://api.openai.com/v1".to_string(),
             allow_base_url_edit: false,
             models_endpoint: Some("/models".to_string()),
+            protocol: ProviderProtocol::OpenAiChat,
+            local: false,
+            timeout_secs: default_provider_timeout_secs(),
+            max_retries: default_provider_max_retries(),
         },
         PostProcessProvider {
             id: "openrouter".to_string(),
@@ -387,6 +579,10 @@ fn default_post_process_providers() -> Vec<PostProcessProvider> {
             base_url: "https://openrouter.ai/api/v1".to_string(),
             allow_base_url_edit: false,
             models_endpoint: Some("/models".to_string()),
+            protocol: ProviderProtocol::OpenAiChat,
+            local: false,
+            timeout_secs: default_provider_timeout_secs(),
+            max_retries: default_provider_max_retries(),
         },
         PostProcessProvider {
             id: "anthropic".to_string(),
@@ -394,6 +590,10 @@ fn default_post_process_providers() -> Vec<PostProcessProvider> {
             base_url: "https://api.anthropic.com/v1".to_string(),
             allow_base_url_edit: false,
             models_endpoint: Some("/models".to_string()),
+            protocol: ProviderProtocol::AnthropicMessages,
+            local: false,
+            timeout_secs: default_provider_timeout_secs(),
+            max_retries: default_provider_max_retries(),
         },
         PostProcessProvider {
             id: "groq".to_string(),
@@ -401,6 +601,10 @@ fn default_post_process_providers() -> Vec<PostProcessProvider> {
             base_url: "https://api.groq.com/openai/v1".to_string(),
             allow_base_url_edit: false,
             models_endpoint: Some("/models".to_string()),
+            protocol: ProviderProtocol::OpenAiChat,
+            local: false,
+            timeout_secs: default_provider_timeout_secs(),
+            max_retries: default_provider_max_retries(),
         },
         PostProcessProvider {
             id: "cerebras".to_string(),
@@ -408,9 +612,37 @@ fn default_post_process_providers() -> Vec<PostProcessProvider> {
             base_url: "https://api.cerebras.ai/v1".to_string(),
             allow_base_url_edit: false,
             models_endpoint: Some("/models".to_string()),
+            protocol: ProviderProtocol::OpenAiChat,
+            local: false,
+            timeout_secs: default_provider_timeout_secs(),
+            max_retries: default_provider_max_retries(),
         },
     ];
 
+    // Local servers, for post-processing without sending text off the machine
+    providers.push(PostProcessProvider {
+        id: "ollama".to_string(),
+        label: "Ollama".to_string(),
+        base_url: "http://localhost:11434".to_string(),
+        allow_base_url_edit: true,
+        models_endpoint: Some("/api/tags".to_string()),
+        protocol: ProviderProtocol::OllamaChat,
+        local: true,
+        timeout_secs: LOCAL_PROVIDER_TIMEOUT_SECS,
+        max_retries: 0,
+    });
+    providers.push(PostProcessProvider {
+        id: "llamacpp".to_string(),
+        label: "llama.cpp server".to_string(),
+        base_url: "http://localhost:8080/v1".to_string(),
+        allow_base_url_edit: true,
+        models_endpoint: Some("/models".to_string()),
+        protocol: ProviderProtocol::OpenAiChat,
+        local: true,
+        timeout_secs: LOCAL_PROVIDER_TIMEOUT_SECS,
+        max_retries: 0,
+    });
+
     // Note: We always include Apple Intelligence on macOS ARM64 without checking availability
     // at startup. The availability check is deferred to when the user actually tries to use it
     // (in actions.rs). This prevents crashes on macOS 26.x beta where accessing
@@ -423,6 +655,10 @@ fn default_post_process_providers() -> Vec<PostProcessProvider> {
             base_url: "apple-intelligence://local".to_string(),
             allow_base_url_edit: false,
             models_endpoint: None,
+            protocol: ProviderProtocol::OpenAiChat,
+            local: false,
+            timeout_secs: default_provider_timeout_secs(),
+            max_retries: default_provider_max_retries(),
         });
     }
 
@@ -433,6 +669,10 @@ fn default_post_process_providers() -> Vec<PostProcessProvider> {
         base_url: "http://localhost:11434/v1".to_string(),
         allow_base_url_edit: true,
         models_endpoint: Some("/models".to_string()),
+        protocol: ProviderProtocol::OpenAiChat,
+        local: false,
+        timeout_secs: default_provider_timeout_secs(),
+        max_retries: default_provider_max_retries(),
     });
 
     providers
@@ -469,9 +709,18 @@ fn default_post_process_prompts() -> Vec<LLMPrompt> {
         id: "default_improve_transcriptions".to_string(),
         name: "Improve Transcriptions".to_string(),
         prompt: "Clean this transcript:\n1. Fix spelling, capitalization, and punctuation errors\n2. Convert number words to digits (twenty-five → 25, ten percent → 10%, five dollars → $5)\n3. Replace spoken punctuation with symbols (period → ., comma → ,, question mark → ?)\n4. Remove filler words (um, uh, like as filler)\n5. Keep the language in the original version (if it was french, keep it in french for example)\n\nPreserve exact meaning and word order. Do not paraphrase or reorder content.\n\nReturn only the cleaned transcript.\n\nTranscript:\n${output}".to_string(),
+        system_prompt: String::new(),
     }]
 }
 
+fn default_translation_target_language() -> String {
+    "English".to_string()
+}
+
+fn default_translation_prompt() -> String {
+    "Translate the following text into ${target_language}.\n\nPreserve the meaning, tone and formatting. Keep names, code and URLs unchanged. If the text is already in ${target_language}, return it unchanged.\n\nReturn only the translation.\n\nText:\n${output}".to_string()
+}
+
 fn ensure_post_process_defaults(settings: &mut AppSettings) -> bool {
     let mut changed = false;
     for provider in default_post_process_providers() {
@@ -482,6 +731,15 @@ fn ensure_post_process_defaults(settings: &mut AppSettings) -> bool {
         {
             settings.post_process_providers.push(provider.clone());
             changed = true;
+        } else if provider.id != "custom" {
+            // Built-in providers always speak the protocol they ship with
+            if let Some(existing) = settings.post_process_provider_mut(&provider.id) {
+                if existing.protocol != provider.protocol || existing.local != provider.local {
+                    existing.protocol = provider.protocol;
+                    existing.local = provider.local;
+                    changed = true;
+                }
+            }
         }
 
         if !settings.post_process_api_keys.contains_key(&provider.id) {
@@ -512,6 +770,7 @@ fn ensure_post_process_defaults(settings: &mut AppSettings) -> bool {
 }
 
 pub const SETTINGS_STORE_PATH: &str = "settings_store.json";
+const REMOTE_TRANSCRIPTION_API_KEY: &str = "remote_transcription_api_key";
 
 pub fn get_default_settings() -> AppSettings {
     #[cfg(target_os = "windows")]
@@ -532,6 +791,19 @@ pub fn get_default_settings() -> AppSettings {
             description: "Converts your speech into text.".to_string(),
             default_binding: default_shortcut.to_string(),
             current_binding: default_shortcut.to_string(),
+            prompt_id: None,
+        },
+    );
+    bindings.insert(
+        "voice_edit".to_string(),
+        ShortcutBinding {
+            id: "voice_edit".to_string(),
+            name: "Voice Edit".to_string(),
+            description: "Rewrites the selected text following a spoken instruction.".to_string(),
+            // Unbound so that upgrading doesn't claim a new global hotkey
+            default_binding: String::new(),
+            current_binding: String::new(),
+            prompt_id: None,
         },
     );
     bindings.insert(
@@ -542,6 +814,7 @@ pub fn get_default_settings() -> AppSettings {
             description: "Cancels the current recording.".to_string(),
             default_binding: "escape".to_string(),
             current_binding: "escape".to_string(),
+            prompt_id: None,
         },
     );
 
@@ -574,17 +847,118 @@ pub fn get_default_settings() -> AppSettings {
         post_process_enabled: default_post_process_enabled(),
         post_process_provider_id: default_post_process_provider_id(),
         post_process_providers: default_post_process_providers(),
+        post_process_fallback_provider_ids: Vec::new(),
+        post_process_prices: Vec::new(),
         post_process_api_keys: default_post_process_api_keys(),
         post_process_models: default_post_process_models(),
         post_process_prompts: default_post_process_prompts(),
         post_process_selected_prompt_id: None,
+        post_process_streaming: LlmStreamingMode::default(),
+        pii_redaction: PiiRedactionSettings::default(),
         mute_while_recording: false,
         append_trailing_space: false,
+        context_aware_insertion: false,
         app_language: default_app_language(),
+        remote_transcription: RemoteTranscriptionSettings::default(),
+        language_model_routes: HashMap::new(),
+        spoken_languages: Vec::new(),
+        translation_enabled: false,
+        translation_target_language: default_translation_target_language(),
+        translation_prompt: default_translation_prompt(),
+        spoken_commands_enabled: false,
+        inverse_text_normalization_enabled: false,
+        disfluency_removal: DisfluencyRemovalSettings::default(),
+        replacement_rules: Vec::new(),
+        phonetic_algorithms: HashMap::new(),
+        text_pipeline: default_text_pipeline(),
+        text_casing: TextCasing::default(),
     }
 }
 
 impl AppSettings {
+    /// API keys kept in the secret store instead of the settings file, paired
+    /// with their name in the store
+    fn secrets_mut(&mut self) -> impl Iterator<Item = (String, &mut String)> {
+        self.post_process_api_keys
+            .iter_mut()
+            .map(|(provider_id, api_key)| (post_process_api_key_name(provider_id), api_key))
+            .chain(std::iter::once((
+                REMOTE_TRANSCRIPTION_API_KEY.to_string(),
+                &mut self.remote_transcription.api_key,
+            )))
+    }
+
+    /// Fills in the API keys held by the secret store
+    fn load_secrets(&mut self, secrets: &SecretManager) {
+        for (name, value) in self.secrets_mut() {
+            if let Some(secret) = secrets.get(&name) {
+                *value = secret;
+            }
+        }
+    }
+
+    /// Hands the API keys to the secret store. A key only stays in the
+    /// settings if the store fails to take it.
+    fn store_secrets(&mut self, secrets: &SecretManager) {
+        for (name, value) in self.secrets_mut() {
+            match secrets.set(&name, value) {
+                Ok(()) => value.clear(),
+                Err(e) => error!("Failed to store secret '{}': {}", name, e),
+            }
+        }
+    }
+
+    fn stored_secret_count(&mut self) -> usize {
+        self.secrets_mut()
+            .filter(|(_, secret)| !secret.is_empty())
+            .count()
+    }
+
+    /// Replaces every API key with a masked form the UI can show
+    pub fn mask_secrets(&mut self) {
+        for (_, secret) in self.secrets_mut() {
+            *secret = mask_secret(secret);
+        }
+    }
+
+    /// Model routed to for a language code, matching on the primary subtag so
+    /// that a detected "zh" finds a "zh-Hans" route
+    pub fn model_for_language(&self, language: &str) -> Option<&str> {
+        let primary = |code: &str| code.split('-').next().unwrap_or(code).to_lowercase();
+        self.language_model_routes
+            .get(language)
+            .or_else(|| {
+                self.language_model_routes
+                    .iter()
+                    .find(|(route, _)| primary(route) == primary(language))
+                    .map(|(_, model)| model)
+            })
+            .map(String::as_str)
+    }
+
+    /// Model to transcribe a language with: its route, or the selected model
+    /// when the language has none
+    pub fn model_for_transcription(&self, language: &str) -> &str {
+        self.model_for_language(language)
+            .unwrap_or(self.selected_model.as_str())
+    }
+
+    /// Phonetic algorithm for custom word correction in a language, preferring
+    /// the user's override for the language or its primary subtag
+    pub fn phonetic_algorithm_for(&self, language: &str) -> PhoneticAlgorithm {
+        let primary = |code: &str| code.split('-').next().unwrap_or(code).to_lowercase();
+        self.phonetic_algorithms
+            .get(language)
+            .or_else(|| {
+                self.phonetic_algorithms
+                    .iter()
+                    .find(|(code, _)| primary(code) == primary(language))
+                    .map(|(_, algorithm)| algorithm)
+            })
+            .copied()
+            .unwrap_or_else(|| PhoneticAlgorithm::for_language(language))
+    }
+
     pub fn active_post_process_provider(&self) -> Option<&PostProcessProvider> {
         self.post_process_providers
             .iter()
@@ -679,17 +1053,67 @@ pub fn get_settings(app: &AppHandle) -> AppSettings {
         store.set("settings", serde_json::to_value(&settings).unwrap());
     }
 
+    if let Some(secrets) = app.try_state::<Arc<SecretManager>>() {
+        settings.load_secrets(&secrets);
+    }
+
     settings
 }
 
-pub fn write_settings(app: &AppHandle, settings: AppSettings) {
+pub fn write_settings(app: &AppHandle, mut settings: AppSettings) {
     let store = app
         .store(SETTINGS_STORE_PATH)
         .expect("Failed to initialize store");
 
+    if let Some(secrets) = app.try_state::<Arc<SecretManager>>() {
+        settings.store_secrets(&secrets);
+    }
+
     store.set("settings", serde_json::to_value(&settings).unwrap());
 }
 
+/// Moves API keys that earlier versions saved in the settings file into the
+/// secret store
+pub fn migrate_secrets_to_secret_store(app: &AppHandle) {
+    let Some(secrets) = app.try_state::<Arc<SecretManager>>() else {
+        return;
+    };
+    let store = app
+        .store(SETTINGS_STORE_PATH)
+        .expect("Failed to initialize store");
+
+    let Some(mut stored) = store
+        .get("settings")
+        .and_then(|value| serde_json::from_value::<AppSettings>(value).ok())
+    else {
+        return;
+    };
+    let count = move_secrets_to_store(&mut stored, &secrets);
+    if count == 0 {
+        return;
+    }
+
+    store.set("settings", serde_json::to_value(&stored).unwrap());
+    info!(
+        "Moved {} API keys from the settings file into the secret store",
+        count
+    );
+}
+
+/// Returns how many API keys left the settings
+fn move_secrets_to_store(settings: &mut AppSettings, secrets: &SecretManager) -> usize {
+    let count = settings.stored_secret_count();
+    if count == 0 {
+        return 0;
+    }
+
+    // Keys already in the store win, the same as when reading settings, so
+    // that an empty value in the file never removes one
+    settings.load_secrets(secrets);
+    settings.store_secrets(secrets);
+    count - settings.stored_secret_count()
+}
+
 pub fn get_bindings(app: &AppHandle) -> HashMap<String, ShortcutBinding> {
     let settings = get_settings(app);
 
@@ -713,3 +1137,105 @@ pub fn get_recording_retention_period(app: &AppHandle) -> RecordingRetentionPeri
     let settings = get_settings(app);
     settings.recording_retention_period
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::managers::secrets::SecretBackend;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Mutex;
+
+    /// A keyring that can be locked, like the Secret Service before login
+    #[derive(Default)]
+    struct MemoryBackend {
+        secrets: Mutex<HashMap<String, String>>,
+        locked: AtomicBool,
+    }
+
+    impl MemoryBackend {
+        fn check(&self) -> anyhow::Result<()> {
+            if self.locked.load(Ordering::SeqCst) {
+                anyhow::bail!("keyring is locked");
+            }
+            Ok(())
+        }
+    }
+
+    impl SecretBackend for Arc<MemoryBackend> {
+        fn name(&self) -> &'static str {
+            "test keyring"
+        }
+
+        fn get(&self, name: &str) -> anyhow::Result<Option<String>> {
+            self.check()?;
+            Ok(self.secrets.lock().unwrap().get(name).cloned())
+        }
+
+        fn set(&self, name: &str, secret: &str) -> anyhow::Result<()> {
+            self.check()?;
+            self.secrets
+                .lock()
+                .unwrap()
+                .insert(name.to_string(), secret.to_string());
+            Ok(())
+        }
+
+        fn delete(&self, name: &str) -> anyhow::Result<()> {
+            self.check()?;
+            self.secrets.lock().unwrap().remove(name);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_migration_moves_secrets_out_of_settings() {
+        let backend = Arc::new(MemoryBackend::default());
+        let manager = SecretManager::with_backend(Box::new(Arc::clone(&backend)));
+        let mut stored = get_default_settings();
+        stored
+            .post_process_api_keys
+            .insert("openai".to_string(), "sk-openai".to_string());
+        stored.remote_transcription.api_key = "gsk-remote".to_string();
+
+        // While the keyring is locked the keys stay where they are
+        backend.locked.store(true, Ordering::SeqCst);
+        assert_eq!(move_secrets_to_store(&mut stored, &manager), 0);
+        assert_eq!(stored.post_process_api_keys["openai"], "sk-openai");
+
+        backend.locked.store(false, Ordering::SeqCst);
+        assert_eq!(move_secrets_to_store(&mut stored, &manager), 2);
+        assert_eq!(stored.post_process_api_keys["openai"], "");
+        assert_eq!(stored.remote_transcription.api_key, "");
+        assert_eq!(
+            manager.get(&post_process_api_key_name("openai")).as_deref(),
+            Some("sk-openai")
+        );
+
+        // A later run finds nothing to move and leaves the store alone
+        assert_eq!(move_secrets_to_store(&mut stored, &manager), 0);
+        stored.load_secrets(&manager);
+        assert_eq!(stored.post_process_api_keys["openai"], "sk-openai");
+        assert_eq!(stored.remote_transcription.api_key, "gsk-remote");
+    }
+
+    #[test]
+    fn test_model_for_transcription_routes_or_falls_back() {
+        let mut settings = get_default_settings();
+        settings.selected_model = "whisper-large".to_string();
+        settings
+            .language_model_routes
+            .insert("en".to_string(), "parakeet-tdt-0.6b-v2".to_string());
+        settings
+            .language_model_routes
+            .insert("zh-Hans".to_string(), "sense-voice".to_string());
+
+        assert_eq!(
+            settings.model_for_transcription("en"),
+            "parakeet-tdt-0.6b-v2"
+        );
+        assert_eq!(settings.model_for_transcription("zh"), "sense-voice");
+        // French has no route, so it goes back to the selected model even
+        // after an English dictation switched to Parakeet
+        assert_eq!(settings.model_for_transcription("fr"), "whisper-large");
+    }
+}
@@ -6,13 +6,19 @@ use tauri::{AppHandle, Emitter, Manager};
 use tauri_plugin_autostart::ManagerExt;
 use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
 
-use crate::actions::ACTION_MAP;
+use crate::actions::{action_for, register_transcribe_action, unregister_action};
+use crate::audio_toolkit::PhoneticAlgorithm;
 use crate::managers::audio::AudioRecordingManager;
+use crate::managers::secrets::mask_secret;
+use crate::prompt_template::validate_prompt_template;
+use crate::replacements::{self, ReplacementRule};
 use crate::settings::ShortcutBinding;
 use crate::settings::{
-    self, get_settings, ClipboardHandling, LLMPrompt, OverlayPosition, PasteMethod, SoundTheme,
+    self, get_settings, ClipboardHandling, DisfluencyRemovalSettings, LLMPrompt, LlmStreamingMode,
+    OverlayPosition, PasteMethod, PiiRedactionSettings, SoundTheme, TokenPrice,
     APPLE_INTELLIGENCE_DEFAULT_MODEL_ID, APPLE_INTELLIGENCE_PROVIDER_ID,
 };
+use crate::text_pipeline::{TextCasing, TextStage};
 use crate::tray;
 use crate::ManagedToggleState;
 
@@ -35,6 +41,17 @@ pub fn init_shortcuts(app: &AppHandle) {
             error!("Failed to register shortcut {} during init: {}", id, e);
         }
     }
+
+    // Then the transcribe bindings the user added
+    for (id, binding) in user_settings.bindings {
+        if !settings::is_extra_transcribe_binding(&id) {
+            continue;
+        }
+        register_transcribe_action(&id);
+        if let Err(e) = register_shortcut(app, binding) {
+            error!("Failed to register shortcut {} during init: {}", id, e);
+        }
+    }
 }
 
 #[derive(Serialize, Type)]
@@ -122,6 +139,152 @@ pub fn change_binding(
     })
 }
 
+/// Validates the prompt an extra transcribe binding uses, if it has one
+fn validate_binding_prompt(
+    settings: &settings::AppSettings,
+    prompt_id: Option<&String>,
+) -> Result<(), String> {
+    if let Some(prompt_id) = prompt_id {
+        if !settings
+            .post_process_prompts
+            .iter()
+            .any(|prompt| &prompt.id == prompt_id)
+        {
+            return Err(format!("Prompt with id '{}' not found", prompt_id));
+        }
+    }
+    Ok(())
+}
+
+/// Adds an extra transcribe binding to `settings` and returns it
+fn insert_transcribe_binding(
+    settings: &mut settings::AppSettings,
+    name: &str,
+    binding: String,
+    prompt_id: Option<String>,
+) -> Result<ShortcutBinding, String> {
+    if name.trim().is_empty() {
+        return Err("Name cannot be empty".to_string());
+    }
+    validate_binding_prompt(settings, prompt_id.as_ref())?;
+    validate_shortcut_string(&binding)?;
+
+    // Ids come from the clock; bump past any taken within the same millisecond
+    let mut stamp = chrono::Utc::now().timestamp_millis();
+    let id = loop {
+        let id = format!("{}{}", settings::EXTRA_TRANSCRIBE_BINDING_PREFIX, stamp);
+        if !settings.bindings.contains_key(&id) {
+            break id;
+        }
+        stamp += 1;
+    };
+    let new_binding = ShortcutBinding {
+        id: id.clone(),
+        name: name.trim().to_string(),
+        description: "Converts your speech into text using its own prompt.".to_string(),
+        default_binding: binding.clone(),
+        current_binding: binding,
+        prompt_id,
+    };
+    settings.bindings.insert(id, new_binding.clone());
+    Ok(new_binding)
+}
+
+/// Removes an extra transcribe binding from `settings` and returns it
+fn take_transcribe_binding(
+    settings: &mut settings::AppSettings,
+    id: &str,
+) -> Result<ShortcutBinding, String> {
+    if !settings::is_extra_transcribe_binding(id) {
+        return Err(format!("Binding '{}' can't be removed", id));
+    }
+    settings
+        .bindings
+        .remove(id)
+        .ok_or_else(|| format!("Binding with id '{}' not found", id))
+}
+
+/// Sets the prompt an extra transcribe binding post-processes with
+fn set_binding_prompt(
+    settings: &mut settings::AppSettings,
+    id: &str,
+    prompt_id: Option<String>,
+) -> Result<(), String> {
+    if !settings::is_extra_transcribe_binding(id) {
+        return Err(format!(
+            "Binding '{}' uses the selected post-processing prompt",
+            id
+        ));
+    }
+    validate_binding_prompt(settings, prompt_id.as_ref())?;
+    let binding = settings
+        .bindings
+        .get_mut(id)
+        .ok_or_else(|| format!("Binding with id '{}' not found", id))?;
+    binding.prompt_id = prompt_id;
+    Ok(())
+}
+
+/// Adds a shortcut that dictates like "transcribe" but post-processes with its
+/// own prompt, or not at all when `prompt_id` is `None`
+#[tauri::command]
+#[specta::specta]
+pub fn add_transcribe_binding(
+    app: AppHandle,
+    name: String,
+    binding: String,
+    prompt_id: Option<String>,
+) -> Result<ShortcutBinding, String> {
+    let mut settings = settings::get_settings(&app);
+    let new_binding = insert_transcribe_binding(&mut settings, &name, binding, prompt_id)?;
+
+    register_transcribe_action(&new_binding.id);
+    if let Err(e) = register_shortcut(&app, new_binding.clone()) {
+        unregister_action(&new_binding.id);
+        return Err(e);
+    }
+
+    settings::write_settings(&app, settings);
+    Ok(new_binding)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn remove_transcribe_binding(app: AppHandle, id: String) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    let binding = take_transcribe_binding(&mut settings, &id)?;
+
+    // Stop a recording this binding started so its release never goes missing
+    let audio_manager = app.state::<Arc<AudioRecordingManager>>();
+    if audio_manager.is_recording_for(&id) {
+        crate::utils::cancel_current_operation(&app);
+    }
+    if let Ok(mut states) = app.state::<ManagedToggleState>().lock() {
+        states.active_toggles.remove(&id);
+    }
+
+    if let Err(e) = unregister_shortcut(&app, binding) {
+        warn!("remove_transcribe_binding: {}", e);
+    }
+    unregister_action(&id);
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_binding_prompt_setting(
+    app: AppHandle,
+    id: String,
+    prompt_id: Option<String>,
+) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    set_binding_prompt(&mut settings, &id, prompt_id)?;
+
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
 #[tauri::command]
 #[specta::specta]
 pub fn reset_binding(app: AppHandle, id: String) -> Result<BindingResponse, String> {
@@ -312,6 +475,47 @@ pub fn update_custom_words(app: AppHandle, words: Vec<String>) -> Result<(), Str
     Ok(())
 }
 
+#[tauri::command]
+#[specta::specta]
+pub fn update_replacement_rules(app: AppHandle, rules: Vec<ReplacementRule>) -> Result<(), String> {
+    replacements::validate_rules(&rules)?;
+
+    let mut settings = settings::get_settings(&app);
+    settings.replacement_rules = rules;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+/// Serialize the replacement rules as JSON for the frontend to save to a file
+#[tauri::command]
+#[specta::specta]
+pub fn export_replacement_rules(app: AppHandle) -> Result<String, String> {
+    let settings = settings::get_settings(&app);
+    replacements::export_rules_json(&settings.replacement_rules)
+}
+
+/// Import replacement rules from JSON, either appending them to the existing
+/// rules or replacing them. Returns the resulting rule list.
+#[tauri::command]
+#[specta::specta]
+pub fn import_replacement_rules(
+    app: AppHandle,
+    json: String,
+    replace_existing: bool,
+) -> Result<Vec<ReplacementRule>, String> {
+    let imported = replacements::import_rules_json(&json)?;
+
+    let mut settings = settings::get_settings(&app);
+    if replace_existing {
+        settings.replacement_rules = imported;
+    } else {
+        settings.replacement_rules.extend(imported);
+    }
+    let rules = settings.replacement_rules.clone();
+    settings::write_settings(&app, settings);
+    Ok(rules)
+}
+
 #[tauri::command]
 #[specta::specta]
 pub fn change_word_correction_threshold_setting(
@@ -373,6 +577,18 @@ pub fn change_post_process_enabled_setting(app: AppHandle, enabled: bool) -> Res
     Ok(())
 }
 
+#[tauri::command]
+#[specta::specta]
+pub fn change_post_process_streaming_setting(
+    app: AppHandle,
+    mode: LlmStreamingMode,
+) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.post_process_streaming = mode;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
 #[tauri::command]
 #[specta::specta]
 pub fn change_post_process_base_url_setting(
@@ -390,7 +606,7 @@ pub fn change_post_process_base_url_setting(
         .post_process_provider_mut(&provider_id)
         .expect("Provider looked up above must exist");
 
-    if provider.id != "custom" {
+    if !provider.allow_base_url_edit {
         return Err(format!(
             "Provider '{}' does not allow editing the base URL",
             label
@@ -402,6 +618,118 @@ pub fn change_post_process_base_url_setting(
     Ok(())
 }
 
+#[tauri::command]
+#[specta::specta]
+pub fn change_post_process_protocol_setting(
+    app: AppHandle,
+    provider_id: String,
+    protocol: settings::ProviderProtocol,
+) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    let provider = settings
+        .post_process_provider_mut(&provider_id)
+        .ok_or_else(|| format!("Provider '{}' not found", provider_id))?;
+
+    if provider.id != "custom" {
+        return Err(format!(
+            "Provider '{}' does not allow changing the protocol",
+            provider.label
+        ));
+    }
+
+    provider.protocol = protocol;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_post_process_request_limits_setting(
+    app: AppHandle,
+    provider_id: String,
+    timeout_secs: u64,
+    max_retries: u32,
+) -> Result<(), String> {
+    if !(1..=600).contains(&timeout_secs) {
+        return Err("Timeout must be between 1 and 600 seconds".to_string());
+    }
+    if max_retries > 5 {
+        return Err("At most 5 retries are allowed".to_string());
+    }
+
+    let mut settings = settings::get_settings(&app);
+    let provider = settings
+        .post_process_provider_mut(&provider_id)
+        .ok_or_else(|| format!("Provider '{}' not found", provider_id))?;
+
+    provider.timeout_secs = timeout_secs;
+    provider.max_retries = max_retries;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_post_process_fallback_providers_setting(
+    app: AppHandle,
+    provider_ids: Vec<String>,
+) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    for (index, provider_id) in provider_ids.iter().enumerate() {
+        validate_provider_exists(&settings, provider_id)?;
+        if provider_ids[..index].contains(provider_id) {
+            return Err(format!(
+                "Provider '{}' appears more than once in the fallback list",
+                provider_id
+            ));
+        }
+    }
+
+    settings.post_process_fallback_provider_ids = provider_ids;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_post_process_prices_setting(
+    app: AppHandle,
+    prices: Vec<TokenPrice>,
+) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    for (index, price) in prices.iter().enumerate() {
+        validate_provider_exists(&settings, &price.provider_id)?;
+        for value in [price.input_per_million, price.output_per_million] {
+            if !value.is_finite() || value < 0.0 {
+                return Err(format!(
+                    "Prices must be zero or more, got {} for provider '{}'",
+                    value, price.provider_id
+                ));
+            }
+        }
+        if prices[..index]
+            .iter()
+            .any(|other| other.provider_id == price.provider_id && other.model == price.model)
+        {
+            return Err(format!(
+                "Provider '{}' has more than one price for {}",
+                price.provider_id,
+                price
+                    .model
+                    .as_deref()
+                    .map_or("all models".to_string(), |model| format!(
+                        "model '{}'",
+                        model
+                    ))
+            ));
+        }
+    }
+
+    settings.post_process_prices = prices;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
 /// Generic helper to validate provider exists
 fn validate_provider_exists(
     settings: &settings::AppSettings,
@@ -426,6 +754,13 @@ pub fn change_post_process_api_key_setting(
 ) -> Result<(), String> {
     let mut settings = settings::get_settings(&app);
     validate_provider_exists(&settings, &provider_id)?;
+
+    // The UI only has the masked key, which it may send back unchanged
+    let current = settings.post_process_api_keys.get(&provider_id);
+    if current.is_some_and(|current| !current.is_empty() && mask_secret(current) == api_key) {
+        return Ok(());
+    }
+
     settings.post_process_api_keys.insert(provider_id, api_key);
     settings::write_settings(&app, settings);
     Ok(())
@@ -461,7 +796,12 @@ pub fn add_post_process_prompt(
     app: AppHandle,
     name: String,
     prompt: String,
+    system_prompt: Option<String>,
 ) -> Result<LLMPrompt, String> {
+    let system_prompt = system_prompt.unwrap_or_default();
+    validate_prompt_template(&prompt)?;
+    validate_prompt_template(&system_prompt)?;
+
     let mut settings = settings::get_settings(&app);
 
     // Generate unique ID using timestamp and random component
@@ -471,6 +811,7 @@ pub fn add_post_process_prompt(
         id: id.clone(),
         name,
         prompt,
+        system_prompt,
     };
 
     settings.post_process_prompts.push(new_prompt.clone());
@@ -486,7 +827,13 @@ pub fn update_post_process_prompt(
     id: String,
     name: String,
     prompt: String,
+    system_prompt: Option<String>,
 ) -> Result<(), String> {
+    validate_prompt_template(&prompt)?;
+    if let Some(system_prompt) = &system_prompt {
+        validate_prompt_template(system_prompt)?;
+    }
+
     let mut settings = settings::get_settings(&app);
 
     if let Some(existing_prompt) = settings
@@ -496,6 +843,10 @@ pub fn update_post_process_prompt(
     {
         existing_prompt.name = name;
         existing_prompt.prompt = prompt;
+        // Callers that don't edit the system prompt leave it as it was
+        if let Some(system_prompt) = system_prompt {
+            existing_prompt.system_prompt = system_prompt;
+        }
         settings::write_settings(&app, settings);
         Ok(())
     } else {
@@ -507,7 +858,17 @@ pub fn update_post_process_prompt(
 #[specta::specta]
 pub fn delete_post_process_prompt(app: AppHandle, id: String) -> Result<(), String> {
     let mut settings = settings::get_settings(&app);
+    remove_post_process_prompt(&mut settings, &id)?;
 
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+/// Removes a prompt from `settings`, moving whatever used it off of it
+fn remove_post_process_prompt(
+    settings: &mut settings::AppSettings,
+    id: &str,
+) -> Result<(), String> {
     // Don't allow deleting the last prompt
     if settings.post_process_prompts.len() <= 1 {
         return Err("Cannot delete the last prompt".to_string());
@@ -522,12 +883,17 @@ pub fn delete_post_process_prompt(app: AppHandle, id: String) -> Result<(), Stri
     }
 
     // If the deleted prompt was selected, select the first one or None
-    if settings.post_process_selected_prompt_id.as_ref() == Some(&id) {
+    if settings.post_process_selected_prompt_id.as_deref() == Some(id) {
         settings.post_process_selected_prompt_id =
             settings.post_process_prompts.first().map(|p| p.id.clone());
     }
 
-    settings::write_settings(&app, settings);
+    // Bindings that used it go back to plain dictation
+    for binding in settings.bindings.values_mut() {
+        if binding.prompt_id.as_deref() == Some(id) {
+            binding.prompt_id = None;
+        }
+    }
     Ok(())
 }
 
@@ -566,7 +932,7 @@ pub async fn fetch_post_process_models(
         .unwrap_or_default();
 
     // Skip fetching if no API key for providers that typically need one
-    if api_key.trim().is_empty() && provider.id != "custom" {
+    if api_key.trim().is_empty() && provider.id != "custom" && !provider.local {
         return Err(format!(
             "API key is required for {}. Please add an API key to list available models.",
             provider.label
@@ -576,6 +942,26 @@ pub async fn fetch_post_process_models(
     crate::llm_client::fetch_models(provider, api_key).await
 }
 
+#[tauri::command]
+#[specta::specta]
+pub async fn check_post_process_provider_health(
+    app: AppHandle,
+    provider_id: String,
+) -> Result<crate::llm_client::ProviderHealth, String> {
+    let settings = settings::get_settings(&app);
+    let provider = settings
+        .post_process_provider(&provider_id)
+        .ok_or_else(|| format!("Provider '{}' not found", provider_id))?;
+
+    let api_key = settings
+        .post_process_api_keys
+        .get(&provider_id)
+        .cloned()
+        .unwrap_or_default();
+
+    Ok(crate::llm_client::check_health(provider, api_key).await)
+}
+
 #[tauri::command]
 #[specta::specta]
 pub fn set_post_process_selected_prompt(app: AppHandle, id: String) -> Result<(), String> {
@@ -611,6 +997,16 @@ pub fn change_append_trailing_space_setting(app: AppHandle, enabled: bool) -> Re
     Ok(())
 }
 
+#[tauri::command]
+#[specta::specta]
+pub fn change_context_aware_insertion_setting(app: AppHandle, enabled: bool) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.context_aware_insertion = enabled;
+    settings::write_settings(&app, settings);
+
+    Ok(())
+}
+
 #[tauri::command]
 #[specta::specta]
 pub fn change_app_language_setting(app: AppHandle, language: String) -> Result<(), String> {
@@ -624,6 +1020,157 @@ pub fn change_app_language_setting(app: AppHandle, language: String) -> Result<(
     Ok(())
 }
 
+#[tauri::command]
+#[specta::specta]
+pub fn change_translation_enabled_setting(app: AppHandle, enabled: bool) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.translation_enabled = enabled;
+    settings::write_settings(&app, settings);
+
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_translation_target_language_setting(
+    app: AppHandle,
+    language: String,
+) -> Result<(), String> {
+    if language.trim().is_empty() {
+        return Err("Target language cannot be empty".to_string());
+    }
+
+    let mut settings = settings::get_settings(&app);
+    settings.translation_target_language = language.trim().to_string();
+    settings::write_settings(&app, settings);
+
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_translation_prompt_setting(app: AppHandle, prompt: String) -> Result<(), String> {
+    if !prompt.contains("${output}") {
+        return Err("Translation prompt must contain ${output}".to_string());
+    }
+
+    let mut settings = settings::get_settings(&app);
+    settings.translation_prompt = prompt;
+    settings::write_settings(&app, settings);
+
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_spoken_commands_setting(app: AppHandle, enabled: bool) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.spoken_commands_enabled = enabled;
+    settings::write_settings(&app, settings);
+
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_inverse_text_normalization_setting(
+    app: AppHandle,
+    enabled: bool,
+) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.inverse_text_normalization_enabled = enabled;
+    settings::write_settings(&app, settings);
+
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_disfluency_removal_setting(
+    app: AppHandle,
+    disfluency_removal: DisfluencyRemovalSettings,
+) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.disfluency_removal = disfluency_removal;
+    settings::write_settings(&app, settings);
+
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_pii_redaction_setting(
+    app: AppHandle,
+    pii_redaction: PiiRedactionSettings,
+) -> Result<(), String> {
+    let mut names: Vec<String> = Vec::new();
+    for name in pii_redaction.names {
+        let name = name.trim().to_string();
+        if !name.is_empty() && !names.contains(&name) {
+            names.push(name);
+        }
+    }
+
+    let mut settings = settings::get_settings(&app);
+    settings.pii_redaction = PiiRedactionSettings {
+        enabled: pii_redaction.enabled,
+        names,
+    };
+    settings::write_settings(&app, settings);
+
+    Ok(())
+}
+
+/// Set the phonetic algorithm custom word correction uses for a language, or
+/// pass `None` to go back to the language's default
+#[tauri::command]
+#[specta::specta]
+pub fn change_phonetic_algorithm_setting(
+    app: AppHandle,
+    language: String,
+    algorithm: Option<PhoneticAlgorithm>,
+) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    match algorithm {
+        Some(algorithm) => {
+            settings.phonetic_algorithms.insert(language, algorithm);
+        }
+        None => {
+            settings.phonetic_algorithms.remove(&language);
+        }
+    }
+    settings::write_settings(&app, settings);
+
+    Ok(())
+}
+
+/// Set which text-processing stages run after transcription, in order
+#[tauri::command]
+#[specta::specta]
+pub fn change_text_pipeline_setting(app: AppHandle, stages: Vec<TextStage>) -> Result<(), String> {
+    for (index, stage) in stages.iter().enumerate() {
+        if stages[..index].contains(stage) {
+            return Err(format!("Stage {:?} appears more than once", stage));
+        }
+    }
+
+    let mut settings = settings::get_settings(&app);
+    settings.text_pipeline = stages;
+    settings::write_settings(&app, settings);
+
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_text_casing_setting(app: AppHandle, casing: TextCasing) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.text_casing = casing;
+    settings::write_settings(&app, settings);
+
+    Ok(())
+}
+
 /// Determine whether a shortcut string contains at least one non-modifier key.
 /// We allow single non-modifier keys (e.g. "f5" or "space") but disallow
 /// modifier-only combos (e.g. "ctrl" or "ctrl+shift").
@@ -711,6 +1258,11 @@ pub fn unregister_cancel_shortcut(app: &AppHandle) {
 }
 
 pub fn register_shortcut(app: &AppHandle, binding: ShortcutBinding) -> Result<(), String> {
+    // Bindings without a shortcut, like voice edit until the user picks one
+    if binding.current_binding.is_empty() {
+        return Ok(());
+    }
+
     // Validate human-level rules first
     if let Err(e) = validate_shortcut_string(&binding.current_binding) {
         warn!(
@@ -749,7 +1301,7 @@ pub fn register_shortcut(app: &AppHandle, binding: ShortcutBinding) -> Result<()
                 let shortcut_string = scut.into_string();
                 let settings = get_settings(ah);
 
-                if let Some(action) = ACTION_MAP.get(&binding_id_for_closure) {
+                if let Some(action) = action_for(&binding_id_for_closure) {
                     if binding_id_for_closure == "cancel" {
                         let audio_manager = ah.state::<Arc<AudioRecordingManager>>();
                         if audio_manager.is_recording() && event.state == ShortcutState::Pressed {
@@ -810,6 +1362,10 @@ pub fn register_shortcut(app: &AppHandle, binding: ShortcutBinding) -> Result<()
 }
 
 pub fn unregister_shortcut(app: &AppHandle, binding: ShortcutBinding) -> Result<(), String> {
+    if binding.current_binding.is_empty() {
+        return Ok(());
+    }
+
     let shortcut = match binding.current_binding.parse::<Shortcut>() {
         Ok(s) => s,
         Err(e) => {
@@ -833,3 +1389,142 @@ pub fn unregister_shortcut(app: &AppHandle, binding: ShortcutBinding) -> Result<
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings_with_prompts(ids: &[&str]) -> settings::AppSettings {
+        let mut settings = settings::get_default_settings();
+        settings.post_process_prompts = ids
+            .iter()
+            .map(|id| LLMPrompt {
+                id: id.to_string(),
+                name: id.to_string(),
+                prompt: "${output}".to_string(),
+                system_prompt: String::new(),
+            })
+            .collect();
+        settings.post_process_selected_prompt_id = Some(ids[0].to_string());
+        settings
+    }
+
+    #[test]
+    fn test_insert_transcribe_binding() {
+        let mut settings = settings_with_prompts(&["email", "notes"]);
+
+        let first = insert_transcribe_binding(
+            &mut settings,
+            " Email ",
+            "ctrl+alt+e".to_string(),
+            Some("email".to_string()),
+        )
+        .unwrap();
+        let second =
+            insert_transcribe_binding(&mut settings, "Plain", "ctrl+alt+p".to_string(), None)
+                .unwrap();
+
+        assert!(settings::is_extra_transcribe_binding(&first.id));
+        assert_ne!(first.id, second.id);
+        assert_eq!(first.name, "Email");
+        assert_eq!(
+            settings.bindings[&first.id].prompt_id,
+            Some("email".to_string())
+        );
+        assert_eq!(settings.bindings[&second.id].prompt_id, None);
+    }
+
+    #[test]
+    fn test_insert_transcribe_binding_rejects_invalid_input() {
+        let mut settings = settings_with_prompts(&["email"]);
+        let before = settings.bindings.len();
+
+        assert!(insert_transcribe_binding(&mut settings, " ", "ctrl+e".to_string(), None).is_err());
+        assert!(
+            insert_transcribe_binding(&mut settings, "A", "ctrl+alt".to_string(), None).is_err()
+        );
+        assert!(insert_transcribe_binding(
+            &mut settings,
+            "A",
+            "ctrl+e".to_string(),
+            Some("missing".to_string())
+        )
+        .is_err());
+        assert_eq!(settings.bindings.len(), before);
+    }
+
+    #[test]
+    fn test_take_transcribe_binding() {
+        let mut settings = settings_with_prompts(&["email"]);
+        let added =
+            insert_transcribe_binding(&mut settings, "Plain", "ctrl+alt+p".to_string(), None)
+                .unwrap();
+
+        // Built-in bindings stay
+        assert!(take_transcribe_binding(&mut settings, "transcribe").is_err());
+        assert!(settings.bindings.contains_key("transcribe"));
+
+        assert_eq!(
+            take_transcribe_binding(&mut settings, &added.id)
+                .unwrap()
+                .id,
+            added.id
+        );
+        assert!(!settings.bindings.contains_key(&added.id));
+        assert!(take_transcribe_binding(&mut settings, &added.id).is_err());
+    }
+
+    #[test]
+    fn test_set_binding_prompt() {
+        let mut settings = settings_with_prompts(&["email", "notes"]);
+        let added =
+            insert_transcribe_binding(&mut settings, "Plain", "ctrl+alt+p".to_string(), None)
+                .unwrap();
+
+        set_binding_prompt(&mut settings, &added.id, Some("notes".to_string())).unwrap();
+        assert_eq!(
+            settings.bindings[&added.id].prompt_id,
+            Some("notes".to_string())
+        );
+        set_binding_prompt(&mut settings, &added.id, None).unwrap();
+        assert_eq!(settings.bindings[&added.id].prompt_id, None);
+
+        assert!(set_binding_prompt(&mut settings, &added.id, Some("missing".to_string())).is_err());
+        assert!(
+            set_binding_prompt(&mut settings, "transcribe", Some("notes".to_string())).is_err()
+        );
+    }
+
+    #[test]
+    fn test_remove_post_process_prompt_resets_bindings() {
+        let mut settings = settings_with_prompts(&["email", "notes"]);
+        let email = insert_transcribe_binding(
+            &mut settings,
+            "Email",
+            "ctrl+alt+e".to_string(),
+            Some("email".to_string()),
+        )
+        .unwrap();
+        let notes = insert_transcribe_binding(
+            &mut settings,
+            "Notes",
+            "ctrl+alt+n".to_string(),
+            Some("notes".to_string()),
+        )
+        .unwrap();
+
+        remove_post_process_prompt(&mut settings, "email").unwrap();
+
+        assert_eq!(settings.bindings[&email.id].prompt_id, None);
+        assert_eq!(
+            settings.bindings[&notes.id].prompt_id,
+            Some("notes".to_string())
+        );
+        assert_eq!(
+            settings.post_process_selected_prompt_id,
+            Some("notes".to_string())
+        );
+        // The last prompt can't go
+        assert!(remove_post_process_prompt(&mut settings, "notes").is_err());
+    }
+}
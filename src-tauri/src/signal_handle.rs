@@ -1,4 +1,4 @@
-use crate::actions::ACTION_MAP;
+use crate::actions::action_for;
 use crate::ManagedToggleState;
 use log::{debug, info, warn};
 use std::thread;
@@ -24,7 +24,7 @@ pub fn setup_signal_handler(app_handle: AppHandle, mut signals: Signals) {
                     let binding_id = "transcribe";
                     let shortcut_string = "SIGUSR2";
 
-                    if let Some(action) = ACTION_MAP.get(binding_id) {
+                    if let Some(action) = action_for(binding_id) {
                         // Determine action and update state while holding the lock,
                         // but RELEASE the lock before calling the action to avoid deadlocks.
                         // (Actions may need to acquire the lock themselves, e.g., cancel_current_operation)
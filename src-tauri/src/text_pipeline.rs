@@ -0,0 +1,812 @@
+use crate::actions::{
+    maybe_convert_chinese_variant, maybe_translate_transcription, post_process_transcription,
+};
+use crate::audio_toolkit::{
+    apply_custom_words, apply_inverse_normalization, apply_spoken_commands, capitalize_sentences,
+    remove_disfluencies, DisfluencyOptions,
+};
+use crate::llm_client::LlmAttempt;
+use crate::managers::history::HistoryManager;
+use crate::prompt_template::PromptInputs;
+use crate::replacements::apply_replacements;
+use crate::settings::{is_extra_transcribe_binding, AppSettings, LLMPrompt};
+use futures_util::future::BoxFuture;
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::future::ready;
+use std::sync::{Arc, Mutex};
+
+/// A step of the text-processing pipeline. The user chooses which stages run
+/// and in what order; each stage is still gated by its own settings.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum TextStage {
+    CustomWords,
+    SpokenCommands,
+    DisfluencyRemoval,
+    InverseNormalization,
+    Replacements,
+    ChineseConversion,
+    LlmPostProcess,
+    Translation,
+    Casing,
+    TrailingSpace,
+}
+
+impl TextStage {
+    /// Every stage, in the default order
+    pub const ALL: [TextStage; 10] = [
+        TextStage::CustomWords,
+        TextStage::SpokenCommands,
+        TextStage::DisfluencyRemoval,
+        TextStage::InverseNormalization,
+        TextStage::Replacements,
+        TextStage::ChineseConversion,
+        TextStage::LlmPostProcess,
+        TextStage::Translation,
+        TextStage::Casing,
+        TextStage::TrailingSpace,
+    ];
+}
+
+pub fn default_text_pipeline() -> Vec<TextStage> {
+    TextStage::ALL.to_vec()
+}
+
+/// Case applied to the text by the casing stage
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum TextCasing {
+    #[default]
+    Unchanged,
+    CapitalizeSentences,
+    Lowercase,
+    Uppercase,
+}
+
+pub type StreamCallback = Arc<dyn Fn(&str) + Send + Sync>;
+
+/// Record of the providers LLM stages asked, shared with the caller. With a
+/// history manager attached, every request is also kept for the usage totals.
+#[derive(Clone, Default)]
+pub struct AttemptLog {
+    attempts: Arc<Mutex<Vec<LlmAttempt>>>,
+    usage: Option<Arc<HistoryManager>>,
+}
+
+impl AttemptLog {
+    pub fn recording_usage(history: Arc<HistoryManager>) -> Self {
+        Self {
+            attempts: Arc::default(),
+            usage: Some(history),
+        }
+    }
+
+    pub fn push(&self, attempt: LlmAttempt) {
+        if let Some(history) = &self.usage {
+            if let Err(e) = history.record_llm_usage(&attempt) {
+                warn!("Failed to record LLM usage: {}", e);
+            }
+        }
+        self.attempts.lock().unwrap().push(attempt);
+    }
+
+    pub fn attempts(&self) -> Vec<LlmAttempt> {
+        self.attempts.lock().unwrap().clone()
+    }
+}
+
+/// What the stages need to know about the text being processed
+pub struct PipelineContext<'a> {
+    pub settings: &'a AppSettings,
+    /// Language of the text, used by the language-specific local stages
+    pub language: String,
+    /// Language reported by detection, if it ran
+    pub detected_language: Option<String>,
+    /// Prompt for the LLM stage; `None` skips it
+    pub post_process_prompt_id: Option<String>,
+    pub translate: bool,
+    /// Receives the LLM stage's output piece by piece as it streams in
+    pub llm_stream: Option<StreamCallback>,
+    /// Providers asked by the LLM stages, in order
+    pub llm_attempts: AttemptLog,
+    /// Values for the prompt variables that come from outside the text
+    pub prompt_inputs: PromptInputs,
+}
+
+impl<'a> PipelineContext<'a> {
+    /// Context for regular dictation, following the user's settings
+    pub fn for_dictation(settings: &'a AppSettings, detected_language: Option<&str>) -> Self {
+        let language = detected_language.unwrap_or(&settings.selected_language);
+        // Without a known spoken language, assume the UI language
+        let language = if language == "auto" {
+            settings.app_language.clone()
+        } else {
+            language.to_string()
+        };
+
+        Self {
+            settings,
+            language,
+            detected_language: detected_language.map(str::to_string),
+            post_process_prompt_id: settings
+                .post_process_selected_prompt_id
+                .clone()
+                .filter(|_| settings.post_process_enabled),
+            translate: true,
+            llm_stream: None,
+            llm_attempts: AttemptLog::default(),
+            prompt_inputs: PromptInputs::default(),
+        }
+    }
+
+    /// Context for dictation started from `binding_id`. Extra transcribe
+    /// bindings bring their own prompt, or none to skip the LLM
+    pub fn for_binding(
+        settings: &'a AppSettings,
+        binding_id: &str,
+        detected_language: Option<&str>,
+    ) -> Self {
+        let mut context = Self::for_dictation(settings, detected_language);
+        if is_extra_transcribe_binding(binding_id) {
+            context.post_process_prompt_id = settings
+                .bindings
+                .get(binding_id)
+                .and_then(|binding| binding.prompt_id.clone())
+                .filter(|_| settings.post_process_enabled);
+        }
+        context
+    }
+
+    /// Prompt the LLM stage uses
+    pub fn post_process_llm_prompt(&self) -> Option<&'a LLMPrompt> {
+        let prompt_id = self.post_process_prompt_id.as_ref()?;
+        self.settings
+            .post_process_prompts
+            .iter()
+            .find(|prompt| &prompt.id == prompt_id)
+    }
+
+    /// Text of the prompt the LLM stage uses
+    pub fn post_process_prompt(&self) -> Option<String> {
+        self.post_process_llm_prompt()
+            .map(|prompt| prompt.prompt.clone())
+    }
+}
+
+pub trait TextProcessor: Send + Sync {
+    fn stage(&self) -> TextStage;
+
+    /// Whether the user's settings turn the stage on for this context
+    fn is_enabled(&self, context: &PipelineContext<'_>) -> bool;
+
+    /// Returns the processed text, or `None` if the stage has nothing to do
+    /// for this text. Only called when the stage is enabled.
+    fn process<'a>(
+        &'a self,
+        text: &'a str,
+        context: &'a PipelineContext<'a>,
+    ) -> BoxFuture<'a, Option<String>>;
+}
+
+struct CustomWordsProcessor;
+
+impl TextProcessor for CustomWordsProcessor {
+    fn stage(&self) -> TextStage {
+        TextStage::CustomWords
+    }
+
+    fn is_enabled(&self, context: &PipelineContext<'_>) -> bool {
+        !context.settings.custom_words.is_empty()
+    }
+
+    fn process<'a>(
+        &'a self,
+        text: &'a str,
+        context: &'a PipelineContext<'a>,
+    ) -> BoxFuture<'a, Option<String>> {
+        let settings = context.settings;
+        Box::pin(ready(Some(apply_custom_words(
+            text,
+            &settings.custom_words,
+            settings.word_correction_threshold,
+            settings.phonetic_algorithm_for(&context.language),
+        ))))
+    }
+}
+
+struct SpokenCommandsProcessor;
+
+impl TextProcessor for SpokenCommandsProcessor {
+    fn stage(&self) -> TextStage {
+        TextStage::SpokenCommands
+    }
+
+    fn is_enabled(&self, context: &PipelineContext<'_>) -> bool {
+        context.settings.spoken_commands_enabled
+    }
+
+    fn process<'a>(
+        &'a self,
+        text: &'a str,
+        context: &'a PipelineContext<'a>,
+    ) -> BoxFuture<'a, Option<String>> {
+        Box::pin(ready(Some(apply_spoken_commands(text, &context.language))))
+    }
+}
+
+struct DisfluencyRemovalProcessor;
+
+impl TextProcessor for DisfluencyRemovalProcessor {
+    fn stage(&self) -> TextStage {
+        TextStage::DisfluencyRemoval
+    }
+
+    fn is_enabled(&self, context: &PipelineContext<'_>) -> bool {
+        context.settings.disfluency_removal.enabled
+    }
+
+    fn process<'a>(
+        &'a self,
+        text: &'a str,
+        context: &'a PipelineContext<'a>,
+    ) -> BoxFuture<'a, Option<String>> {
+        let disfluency = &context.settings.disfluency_removal;
+        let options = DisfluencyOptions {
+            remove_fillers: disfluency.remove_fillers,
+            remove_repetitions: disfluency.remove_repetitions,
+            remove_self_corrections: disfluency.remove_self_corrections,
+            extra_fillers: &disfluency.extra_fillers,
+        };
+        Box::pin(ready(Some(remove_disfluencies(
+            text,
+            &context.language,
+            &options,
+        ))))
+    }
+}
+
+struct InverseNormalizationProcessor;
+
+impl TextProcessor for InverseNormalizationProcessor {
+    fn stage(&self) -> TextStage {
+        TextStage::InverseNormalization
+    }
+
+    fn is_enabled(&self, context: &PipelineContext<'_>) -> bool {
+        context.settings.inverse_text_normalization_enabled
+    }
+
+    fn process<'a>(
+        &'a self,
+        text: &'a str,
+        context: &'a PipelineContext<'a>,
+    ) -> BoxFuture<'a, Option<String>> {
+        Box::pin(ready(Some(apply_inverse_normalization(
+            text,
+            &context.language,
+        ))))
+    }
+}
+
+struct ReplacementsProcessor;
+
+impl TextProcessor for ReplacementsProcessor {
+    fn stage(&self) -> TextStage {
+        TextStage::Replacements
+    }
+
+    fn is_enabled(&self, context: &PipelineContext<'_>) -> bool {
+        !context.settings.replacement_rules.is_empty()
+    }
+
+    fn process<'a>(
+        &'a self,
+        text: &'a str,
+        context: &'a PipelineContext<'a>,
+    ) -> BoxFuture<'a, Option<String>> {
+        Box::pin(ready(Some(apply_replacements(
+            text,
+            &context.settings.replacement_rules,
+        ))))
+    }
+}
+
+struct ChineseConversionProcessor;
+
+impl TextProcessor for ChineseConversionProcessor {
+    fn stage(&self) -> TextStage {
+        TextStage::ChineseConversion
+    }
+
+    fn is_enabled(&self, context: &PipelineContext<'_>) -> bool {
+        matches!(
+            context.settings.selected_language.as_str(),
+            "zh-Hans" | "zh-Hant"
+        )
+    }
+
+    fn process<'a>(
+        &'a self,
+        text: &'a str,
+        context: &'a PipelineContext<'a>,
+    ) -> BoxFuture<'a, Option<String>> {
+        Box::pin(maybe_convert_chinese_variant(context.settings, text))
+    }
+}
+
+struct LlmPostProcessProcessor;
+
+impl TextProcessor for LlmPostProcessProcessor {
+    fn stage(&self) -> TextStage {
+        TextStage::LlmPostProcess
+    }
+
+    fn is_enabled(&self, context: &PipelineContext<'_>) -> bool {
+        context.post_process_prompt_id.is_some()
+    }
+
+    fn process<'a>(
+        &'a self,
+        text: &'a str,
+        context: &'a PipelineContext<'a>,
+    ) -> BoxFuture<'a, Option<String>> {
+        Box::pin(post_process_transcription(context, text))
+    }
+}
+
+struct TranslationProcessor;
+
+impl TextProcessor for TranslationProcessor {
+    fn stage(&self) -> TextStage {
+        TextStage::Translation
+    }
+
+    fn is_enabled(&self, context: &PipelineContext<'_>) -> bool {
+        context.translate && context.settings.translation_enabled
+    }
+
+    fn process<'a>(
+        &'a self,
+        text: &'a str,
+        context: &'a PipelineContext<'a>,
+    ) -> BoxFuture<'a, Option<String>> {
+        Box::pin(maybe_translate_transcription(
+            context.settings,
+            text,
+            context.detected_language.as_deref(),
+            &context.llm_attempts,
+        ))
+    }
+}
+
+struct CasingProcessor;
+
+impl TextProcessor for CasingProcessor {
+    fn stage(&self) -> TextStage {
+        TextStage::Casing
+    }
+
+    fn is_enabled(&self, context: &PipelineContext<'_>) -> bool {
+        context.settings.text_casing != TextCasing::Unchanged
+    }
+
+    fn process<'a>(
+        &'a self,
+        text: &'a str,
+        context: &'a PipelineContext<'a>,
+    ) -> BoxFuture<'a, Option<String>> {
+        let result = match context.settings.text_casing {
+            TextCasing::Unchanged => None,
+            TextCasing::CapitalizeSentences => Some(capitalize_sentences(text)),
+            TextCasing::Lowercase => Some(text.to_lowercase()),
+            TextCasing::Uppercase => Some(text.to_uppercase()),
+        };
+        Box::pin(ready(result))
+    }
+}
+
+struct TrailingSpaceProcessor;
+
+impl TextProcessor for TrailingSpaceProcessor {
+    fn stage(&self) -> TextStage {
+        TextStage::TrailingSpace
+    }
+
+    fn is_enabled(&self, context: &PipelineContext<'_>) -> bool {
+        context.settings.append_trailing_space
+    }
+
+    fn process<'a>(
+        &'a self,
+        text: &'a str,
+        _context: &'a PipelineContext<'a>,
+    ) -> BoxFuture<'a, Option<String>> {
+        Box::pin(ready(Some(format!("{} ", text))))
+    }
+}
+
+fn processor_for(stage: TextStage) -> Box<dyn TextProcessor> {
+    match stage {
+        TextStage::CustomWords => Box::new(CustomWordsProcessor),
+        TextStage::SpokenCommands => Box::new(SpokenCommandsProcessor),
+        TextStage::DisfluencyRemoval => Box::new(DisfluencyRemovalProcessor),
+        TextStage::InverseNormalization => Box::new(InverseNormalizationProcessor),
+        TextStage::Replacements => Box::new(ReplacementsProcessor),
+        TextStage::ChineseConversion => Box::new(ChineseConversionProcessor),
+        TextStage::LlmPostProcess => Box::new(LlmPostProcessProcessor),
+        TextStage::Translation => Box::new(TranslationProcessor),
+        TextStage::Casing => Box::new(CasingProcessor),
+        TextStage::TrailingSpace => Box::new(TrailingSpaceProcessor),
+    }
+}
+
+/// Output of a single stage
+#[derive(Serialize, Debug, Clone, Type)]
+pub struct StageOutput {
+    pub stage: TextStage,
+    /// Whether the stage ran; stages that are disabled pass the text through
+    pub applied: bool,
+    pub text: String,
+}
+
+/// The input of a pipeline run and the text after every stage
+#[derive(Serialize, Debug, Clone, Type)]
+pub struct PipelineRun {
+    pub input: String,
+    pub stages: Vec<StageOutput>,
+}
+
+impl PipelineRun {
+    pub fn output(&self) -> &str {
+        self.stages
+            .last()
+            .map_or(self.input.as_str(), |stage| stage.text.as_str())
+    }
+
+    /// Text entering `stage`, or the final output if the stage isn't part of
+    /// the pipeline
+    pub fn text_before(&self, stage: TextStage) -> &str {
+        match self.stages.iter().position(|output| output.stage == stage) {
+            Some(0) => &self.input,
+            Some(index) => &self.stages[index - 1].text,
+            None => self.output(),
+        }
+    }
+
+    pub fn applied(&self, stage: TextStage) -> bool {
+        self.stages
+            .iter()
+            .any(|output| output.stage == stage && output.applied)
+    }
+}
+
+pub struct TextPipeline {
+    processors: Vec<Box<dyn TextProcessor>>,
+}
+
+impl TextPipeline {
+    /// Builds a pipeline running `stages` in order; repeated stages only run
+    /// the first time
+    pub fn from_stages(stages: &[TextStage]) -> Self {
+        let mut processors: Vec<Box<dyn TextProcessor>> = Vec::new();
+        for &stage in stages {
+            if processors
+                .iter()
+                .all(|processor| processor.stage() != stage)
+            {
+                processors.push(processor_for(stage));
+            }
+        }
+        Self { processors }
+    }
+
+    pub fn from_settings(settings: &AppSettings) -> Self {
+        Self::from_stages(&settings.text_pipeline)
+    }
+
+    /// Whether the LLM stage's output reaches the end of the pipeline as is:
+    /// the stage is enabled and every enabled stage after it only appends
+    pub fn llm_output_is_final(&self, context: &PipelineContext<'_>) -> bool {
+        let index = match self
+            .processors
+            .iter()
+            .position(|processor| processor.stage() == TextStage::LlmPostProcess)
+        {
+            Some(index) => index,
+            None => return false,
+        };
+
+        self.processors[index].is_enabled(context)
+            && self.processors[index + 1..].iter().all(|processor| {
+                processor.stage() == TextStage::TrailingSpace || !processor.is_enabled(context)
+            })
+    }
+
+    pub async fn run(&self, text: &str, context: &PipelineContext<'_>) -> PipelineRun {
+        let mut stages = Vec::with_capacity(self.processors.len());
+        let mut current = text.to_string();
+
+        for processor in &self.processors {
+            let processed = if processor.is_enabled(context) {
+                processor.process(&current, context).await
+            } else {
+                None
+            };
+            let applied = processed.is_some();
+            if let Some(processed) = processed {
+                current = processed;
+            }
+            debug!(
+                "Text stage {:?} {}",
+                processor.stage(),
+                if applied { "applied" } else { "skipped" }
+            );
+            stages.push(StageOutput {
+                stage: processor.stage(),
+                applied,
+                text: current.clone(),
+            });
+        }
+
+        PipelineRun {
+            input: text.to_string(),
+            stages,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm_client::test_server::{closed_port_url, json_response, serve_once};
+    use crate::llm_client::AttemptOutcome;
+    use crate::replacements::ReplacementRule;
+    use crate::settings::{get_default_settings, ShortcutBinding};
+
+    fn run(stages: &[TextStage], settings: &AppSettings, text: &str) -> PipelineRun {
+        let context = PipelineContext::for_dictation(settings, Some("en"));
+        tauri::async_runtime::block_on(TextPipeline::from_stages(stages).run(text, &context))
+    }
+
+    #[test]
+    fn test_default_settings_leave_text_unchanged() {
+        let settings = get_default_settings();
+        let result = run(&settings.text_pipeline, &settings, "hello world");
+
+        assert_eq!(result.output(), "hello world");
+        assert_eq!(result.stages.len(), TextStage::ALL.len());
+        assert!(result.stages.iter().all(|stage| !stage.applied));
+    }
+
+    #[test]
+    fn test_stage_order_matters() {
+        let mut settings = get_default_settings();
+        settings.text_casing = TextCasing::Uppercase;
+        settings.append_trailing_space = true;
+        settings.replacement_rules = vec![ReplacementRule {
+            pattern: "teh".to_string(),
+            replacement: "the".to_string(),
+            is_regex: false,
+            case_sensitive: true,
+            whole_word: true,
+            preserve_case: false,
+            enabled: true,
+        }];
+
+        let replace_first = run(
+            &[TextStage::Replacements, TextStage::Casing],
+            &settings,
+            "teh end",
+        );
+        assert_eq!(replace_first.output(), "THE END");
+
+        let case_first = run(
+            &[
+                TextStage::Casing,
+                TextStage::Replacements,
+                TextStage::TrailingSpace,
+            ],
+            &settings,
+            "teh end",
+        );
+        assert_eq!(case_first.output(), "TEH END ");
+        assert_eq!(case_first.text_before(TextStage::TrailingSpace), "TEH END");
+        assert!(case_first.applied(TextStage::Casing));
+    }
+
+    #[test]
+    fn test_local_stages_keep_spoken_line_breaks() {
+        let mut settings = get_default_settings();
+        settings.spoken_commands_enabled = true;
+        settings.disfluency_removal.enabled = true;
+        settings.inverse_text_normalization_enabled = true;
+
+        let result = run(
+            &settings.text_pipeline,
+            &settings,
+            "Total new paragraph twenty five dollars",
+        );
+        assert_eq!(result.output(), "Total\n\n$25");
+    }
+
+    #[test]
+    fn test_repeated_stages_run_once() {
+        let mut settings = get_default_settings();
+        settings.append_trailing_space = true;
+        let result = run(
+            &[TextStage::TrailingSpace, TextStage::TrailingSpace],
+            &settings,
+            "done",
+        );
+
+        assert_eq!(result.stages.len(), 1);
+        assert_eq!(result.output(), "done ");
+    }
+
+    #[test]
+    fn test_llm_output_is_final() {
+        let mut settings = get_default_settings();
+        settings.post_process_enabled = true;
+        settings.post_process_selected_prompt_id =
+            Some("default_improve_transcriptions".to_string());
+        settings.append_trailing_space = true;
+        let context = PipelineContext::for_dictation(&settings, Some("en"));
+        let pipeline = TextPipeline::from_settings(&settings);
+        assert!(pipeline.llm_output_is_final(&context));
+
+        settings.text_casing = TextCasing::Uppercase;
+        let context = PipelineContext::for_dictation(&settings, Some("en"));
+        assert!(!pipeline.llm_output_is_final(&context));
+
+        let casing_first =
+            TextPipeline::from_stages(&[TextStage::Casing, TextStage::LlmPostProcess]);
+        assert!(casing_first.llm_output_is_final(&context));
+
+        settings.post_process_enabled = false;
+        let context = PipelineContext::for_dictation(&settings, Some("en"));
+        assert!(!casing_first.llm_output_is_final(&context));
+    }
+
+    /// Settings with LLM post-processing on, asking `chain` in order and
+    /// pointing each provider at the given base URL
+    fn settings_with_provider_chain(chain: &[(&str, String)]) -> AppSettings {
+        let mut settings = get_default_settings();
+        settings.post_process_enabled = true;
+        settings.post_process_selected_prompt_id =
+            Some("default_improve_transcriptions".to_string());
+        settings.post_process_provider_id = chain[0].0.to_string();
+        settings.post_process_fallback_provider_ids =
+            chain[1..].iter().map(|(id, _)| id.to_string()).collect();
+        for (id, base_url) in chain {
+            let provider = settings.post_process_provider_mut(id).unwrap();
+            provider.base_url = base_url.clone();
+            provider.max_retries = 0;
+            settings
+                .post_process_models
+                .insert(id.to_string(), "test-model".to_string());
+        }
+        settings
+    }
+
+    fn run_llm_stage(settings: &AppSettings, text: &str) -> (PipelineRun, Vec<LlmAttempt>) {
+        let context = PipelineContext::for_dictation(settings, Some("en"));
+        let result = tauri::async_runtime::block_on(
+            TextPipeline::from_stages(&[TextStage::LlmPostProcess]).run(text, &context),
+        );
+        let attempts = context.llm_attempts.attempts();
+        (result, attempts)
+    }
+
+    #[test]
+    fn test_llm_stage_falls_back_through_providers() {
+        let (openrouter, _) = serve_once(vec![json_response(
+            "502 Bad Gateway",
+            r#"{"error":"upstream"}"#,
+        )]);
+        let (ollama, _) = serve_once(vec![json_response(
+            "200 OK",
+            r#"{"message":{"role":"assistant","content":"Cleaned up."},"done":true}"#,
+        )]);
+        let settings = settings_with_provider_chain(&[
+            ("openrouter", openrouter),
+            ("groq", closed_port_url()),
+            ("ollama", ollama),
+        ]);
+
+        let (result, attempts) = run_llm_stage(&settings, "um cleaned up");
+
+        assert_eq!(result.output(), "Cleaned up.");
+        let providers: Vec<&str> = attempts.iter().map(|a| a.provider_id.as_str()).collect();
+        assert_eq!(providers, vec!["openrouter", "groq", "ollama"]);
+        assert!(
+            matches!(&attempts[0].outcome, AttemptOutcome::Failed { error } if error.contains("502"))
+        );
+        assert!(matches!(attempts[1].outcome, AttemptOutcome::Failed { .. }));
+        assert_eq!(attempts[2].outcome, AttemptOutcome::Succeeded);
+    }
+
+    #[test]
+    fn test_llm_stage_keeps_raw_text_when_every_provider_fails() {
+        let (openrouter, _) = serve_once(vec![json_response("429 Too Many Requests", "{}")]);
+        let settings = settings_with_provider_chain(&[
+            ("openrouter", openrouter),
+            ("ollama", closed_port_url()),
+        ]);
+
+        let (result, attempts) = run_llm_stage(&settings, "um raw text");
+
+        assert_eq!(result.output(), "um raw text");
+        assert!(!result.applied(TextStage::LlmPostProcess));
+        assert_eq!(attempts.len(), 2);
+        assert!(
+            matches!(&attempts[1].outcome, AttemptOutcome::Failed { error } if error.contains("not running"))
+        );
+    }
+
+    #[test]
+    fn test_translation_skipped_when_already_in_target_language() {
+        let mut settings = settings_with_provider_chain(&[("groq", closed_port_url())]);
+        settings.post_process_enabled = false;
+        settings.translation_enabled = true;
+        // The default target is a language name, detection reports a code
+        assert_eq!(settings.translation_target_language, "English");
+
+        let attempts = |language: Option<&str>| {
+            let context = PipelineContext::for_dictation(&settings, language);
+            tauri::async_runtime::block_on(
+                TextPipeline::from_stages(&[TextStage::Translation]).run("hello", &context),
+            );
+            context.llm_attempts.attempts().len()
+        };
+        assert_eq!(attempts(Some("en")), 0);
+        assert_eq!(attempts(Some("en-GB")), 0);
+        assert_eq!(attempts(Some("de")), 1);
+        // Nothing detected with "auto" selected leaves the source unknown
+        assert_eq!(settings.selected_language, "auto");
+        assert_eq!(attempts(None), 0);
+    }
+
+    #[test]
+    fn test_binding_chooses_post_process_prompt() {
+        let mut settings = get_default_settings();
+        settings.post_process_enabled = true;
+        settings.post_process_selected_prompt_id = Some("selected".to_string());
+        for (id, prompt_id) in [
+            ("transcribe_email", Some("email".to_string())),
+            ("transcribe_plain", None),
+        ] {
+            settings.bindings.insert(
+                id.to_string(),
+                ShortcutBinding {
+                    id: id.to_string(),
+                    name: id.to_string(),
+                    description: String::new(),
+                    default_binding: String::new(),
+                    current_binding: String::new(),
+                    prompt_id,
+                },
+            );
+        }
+        let prompt_id = |settings: &AppSettings, binding_id: &str| {
+            PipelineContext::for_binding(settings, binding_id, Some("en")).post_process_prompt_id
+        };
+
+        // The main binding follows the selected prompt
+        assert_eq!(
+            prompt_id(&settings, "transcribe"),
+            Some("selected".to_string())
+        );
+        // Extra bindings use their own prompt, or none to skip the LLM
+        assert_eq!(
+            prompt_id(&settings, "transcribe_email"),
+            Some("email".to_string())
+        );
+        assert_eq!(prompt_id(&settings, "transcribe_plain"), None);
+
+        settings.post_process_enabled = false;
+        assert_eq!(prompt_id(&settings, "transcribe"), None);
+        assert_eq!(prompt_id(&settings, "transcribe_email"), None);
+    }
+}
@@ -0,0 +1,139 @@
+use anyhow::Result;
+use std::path::Path;
+use whisper_rs::{get_lang_str, WhisperContext, WhisperContextParameters};
+
+use crate::audio_toolkit::constants::WHISPER_SAMPLE_RATE;
+
+/// Only the start of a recording is used to identify the language
+const DETECTION_WINDOW_SECONDS: usize = 5;
+
+/// Share of the candidates' combined probability the winner needs; below this
+/// the detection is treated as a guess and the first candidate is used instead
+const MIN_CANDIDATE_CONFIDENCE: f32 = 0.6;
+
+/// Identifies the spoken language with the language head of a Whisper model,
+/// without decoding any text.
+pub struct LanguageDetector {
+    context: WhisperContext,
+}
+
+impl LanguageDetector {
+    pub fn load(model_path: &Path) -> Result<Self> {
+        let path = model_path
+            .to_str()
+            .ok_or_else(|| anyhow::anyhow!("Invalid model path: {:?}", model_path))?;
+        let context = WhisperContext::new_with_params(path, WhisperContextParameters::default())
+            .map_err(|e| anyhow::anyhow!("Failed to load language detection model: {}", e))?;
+        Ok(Self { context })
+    }
+
+    /// Returns language codes with their probabilities, most likely first.
+    pub fn detect(&self, audio: &[f32]) -> Result<Vec<(String, f32)>> {
+        let window = (DETECTION_WINDOW_SECONDS * WHISPER_SAMPLE_RATE as usize).min(audio.len());
+        let threads = std::thread::available_parallelism()
+            .map(|n| n.get().min(4))
+            .unwrap_or(1);
+
+        let mut state = self
+            .context
+            .create_state()
+            .map_err(|e| anyhow::anyhow!("Failed to create whisper state: {}", e))?;
+        state
+            .pcm_to_mel(&audio[..window], threads)
+            .map_err(|e| anyhow::anyhow!("Failed to compute mel spectrogram: {}", e))?;
+        let (_, probabilities) = state
+            .lang_detect(0, threads)
+            .map_err(|e| anyhow::anyhow!("Language detection failed: {}", e))?;
+
+        let mut languages: Vec<(String, f32)> = probabilities
+            .into_iter()
+            .enumerate()
+            .filter_map(|(id, p)| get_lang_str(id as i32).map(|code| (code.to_string(), p)))
+            .collect();
+        languages.sort_by(|a, b| b.1.total_cmp(&a.1));
+        Ok(languages)
+    }
+}
+
+/// Picks the most likely language among the user's candidates. Detected codes are
+/// Whisper's ISO 639-1 codes, so candidates match on their primary subtag and the
+/// candidate's own code (e.g. "zh-Hans") is returned. Falls back to the first
+/// candidate when the winner is not clearly ahead of the others.
+pub fn choose_language(detected: &[(String, f32)], candidates: &[String]) -> Option<String> {
+    let primary = |code: &str| code.split('-').next().unwrap_or(code).to_lowercase();
+
+    let scored: Vec<(&String, f32)> = candidates
+        .iter()
+        .map(|candidate| {
+            let probability = detected
+                .iter()
+                .find(|(code, _)| primary(code) == primary(candidate))
+                .map_or(0.0, |(_, p)| *p);
+            (candidate, probability)
+        })
+        .collect();
+
+    let total: f32 = scored.iter().map(|(_, p)| p).sum();
+    let (best, best_probability) = scored.iter().copied().max_by(|a, b| a.1.total_cmp(&b.1))?;
+
+    if total > 0.0 && best_probability / total >= MIN_CANDIDATE_CONFIDENCE {
+        Some(best.clone())
+    } else {
+        candidates.first().cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detected(probabilities: &[(&str, f32)]) -> Vec<(String, f32)> {
+        probabilities
+            .iter()
+            .map(|(code, p)| (code.to_string(), *p))
+            .collect()
+    }
+
+    fn candidates(codes: &[&str]) -> Vec<String> {
+        codes.iter().map(|code| code.to_string()).collect()
+    }
+
+    #[test]
+    fn test_choose_language_ignores_languages_outside_candidates() {
+        let probabilities = detected(&[("cy", 0.55), ("en", 0.30), ("pt", 0.10), ("de", 0.05)]);
+        assert_eq!(
+            choose_language(&probabilities, &candidates(&["de", "en"])),
+            Some("en".to_string())
+        );
+    }
+
+    #[test]
+    fn test_choose_language_falls_back_to_first_candidate_when_unsure() {
+        let close_call = detected(&[("en", 0.40), ("de", 0.35), ("fr", 0.25)]);
+        assert_eq!(
+            choose_language(&close_call, &candidates(&["de", "en"])),
+            Some("de".to_string())
+        );
+
+        let no_candidate_detected = detected(&[("cy", 0.9), ("pt", 0.1)]);
+        assert_eq!(
+            choose_language(&no_candidate_detected, &candidates(&["fr", "en"])),
+            Some("fr".to_string())
+        );
+    }
+
+    #[test]
+    fn test_choose_language_returns_candidate_code() {
+        let probabilities = detected(&[("zh", 0.8), ("en", 0.2)]);
+        assert_eq!(
+            choose_language(&probabilities, &candidates(&["en", "zh-Hant"])),
+            Some("zh-Hant".to_string())
+        );
+    }
+
+    #[test]
+    fn test_choose_language_without_candidates() {
+        let probabilities = detected(&[("en", 1.0)]);
+        assert_eq!(choose_language(&probabilities, &[]), None);
+    }
+}
@@ -0,0 +1,103 @@
+use anyhow::Result;
+use std::path::PathBuf;
+use transcribe_rs::{
+    engines::{
+        parakeet::{
+            ParakeetEngine, ParakeetInferenceParams, ParakeetModelParams, TimestampGranularity,
+        },
+        whisper::{WhisperEngine, WhisperInferenceParams},
+    },
+    TranscriptionEngine,
+};
+
+use super::{
+    whisper_language, BackendContext, BackendFactory, TranscribeOptions, TranscriptionBackend,
+};
+
+fn model_path(context: &BackendContext) -> Result<PathBuf> {
+    context
+        .model_path
+        .clone()
+        .ok_or_else(|| anyhow::anyhow!("No model files for {}", context.model_info.id))
+}
+
+struct WhisperBackend {
+    engine: WhisperEngine,
+}
+
+impl TranscriptionBackend for WhisperBackend {
+    fn transcribe(&mut self, audio: Vec<f32>, options: &TranscribeOptions) -> Result<String> {
+        let params = WhisperInferenceParams {
+            language: whisper_language(options.language),
+            translate: options.translate,
+            ..Default::default()
+        };
+
+        let result = self
+            .engine
+            .transcribe_samples(audio, Some(params))
+            .map_err(|e| anyhow::anyhow!("Whisper transcription failed: {}", e))?;
+        Ok(result.text)
+    }
+
+    fn unload(&mut self) {
+        self.engine.unload_model();
+    }
+}
+
+pub struct WhisperBackendFactory;
+
+impl BackendFactory for WhisperBackendFactory {
+    fn create(&self, context: &BackendContext) -> Result<Box<dyn TranscriptionBackend>> {
+        let mut engine = WhisperEngine::new();
+        engine.load_model(&model_path(context)?).map_err(|e| {
+            anyhow::anyhow!(
+                "Failed to load whisper model {}: {}",
+                context.model_info.id,
+                e
+            )
+        })?;
+        Ok(Box::new(WhisperBackend { engine }))
+    }
+}
+
+struct ParakeetBackend {
+    engine: ParakeetEngine,
+}
+
+impl TranscriptionBackend for ParakeetBackend {
+    fn transcribe(&mut self, audio: Vec<f32>, _options: &TranscribeOptions) -> Result<String> {
+        let params = ParakeetInferenceParams {
+            timestamp_granularity: TimestampGranularity::Segment,
+            ..Default::default()
+        };
+
+        let result = self
+            .engine
+            .transcribe_samples(audio, Some(params))
+            .map_err(|e| anyhow::anyhow!("Parakeet transcription failed: {}", e))?;
+        Ok(result.text)
+    }
+
+    fn unload(&mut self) {
+        self.engine.unload_model();
+    }
+}
+
+pub struct ParakeetBackendFactory;
+
+impl BackendFactory for ParakeetBackendFactory {
+    fn create(&self, context: &BackendContext) -> Result<Box<dyn TranscriptionBackend>> {
+        let mut engine = ParakeetEngine::new();
+        engine
+            .load_model_with_params(&model_path(context)?, ParakeetModelParams::int8())
+            .map_err(|e| {
+                anyhow::anyhow!(
+                    "Failed to load parakeet model {}: {}",
+                    context.model_info.id,
+                    e
+                )
+            })?;
+        Ok(Box::new(ParakeetBackend { engine }))
+    }
+}
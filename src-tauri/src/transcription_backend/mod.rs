@@ -0,0 +1,92 @@
+use anyhow::Result;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use crate::managers::model::{EngineType, ModelInfo};
+use crate::settings::AppSettings;
+
+/// Per-call options passed to a backend alongside the audio
+pub struct TranscribeOptions<'a> {
+    /// Language code from settings, or "auto" to let the engine detect it
+    pub language: &'a str,
+    /// Translate the speech to English instead of transcribing it
+    pub translate: bool,
+}
+
+pub trait TranscriptionBackend: Send {
+    /// Transcribe mono 16 kHz samples to text.
+    fn transcribe(&mut self, audio: Vec<f32>, options: &TranscribeOptions) -> Result<String>;
+
+    /// Release any resources held by the backend before it is dropped.
+    fn unload(&mut self) {}
+}
+
+/// Everything a factory may need to create a backend for a model
+pub struct BackendContext<'a> {
+    pub model_info: &'a ModelInfo,
+    /// Location of the model files; `None` for remote engines
+    pub model_path: Option<PathBuf>,
+    pub settings: &'a AppSettings,
+}
+
+pub trait BackendFactory: Send + Sync {
+    fn create(&self, context: &BackendContext) -> Result<Box<dyn TranscriptionBackend>>;
+}
+
+/// Maps each engine type to the factory that creates its backend
+pub struct BackendRegistry {
+    factories: HashMap<EngineType, Box<dyn BackendFactory>>,
+}
+
+impl BackendRegistry {
+    /// Registry with the built-in local and remote backends
+    pub fn new() -> Self {
+        let mut registry = Self {
+            factories: HashMap::new(),
+        };
+        registry.register(EngineType::Whisper, Box::new(WhisperBackendFactory));
+        registry.register(EngineType::Parakeet, Box::new(ParakeetBackendFactory));
+        registry.register(
+            EngineType::OpenAiCompatible,
+            Box::new(OpenAiCompatibleBackendFactory),
+        );
+        registry
+    }
+
+    /// Register or replace the factory for an engine type
+    pub fn register(&mut self, engine_type: EngineType, factory: Box<dyn BackendFactory>) {
+        self.factories.insert(engine_type, factory);
+    }
+
+    pub fn create(&self, context: &BackendContext) -> Result<Box<dyn TranscriptionBackend>> {
+        let engine_type = context.model_info.engine_type;
+        let factory = self.factories.get(&engine_type).ok_or_else(|| {
+            anyhow::anyhow!("No transcription backend registered for {:?}", engine_type)
+        })?;
+        factory.create(context)
+    }
+}
+
+impl Default for BackendRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Whisper uses ISO 639-1 codes, so map "auto" to `None` and the Chinese
+/// script variants to "zh"
+fn whisper_language(language: &str) -> Option<String> {
+    match language {
+        "auto" => None,
+        "zh-Hans" | "zh-Hant" => Some("zh".to_string()),
+        other => Some(other.to_string()),
+    }
+}
+
+mod language_id;
+mod local;
+mod openai;
+
+pub use language_id::{choose_language, LanguageDetector};
+pub use local::{ParakeetBackendFactory, WhisperBackendFactory};
+pub use openai::{OpenAiCompatibleBackend, OpenAiCompatibleBackendFactory};
@@ -0,0 +1,269 @@
+use anyhow::Result;
+use hound::{WavSpec, WavWriter};
+use log::debug;
+use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION};
+use reqwest::multipart::{Form, Part};
+use serde::Deserialize;
+use std::io::Cursor;
+use std::thread;
+use std::time::Duration;
+
+use super::{
+    whisper_language, BackendContext, BackendFactory, TranscribeOptions, TranscriptionBackend,
+};
+use crate::audio_toolkit::constants::WHISPER_SAMPLE_RATE;
+use crate::settings::RemoteTranscriptionSettings;
+
+#[derive(Deserialize)]
+struct TranscriptionResponse {
+    text: String,
+}
+
+/// Sends audio to a server implementing the OpenAI `/v1/audio/transcriptions`
+/// protocol, e.g. a self-hosted faster-whisper or whisper.cpp server.
+pub struct OpenAiCompatibleBackend {
+    config: RemoteTranscriptionSettings,
+}
+
+impl OpenAiCompatibleBackend {
+    pub fn new(config: RemoteTranscriptionSettings) -> Self {
+        Self { config }
+    }
+
+    fn endpoint(&self, translate: bool) -> String {
+        // Translation to English has its own endpoint in this protocol
+        let path = if translate {
+            "audio/translations"
+        } else {
+            "audio/transcriptions"
+        };
+        format!("{}/{}", self.config.base_url.trim_end_matches('/'), path)
+    }
+
+    fn build_headers(&self) -> Result<HeaderMap> {
+        let mut headers = HeaderMap::new();
+        if !self.config.api_key.is_empty() {
+            headers.insert(
+                AUTHORIZATION,
+                HeaderValue::from_str(&format!("Bearer {}", self.config.api_key))
+                    .map_err(|e| anyhow::anyhow!("Invalid API key: {}", e))?,
+            );
+        }
+        Ok(headers)
+    }
+}
+
+impl TranscriptionBackend for OpenAiCompatibleBackend {
+    fn transcribe(&mut self, audio: Vec<f32>, options: &TranscribeOptions) -> Result<String> {
+        let url = self.endpoint(options.translate);
+        let headers = self.build_headers()?;
+        let timeout = Duration::from_secs(self.config.timeout_seconds);
+
+        let wav = encode_wav(&audio)?;
+        debug!("Sending {} bytes of audio to {}", wav.len(), url);
+
+        let mut form = Form::new()
+            .text("model", self.config.model.clone())
+            .text("response_format", "json");
+        if !options.translate {
+            if let Some(language) = whisper_language(options.language) {
+                form = form.text("language", language);
+            }
+        }
+        let file = Part::bytes(wav)
+            .file_name("audio.wav")
+            .mime_str("audio/wav")?;
+        let form = form.part("file", file);
+
+        // Transcription is called from blocking code that may itself be running on
+        // the async runtime, so make the request from a dedicated thread.
+        let response = thread::spawn(move || {
+            tauri::async_runtime::block_on(async move {
+                let client = reqwest::Client::builder()
+                    .timeout(timeout)
+                    .default_headers(headers)
+                    .build()?;
+                let response = client.post(&url).multipart(form).send().await?;
+
+                let status = response.status();
+                if !status.is_success() {
+                    let error_text = response.text().await.unwrap_or_default();
+                    return Err(anyhow::anyhow!(
+                        "Transcription server returned {}: {}",
+                        status,
+                        error_text
+                    ));
+                }
+
+                Ok(response.json::<TranscriptionResponse>().await?)
+            })
+        })
+        .join()
+        .map_err(|_| anyhow::anyhow!("Transcription request thread panicked"))??;
+
+        Ok(response.text)
+    }
+}
+
+pub struct OpenAiCompatibleBackendFactory;
+
+impl BackendFactory for OpenAiCompatibleBackendFactory {
+    fn create(&self, context: &BackendContext) -> Result<Box<dyn TranscriptionBackend>> {
+        let config = context.settings.remote_transcription.clone();
+        if config.base_url.trim().is_empty() {
+            return Err(anyhow::anyhow!(
+                "No base URL configured for the remote transcription server"
+            ));
+        }
+        Ok(Box::new(OpenAiCompatibleBackend::new(config)))
+    }
+}
+
+/// Encode samples as a 16-bit mono WAV, the same format recordings are saved in
+fn encode_wav(samples: &[f32]) -> Result<Vec<u8>> {
+    let spec = WavSpec {
+        channels: 1,
+        sample_rate: WHISPER_SAMPLE_RATE,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+
+    let mut buffer = Cursor::new(Vec::new());
+    {
+        let mut writer = WavWriter::new(&mut buffer, spec)?;
+        for sample in samples {
+            writer.write_sample((sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16)?;
+        }
+        writer.finalize()?;
+    }
+    Ok(buffer.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::sync::mpsc;
+
+    /// Serves a single HTTP request with the given status and body, and sends the
+    /// raw request back over the channel.
+    fn spawn_mock_server(
+        status: &'static str,
+        body: &'static str,
+    ) -> (String, mpsc::Receiver<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (tx, rx) = mpsc::channel();
+
+        thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0u8; 8192];
+
+            // Read the headers, then as much body as Content-Length announces
+            loop {
+                let n = stream.read(&mut buf).unwrap();
+                request.extend_from_slice(&buf[..n]);
+                let text = String::from_utf8_lossy(&request).to_string();
+                if let Some(header_end) = text.find("\r\n\r\n") {
+                    let content_length = text[..header_end]
+                        .lines()
+                        .find_map(|line| {
+                            let (name, value) = line.split_once(':')?;
+                            name.eq_ignore_ascii_case("content-length")
+                                .then(|| value.trim().parse::<usize>().ok())?
+                        })
+                        .unwrap_or(0);
+                    if request.len() >= header_end + 4 + content_length {
+                        break;
+                    }
+                }
+                if n == 0 {
+                    break;
+                }
+            }
+
+            let response = format!(
+                "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            );
+            stream.write_all(response.as_bytes()).unwrap();
+            tx.send(String::from_utf8_lossy(&request).to_string())
+                .unwrap();
+        });
+
+        (format!("http://{}/v1", addr), rx)
+    }
+
+    fn backend(base_url: String) -> OpenAiCompatibleBackend {
+        OpenAiCompatibleBackend::new(RemoteTranscriptionSettings {
+            base_url,
+            api_key: "test-key".to_string(),
+            model: "whisper-large-v3".to_string(),
+            timeout_seconds: 5,
+        })
+    }
+
+    #[test]
+    fn test_transcribe_sends_multipart_request() {
+        let (base_url, requests) = spawn_mock_server("200 OK", r#"{"text":"hello world"}"#);
+        let options = TranscribeOptions {
+            language: "zh-Hans",
+            translate: false,
+        };
+
+        let text = backend(base_url)
+            .transcribe(vec![0.0; 1600], &options)
+            .unwrap();
+        assert_eq!(text, "hello world");
+
+        let request = requests.recv().unwrap();
+        let lower = request.to_lowercase();
+        assert!(request.starts_with("POST /v1/audio/transcriptions HTTP/1.1"));
+        assert!(lower.contains("authorization: bearer test-key"));
+        assert!(lower.contains("content-type: multipart/form-data; boundary="));
+        assert!(request.contains("name=\"model\"\r\n\r\nwhisper-large-v3\r\n"));
+        assert!(request.contains("name=\"language\"\r\n\r\nzh\r\n"));
+        assert!(request.contains("filename=\"audio.wav\""));
+        assert!(request.contains("RIFF"));
+    }
+
+    #[test]
+    fn test_translate_uses_translation_endpoint() {
+        let (base_url, requests) = spawn_mock_server("200 OK", r#"{"text":"hi"}"#);
+        let options = TranscribeOptions {
+            language: "de",
+            translate: true,
+        };
+
+        backend(base_url)
+            .transcribe(vec![0.0; 1600], &options)
+            .unwrap();
+
+        let request = requests.recv().unwrap();
+        assert!(request.starts_with("POST /v1/audio/translations HTTP/1.1"));
+        assert!(!request.contains("name=\"language\""));
+    }
+
+    #[test]
+    fn test_server_error_is_reported() {
+        let (base_url, _requests) = spawn_mock_server(
+            "500 Internal Server Error",
+            r#"{"error":"model not found"}"#,
+        );
+        let options = TranscribeOptions {
+            language: "auto",
+            translate: false,
+        };
+
+        let err = backend(base_url)
+            .transcribe(vec![0.0; 1600], &options)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("500"));
+        assert!(err.contains("model not found"));
+    }
+}
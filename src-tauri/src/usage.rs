@@ -0,0 +1,188 @@
+use crate::llm_client::LlmAttempt;
+use crate::settings::TokenPrice;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum UsagePeriod {
+    Day,
+    Month,
+}
+
+impl UsagePeriod {
+    /// Label of the local day or month a timestamp falls in
+    fn label(self, timestamp: i64) -> String {
+        let format = match self {
+            UsagePeriod::Day => "%Y-%m-%d",
+            UsagePeriod::Month => "%Y-%m",
+        };
+        DateTime::from_timestamp(timestamp, 0)
+            .map(|time| time.with_timezone(&Local).format(format).to_string())
+            .unwrap_or_default()
+    }
+}
+
+/// Post-processing requests to one provider and model within a day or month
+#[derive(Debug, Clone, PartialEq, Serialize, Type)]
+pub struct UsageAggregate {
+    /// "2024-05-01" for a day, "2024-05" for a month
+    pub period: String,
+    pub provider_id: String,
+    pub model: String,
+    pub requests: u64,
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    /// Time spent waiting on the provider
+    pub duration_ms: u64,
+    /// In US dollars, or `None` when no price is configured for the model
+    pub cost: Option<f64>,
+}
+
+/// The model's own price, falling back to one for the whole provider
+pub fn price_for<'a>(
+    prices: &'a [TokenPrice],
+    provider_id: &str,
+    model: &str,
+) -> Option<&'a TokenPrice> {
+    let for_provider = || {
+        prices
+            .iter()
+            .filter(|price| price.provider_id == provider_id)
+    };
+    for_provider()
+        .find(|price| price.model.as_deref() == Some(model))
+        .or_else(|| for_provider().find(|price| price.model.is_none()))
+}
+
+/// Sums recorded LLM requests, given with the time they were made. The newest
+/// period comes first.
+pub fn aggregate_usage(
+    records: &[(i64, LlmAttempt)],
+    period: UsagePeriod,
+    prices: &[TokenPrice],
+) -> Vec<UsageAggregate> {
+    let mut aggregates: BTreeMap<(String, String, String), UsageAggregate> = BTreeMap::new();
+    for (timestamp, attempt) in records {
+        let label = period.label(*timestamp);
+        let price = price_for(prices, &attempt.provider_id, &attempt.model);
+        let key = (
+            label.clone(),
+            attempt.provider_id.clone(),
+            attempt.model.clone(),
+        );
+        let aggregate = aggregates.entry(key).or_insert_with(|| UsageAggregate {
+            period: label.clone(),
+            provider_id: attempt.provider_id.clone(),
+            model: attempt.model.clone(),
+            requests: 0,
+            prompt_tokens: 0,
+            completion_tokens: 0,
+            duration_ms: 0,
+            cost: price.map(|_| 0.0),
+        });
+
+        aggregate.requests += 1;
+        aggregate.duration_ms += attempt.duration_ms;
+        let Some(usage) = attempt.usage else {
+            continue;
+        };
+        aggregate.prompt_tokens += usage.prompt_tokens;
+        aggregate.completion_tokens += usage.completion_tokens;
+        if let (Some(cost), Some(price)) = (aggregate.cost.as_mut(), price) {
+            *cost += (usage.prompt_tokens as f64 * price.input_per_million
+                + usage.completion_tokens as f64 * price.output_per_million)
+                / 1_000_000.0;
+        }
+    }
+
+    let mut aggregates: Vec<UsageAggregate> = aggregates.into_values().collect();
+    // Stable, so providers and models stay in order within a period
+    aggregates.sort_by(|a, b| b.period.cmp(&a.period));
+    aggregates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm_client::{AttemptOutcome, TokenUsage};
+
+    fn attempt(provider_id: &str, model: &str, tokens: Option<(u64, u64)>) -> LlmAttempt {
+        LlmAttempt {
+            provider_id: provider_id.to_string(),
+            model: model.to_string(),
+            duration_ms: 100,
+            outcome: AttemptOutcome::Succeeded,
+            usage: tokens.map(|(prompt_tokens, completion_tokens)| TokenUsage {
+                prompt_tokens,
+                completion_tokens,
+            }),
+        }
+    }
+
+    fn price(provider_id: &str, model: Option<&str>, input: f64, output: f64) -> TokenPrice {
+        TokenPrice {
+            provider_id: provider_id.to_string(),
+            model: model.map(str::to_string),
+            input_per_million: input,
+            output_per_million: output,
+        }
+    }
+
+    #[test]
+    fn test_price_for_prefers_the_model() {
+        let prices = vec![
+            price("openai", None, 1.0, 2.0),
+            price("openai", Some("gpt-4o"), 5.0, 15.0),
+        ];
+        assert_eq!(price_for(&prices, "openai", "gpt-4o"), Some(&prices[1]));
+        assert_eq!(
+            price_for(&prices, "openai", "gpt-4o-mini"),
+            Some(&prices[0])
+        );
+        assert_eq!(price_for(&prices, "groq", "gpt-4o"), None);
+    }
+
+    #[test]
+    fn test_aggregate_usage_by_period() {
+        // Noon UTC keeps both days the same in any time zone
+        let may_1 = 1_714_564_800;
+        let may_2 = may_1 + 86_400;
+        let records = vec![
+            (may_1, attempt("groq", "llama", None)),
+            (may_1, attempt("openai", "gpt-4o", Some((1_000, 500)))),
+            (may_2, attempt("openai", "gpt-4o", Some((2_000, 1_000)))),
+        ];
+        let prices = vec![price("openai", Some("gpt-4o"), 5.0, 15.0)];
+
+        let daily = aggregate_usage(&records, UsagePeriod::Day, &prices);
+        let summary: Vec<_> = daily
+            .iter()
+            .map(|a| (a.period.as_str(), a.provider_id.as_str(), a.requests))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("2024-05-02", "openai", 1),
+                ("2024-05-01", "groq", 1),
+                ("2024-05-01", "openai", 1)
+            ]
+        );
+        assert_eq!(daily[1].cost, None);
+        assert_eq!(daily[1].prompt_tokens, 0);
+
+        let monthly = aggregate_usage(&records, UsagePeriod::Month, &prices);
+        let openai = monthly.iter().find(|a| a.provider_id == "openai").unwrap();
+        assert_eq!(openai.period, "2024-05");
+        assert_eq!(openai.requests, 2);
+        assert_eq!(
+            (openai.prompt_tokens, openai.completion_tokens),
+            (3_000, 1_500)
+        );
+        assert_eq!(openai.duration_ms, 200);
+        let cost = openai.cost.unwrap();
+        assert!((cost - 0.0375).abs() < 1e-9, "{}", cost);
+    }
+}
@@ -21,6 +21,34 @@ async resetBinding(id: string) : Promise<Result<BindingResponse, string>> {
     else return { status: "error", error: e  as any };
 }
 },
+/**
+ * Adds a shortcut that dictates like "transcribe" but post-processes with its
+ * own prompt, or not at all when `prompt_id` is `None`
+ */
+async addTranscribeBinding(name: string, binding: string, promptId: string | null) : Promise<Result<ShortcutBinding, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("add_transcribe_binding", { name, binding, promptId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async removeTranscribeBinding(id: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("remove_transcribe_binding", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async changeBindingPromptSetting(id: string, promptId: string | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_binding_prompt_setting", { id, promptId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 async changePttSetting(enabled: boolean) : Promise<Result<null, string>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("change_ptt_setting", { enabled }) };
@@ -133,6 +161,14 @@ async changePostProcessEnabledSetting(enabled: boolean) : Promise<Result<null, s
     else return { status: "error", error: e  as any };
 }
 },
+async changePostProcessStreamingSetting(mode: LlmStreamingMode) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_post_process_streaming_setting", { mode }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 async changePostProcessBaseUrlSetting(providerId: string, baseUrl: string) : Promise<Result<null, string>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("change_post_process_base_url_setting", { providerId, baseUrl }) };
@@ -141,6 +177,38 @@ async changePostProcessBaseUrlSetting(providerId: string, baseUrl: string) : Pro
     else return { status: "error", error: e  as any };
 }
 },
+async changePostProcessProtocolSetting(providerId: string, protocol: ProviderProtocol) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_post_process_protocol_setting", { providerId, protocol }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async changePostProcessRequestLimitsSetting(providerId: string, timeoutSecs: number, maxRetries: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_post_process_request_limits_setting", { providerId, timeoutSecs, maxRetries }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async changePostProcessFallbackProvidersSetting(providerIds: string[]) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_post_process_fallback_providers_setting", { providerIds }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async changePostProcessPricesSetting(prices: TokenPrice[]) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_post_process_prices_setting", { prices }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 async changePostProcessApiKeySetting(providerId: string, apiKey: string) : Promise<Result<null, string>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("change_post_process_api_key_setting", { providerId, apiKey }) };
@@ -173,17 +241,25 @@ async fetchPostProcessModels(providerId: string) : Promise<Result<string[], stri
     else return { status: "error", error: e  as any };
 }
 },
-async addPostProcessPrompt(name: string, prompt: string) : Promise<Result<LLMPrompt, string>> {
+async checkPostProcessProviderHealth(providerId: string) : Promise<Result<ProviderHealth, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("check_post_process_provider_health", { providerId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async addPostProcessPrompt(name: string, prompt: string, systemPrompt: string | null) : Promise<Result<LLMPrompt, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("add_post_process_prompt", { name, prompt }) };
+    return { status: "ok", data: await TAURI_INVOKE("add_post_process_prompt", { name, prompt, systemPrompt }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-async updatePostProcessPrompt(id: string, name: string, prompt: string) : Promise<Result<null, string>> {
+async updatePostProcessPrompt(id: string, name: string, prompt: string, systemPrompt: string | null) : Promise<Result<null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("update_post_process_prompt", { id, name, prompt }) };
+    return { status: "ok", data: await TAURI_INVOKE("update_post_process_prompt", { id, name, prompt, systemPrompt }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
@@ -252,6 +328,14 @@ async changeAppendTrailingSpaceSetting(enabled: boolean) : Promise<Result<null,
     else return { status: "error", error: e  as any };
 }
 },
+async changeContextAwareInsertionSetting(enabled: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_context_aware_insertion_setting", { enabled }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 async changeAppLanguageSetting(language: string) : Promise<Result<null, string>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("change_app_language_setting", { language }) };
@@ -260,6 +344,124 @@ async changeAppLanguageSetting(language: string) : Promise<Result<null, string>>
     else return { status: "error", error: e  as any };
 }
 },
+async changeTranslationEnabledSetting(enabled: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_translation_enabled_setting", { enabled }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async changeTranslationTargetLanguageSetting(language: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_translation_target_language_setting", { language }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async changeTranslationPromptSetting(prompt: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_translation_prompt_setting", { prompt }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async changeSpokenCommandsSetting(enabled: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_spoken_commands_setting", { enabled }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async updateReplacementRules(rules: ReplacementRule[]) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("update_replacement_rules", { rules }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Serialize the replacement rules as JSON for the frontend to save to a file
+ */
+async exportReplacementRules() : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("export_replacement_rules") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Import replacement rules from JSON, either appending them to the existing
+ * rules or replacing them. Returns the resulting rule list.
+ */
+async importReplacementRules(json: string, replaceExisting: boolean) : Promise<Result<ReplacementRule[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("import_replacement_rules", { json, replaceExisting }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Set the phonetic algorithm custom word correction uses for a language, or
+ * pass `None` to go back to the language's default
+ */
+async changePhoneticAlgorithmSetting(language: string, algorithm: PhoneticAlgorithm | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_phonetic_algorithm_setting", { language, algorithm }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async changeInverseTextNormalizationSetting(enabled: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_inverse_text_normalization_setting", { enabled }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async changeDisfluencyRemovalSetting(disfluencyRemoval: DisfluencyRemovalSettings) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_disfluency_removal_setting", { disfluencyRemoval }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async changePiiRedactionSetting(piiRedaction: PiiRedactionSettings) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_pii_redaction_setting", { piiRedaction }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Set which text-processing stages run after transcription, in order
+ */
+async changeTextPipelineSetting(stages: TextStage[]) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_text_pipeline_setting", { stages }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async changeTextCasingSetting(casing: TextCasing) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_text_casing_setting", { casing }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 async changeUpdateChecksSetting(enabled: boolean) : Promise<Result<null, string>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("change_update_checks_setting", { enabled }) };
@@ -446,6 +648,27 @@ async getRecommendedFirstModel() : Promise<Result<string, string>> {
     else return { status: "error", error: e  as any };
 }
 },
+async getModelBenchmarks() : Promise<Result<ModelBenchmark[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_model_benchmarks") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Benchmark downloaded models on this machine. Uses the given history entry as
+ * reference audio, otherwise the bundled reference clip, falling back to the most
+ * recent recording. Progress is reported via `model-benchmark-progress` events.
+ */
+async benchmarkModels(historyId: number | null, modelIds: string[] | null) : Promise<Result<ModelBenchmark[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("benchmark_models", { historyId, modelIds }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 async updateMicrophoneMode(alwaysOn: boolean) : Promise<Result<null, string>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("update_microphone_mode", { alwaysOn }) };
@@ -554,6 +777,66 @@ async unloadModelManually() : Promise<Result<null, string>> {
     else return { status: "error", error: e  as any };
 }
 },
+/**
+ * Score the dictation pipeline against a directory of `.wav` files with `.txt`
+ * reference transcripts. Also available as `handy --evaluate <dir>`.
+ */
+async evaluateTranscriptionAccuracy(directory: string, modelId: string | null, promptId: string | null) : Promise<Result<EvaluationReport, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("evaluate_transcription_accuracy", { directory, modelId, promptId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Update the OpenAI-compatible transcription server. A loaded remote model is
+ * unloaded so the next transcription picks up the new connection details.
+ */
+async changeRemoteTranscriptionSettings(remote: RemoteTranscriptionSettings) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_remote_transcription_settings", { remote }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Set the language → model routing table. With "auto" language selection the
+ * spoken language is detected first and the matching model is used.
+ */
+async changeLanguageModelRoutesSetting(routes: Partial<{ [key in string]: string }>) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_language_model_routes_setting", { routes }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Set the languages the user speaks. Automatic detection then only chooses
+ * among these, falling back to the first one when it is unsure.
+ */
+async changeSpokenLanguagesSetting(languages: string[]) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_spoken_languages_setting", { languages }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Run arbitrary text through the configured text-processing pipeline and
+ * return the output of every stage. Enabled LLM stages make real requests.
+ */
+async previewTextPipeline(text: string) : Promise<Result<PipelineRun, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("preview_text_pipeline", { text }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 async getHistoryEntries() : Promise<Result<HistoryEntry[], string>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("get_history_entries") };
@@ -602,6 +885,39 @@ async updateRecordingRetentionPeriod(period: string) : Promise<Result<null, stri
     else return { status: "error", error: e  as any };
 }
 },
+async getHistoryEntryRevisions(id: number) : Promise<Result<HistoryRevision[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_history_entry_revisions", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Re-run transcription (and post-processing) on a stored recording, optionally
+ * with a different model, language or post-processing prompt. The result is
+ * stored as a new revision of the entry.
+ */
+async retranscribeHistoryEntry(id: number, modelId: string | null, language: string | null, promptId: string | null) : Promise<Result<HistoryRevision, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("retranscribe_history_entry", { id, modelId, language, promptId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tokens, latency and estimated cost of post-processing per day or month,
+ * provider and model
+ */
+async getPostProcessUsage(period: UsagePeriod) : Promise<Result<UsageAggregate[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_post_process_usage", { period }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
  * Checks if the Mac is a laptop by detecting battery presence
  * 
@@ -628,24 +944,307 @@ async isLaptop() : Promise<Result<boolean, string>> {
 
 /** user-defined types **/
 
-export type AppSettings = { bindings: Partial<{ [key in string]: ShortcutBinding }>; push_to_talk: boolean; audio_feedback: boolean; audio_feedback_volume?: number; sound_theme?: SoundTheme; start_hidden?: boolean; autostart_enabled?: boolean; update_checks_enabled?: boolean; selected_model?: string; always_on_microphone?: boolean; selected_microphone?: string | null; clamshell_microphone?: string | null; selected_output_device?: string | null; translate_to_english?: boolean; selected_language?: string; overlay_position?: OverlayPosition; debug_mode?: boolean; log_level?: LogLevel; custom_words?: string[]; model_unload_timeout?: ModelUnloadTimeout; word_correction_threshold?: number; history_limit?: number; recording_retention_period?: RecordingRetentionPeriod; paste_method?: PasteMethod; clipboard_handling?: ClipboardHandling; post_process_enabled?: boolean; post_process_provider_id?: string; post_process_providers?: PostProcessProvider[]; post_process_api_keys?: Partial<{ [key in string]: string }>; post_process_models?: Partial<{ [key in string]: string }>; post_process_prompts?: LLMPrompt[]; post_process_selected_prompt_id?: string | null; mute_while_recording?: boolean; append_trailing_space?: boolean; app_language?: string }
+export type AppSettings = { bindings: Partial<{ [key in string]: ShortcutBinding }>; push_to_talk: boolean; audio_feedback: boolean; audio_feedback_volume?: number; sound_theme?: SoundTheme; start_hidden?: boolean; autostart_enabled?: boolean; update_checks_enabled?: boolean; selected_model?: string; always_on_microphone?: boolean; selected_microphone?: string | null; clamshell_microphone?: string | null; selected_output_device?: string | null; translate_to_english?: boolean; selected_language?: string; overlay_position?: OverlayPosition; debug_mode?: boolean; log_level?: LogLevel; custom_words?: string[]; model_unload_timeout?: ModelUnloadTimeout; word_correction_threshold?: number; history_limit?: number; recording_retention_period?: RecordingRetentionPeriod; paste_method?: PasteMethod; clipboard_handling?: ClipboardHandling; post_process_enabled?: boolean; post_process_provider_id?: string; post_process_providers?: PostProcessProvider[]; 
+/**
+ * Providers tried in order when the selected one fails, before falling
+ * back to the unprocessed text
+ */
+post_process_fallback_provider_ids?: string[]; 
+/**
+ * Used to estimate what post-processing costs
+ */
+post_process_prices?: TokenPrice[]; post_process_api_keys?: Partial<{ [key in string]: string }>; post_process_models?: Partial<{ [key in string]: string }>; post_process_prompts?: LLMPrompt[]; post_process_selected_prompt_id?: string | null; post_process_streaming?: LlmStreamingMode; 
+/**
+ * Local providers always receive the unredacted text
+ */
+pii_redaction?: PiiRedactionSettings; mute_while_recording?: boolean; append_trailing_space?: boolean; 
+/**
+ * Match capitalisation, spacing and the final full stop to the text
+ * before the cursor
+ */
+context_aware_insertion?: boolean; app_language?: string; remote_transcription?: RemoteTranscriptionSettings; language_model_routes?: Partial<{ [key in string]: string }>; 
+/**
+ * Languages the user speaks; automatic detection only chooses among these
+ */
+spoken_languages?: string[]; translation_enabled?: boolean; 
+/**
+ * Language to translate dictation into, e.g. "German" or "de"
+ */
+translation_target_language?: string; translation_prompt?: string; 
+/**
+ * Convert dictated "comma", "new line", etc. into punctuation and formatting
+ */
+spoken_commands_enabled?: boolean; 
+/**
+ * Write spoken numbers, dates, times, currency and units in written form
+ */
+inverse_text_normalization_enabled?: boolean; disfluency_removal?: DisfluencyRemovalSettings; 
+/**
+ * User replacement rules, applied in order after transcription
+ */
+replacement_rules?: ReplacementRule[]; 
+/**
+ * Per-language overrides of the phonetic algorithm used for custom words;
+ * languages without an entry use a default suited to the language
+ */
+phonetic_algorithms?: Partial<{ [key in string]: PhoneticAlgorithm }>; 
+/**
+ * Text-processing stages in the order they run after transcription
+ */
+text_pipeline?: TextStage[]; text_casing?: TextCasing }
+/**
+ * What came of asking one provider during post-processing
+ */
+export type AttemptOutcome = { status: "succeeded" } | 
+/**
+ * The provider answered without any text
+ */
+{ status: "empty" } | { status: "failed"; error: string }
 export type AudioDevice = { index: string; name: string; is_default: boolean }
 export type BindingResponse = { success: boolean; binding: ShortcutBinding | null; error: string | null }
 export type ClipboardHandling = "dont_modify" | "copy_to_clipboard"
 export type CustomSounds = { start: boolean; stop: boolean }
-export type EngineType = "Whisper" | "Parakeet"
-export type HistoryEntry = { id: number; file_name: string; timestamp: number; saved: boolean; title: string; transcription_text: string; post_processed_text: string | null; post_process_prompt: string | null }
-export type LLMPrompt = { id: string; name: string; prompt: string }
+/**
+ * Local cleanup of fillers, repetitions and self-corrections
+ */
+export type DisfluencyRemovalSettings = { enabled: boolean; remove_fillers: boolean; remove_repetitions: boolean; remove_self_corrections: boolean; 
+/**
+ * Extra words the user wants removed as fillers
+ */
+extra_fillers?: string[] }
+export type EngineType = "Whisper" | "Parakeet" | "OpenAiCompatible"
+export type ErrorCounts = { reference_words: number; substitutions: number; deletions: number; insertions: number; word_error_rate: number }
+export type EvaluationReport = { timestamp: number; model_id: string | null; language: string; custom_words: string[]; word_correction_threshold: number; post_process_prompt_id: string | null; totals: ErrorCounts; files: FileEvaluation[] }
+export type FileEvaluation = { file: string; reference: string; hypothesis: string; counts: ErrorCounts; diff: WordDiff[]; error: string | null }
+export type HistoryEntry = { id: number; file_name: string; timestamp: number; saved: boolean; title: string; transcription_text: string; post_processed_text: string | null; post_process_prompt: string | null; 
+/**
+ * Language identified automatically before transcription, if detection ran
+ */
+detected_language: string | null; 
+/**
+ * Final text translated into `translation_language`, if translation ran
+ */
+translated_text: string | null; translation_language: string | null; 
+/**
+ * Providers asked during post-processing and translation, in order
+ */
+post_process_attempts: LlmAttempt[] }
+/**
+ * A re-transcription of an existing history entry, e.g. with a different
+ * model or language. The original entry is left untouched.
+ */
+export type HistoryRevision = { id: number; history_id: number; timestamp: number; model_id: string | null; language: string | null; transcription_text: string; post_processed_text: string | null; post_process_prompt: string | null }
+export type LLMPrompt = { id: string; name: string; 
+/**
+ * Sent as the user message
+ */
+prompt: string; 
+/**
+ * Sent as the system message; empty sends none
+ */
+system_prompt?: string }
+/**
+ * One provider tried for an LLM request, as recorded in history
+ */
+export type LlmAttempt = { provider_id: string; model: string; duration_ms: number; outcome: AttemptOutcome; 
+/**
+ * Tokens billed, when the provider reported them
+ */
+usage?: TokenUsage | null }
+/**
+ * How LLM post-processing output is shown while it streams in
+ */
+export type LlmStreamingMode = 
+/**
+ * Wait for the whole reply, then paste it
+ */
+"disabled" | 
+/**
+ * Show the reply in the overlay as it arrives, then paste it
+ */
+"overlay" | 
+/**
+ * Type the reply into the focused app as it arrives. Falls back to the
+ * overlay when a later pipeline stage would still change the text.
+ */
+"type"
 export type LogLevel = "trace" | "debug" | "info" | "warn" | "error"
-export type ModelInfo = { id: string; name: string; description: string; filename: string; url: string | null; size_mb: number; is_downloaded: boolean; is_downloading: boolean; partial_size: number; is_directory: boolean; engine_type: EngineType; accuracy_score: number; speed_score: number }
+/**
+ * Measured performance of a model on this machine
+ */
+export type ModelBenchmark = { model_id: string; load_time_ms: number; audio_duration_ms: number; transcription_time_ms: number; 
+/**
+ * Transcription time divided by audio duration; below 1.0 is faster than real time
+ */
+real_time_factor: number; 
+/**
+ * Peak resident memory growth while loading and running the model, if measurable
+ */
+peak_memory_mb: number | null; timestamp: number }
+export type ModelInfo = { id: string; name: string; description: string; filename: string; url: string | null; size_mb: number; is_downloaded: boolean; is_downloading: boolean; partial_size: number; is_directory: boolean; engine_type: EngineType; accuracy_score: number; speed_score: number; benchmark?: ModelBenchmark | null }
 export type ModelLoadStatus = { is_loaded: boolean; current_model: string | null }
 export type ModelUnloadTimeout = "never" | "immediately" | "min_2" | "min_5" | "min_10" | "min_15" | "hour_1" | "sec_5"
 export type OverlayPosition = "none" | "top" | "bottom"
 export type PasteMethod = "ctrl_v" | "direct" | "none" | "shift_insert" | "ctrl_shift_v"
-export type PostProcessProvider = { id: string; label: string; base_url: string; allow_base_url_edit?: boolean; models_endpoint?: string | null }
+/**
+ * Phonetic comparison used when fuzzy matching custom words
+ */
+export type PhoneticAlgorithm = 
+/**
+ * English Soundex
+ */
+"soundex" | 
+/**
+ * Double Metaphone, which handles many European spellings and names
+ */
+"double_metaphone" | 
+/**
+ * Kölner Phonetik, designed for German
+ */
+"cologne" | 
+/**
+ * No phonetic comparison, only edit distance
+ */
+"edit_distance"
+/**
+ * Redaction of personal data before text is sent to a cloud LLM provider
+ */
+export type PiiRedactionSettings = { enabled: boolean; 
+/**
+ * Names and other terms that are always redacted, on top of the emails,
+ * phone numbers and card numbers found automatically
+ */
+names?: string[] }
+/**
+ * The input of a pipeline run and the text after every stage
+ */
+export type PipelineRun = { input: string; stages: StageOutput[] }
+export type PostProcessProvider = { id: string; label: string; base_url: string; allow_base_url_edit?: boolean; models_endpoint?: string | null; protocol?: ProviderProtocol; 
+/**
+ * Runs on this machine, so needs no API key
+ */
+local?: boolean; 
+/**
+ * How long to wait for the provider to send anything before giving up
+ */
+timeout_secs?: number; 
+/**
+ * Extra attempts after a rate limit or server error
+ */
+max_retries?: number }
+/**
+ * Reachability of a post-processing provider
+ */
+export type ProviderHealth = 
+/**
+ * The server answered and lists this many models
+ */
+{ status: "ready"; models: number } | 
+/**
+ * Nothing is listening at the provider's base URL
+ */
+{ status: "not_running"; message: string } | 
+/**
+ * The server answered, but not successfully
+ */
+{ status: "unhealthy"; message: string }
+/**
+ * Wire format used to talk to a post-processing provider
+ */
+export type ProviderProtocol = 
+/**
+ * OpenAI Chat Completions, also spoken by most hosted and local servers
+ */
+"openai_chat" | 
+/**
+ * Anthropic Messages API
+ */
+"anthropic_messages" | 
+/**
+ * Ollama's native `/api/chat`
+ */
+"ollama_chat"
 export type RecordingRetentionPeriod = "never" | "preserve_limit" | "days_3" | "weeks_2" | "months_3"
-export type ShortcutBinding = { id: string; name: string; description: string; default_binding: string; current_binding: string }
+/**
+ * Connection details for a server speaking the OpenAI `/v1/audio/transcriptions` protocol
+ */
+export type RemoteTranscriptionSettings = { base_url: string; api_key?: string; model: string; timeout_seconds: number }
+/**
+ * A user-defined replacement applied to the transcription, e.g. expanding
+ * "sig block" into a signature or joining "h t t p s" into "https"
+ */
+export type ReplacementRule = { pattern: string; 
+/**
+ * Replacement text; for regex rules `$1`, `${name}` refer to capture groups
+ */
+replacement: string; is_regex?: boolean; case_sensitive?: boolean; 
+/**
+ * Only match at word boundaries, so "cat" doesn't match inside "concatenate"
+ */
+whole_word?: boolean; 
+/**
+ * Upper-case or capitalize the replacement like the matched text
+ */
+preserve_case?: boolean; enabled?: boolean }
+export type ShortcutBinding = { id: string; name: string; description: string; default_binding: string; current_binding: string; 
+/**
+ * Prompt used to post-process dictations started from an extra
+ * transcribe binding; `None` pastes them unprocessed
+ */
+prompt_id?: string | null }
 export type SoundTheme = "marimba" | "pop" | "custom"
+/**
+ * Output of a single stage
+ */
+export type StageOutput = { stage: TextStage; 
+/**
+ * Whether the stage ran; stages that are disabled pass the text through
+ */
+applied: boolean; text: string }
+/**
+ * Case applied to the text by the casing stage
+ */
+export type TextCasing = "unchanged" | "capitalize_sentences" | "lowercase" | "uppercase"
+/**
+ * A step of the text-processing pipeline. The user chooses which stages run
+ * and in what order; each stage is still gated by its own settings.
+ */
+export type TextStage = "custom_words" | "spoken_commands" | "disfluency_removal" | "inverse_normalization" | "replacements" | "chinese_conversion" | "llm_post_process" | "translation" | "casing" | "trailing_space"
+/**
+ * What a provider charges for tokens, in US dollars per million tokens
+ */
+export type TokenPrice = { provider_id: string; 
+/**
+ * Model the price applies to. Without one it covers every model of the
+ * provider that has no price of its own.
+ */
+model?: string | null; input_per_million: number; output_per_million: number }
+/**
+ * Tokens a provider billed for one request
+ */
+export type TokenUsage = { prompt_tokens: number; completion_tokens: number }
+/**
+ * Post-processing requests to one provider and model within a day or month
+ */
+export type UsageAggregate = { 
+/**
+ * "2024-05-01" for a day, "2024-05" for a month
+ */
+period: string; provider_id: string; model: string; requests: number; prompt_tokens: number; completion_tokens: number; 
+/**
+ * Time spent waiting on the provider
+ */
+duration_ms: number; 
+/**
+ * In US dollars, or `None` when no price is configured for the model
+ */
+cost: number | null }
+export type UsagePeriod = "day" | "month"
+/**
+ * One step of the alignment between reference and hypothesis words
+ */
+export type WordDiff = { op: WordOp; reference: string | null; hypothesis: string | null }
+export type WordOp = "equal" | "substitution" | "deletion" | "insertion"
 
 /** tauri-specta globals **/
 
@@ -17,6 +17,7 @@ import { ApiKeyField } from "../PostProcessingSettingsApi/ApiKeyField";
 import { ModelSelect } from "../PostProcessingSettingsApi/ModelSelect";
 import { usePostProcessProviderState } from "../PostProcessingSettingsApi/usePostProcessProviderState";
 import { useSettings } from "../../../hooks/useSettings";
+import { HandyShortcut } from "../HandyShortcut";
 
 const DisabledNotice: React.FC<{ children: React.ReactNode }> = ({
   children,
@@ -452,6 +453,153 @@ const PostProcessingSettingsPromptsComponent: React.FC = () => {
   );
 };
 
+const TranscribeBindingsComponent: React.FC = () => {
+  const { t } = useTranslation();
+  const { getSetting, refreshSettings } = useSettings();
+  const [draftName, setDraftName] = useState("");
+  const [draftPromptId, setDraftPromptId] = useState("");
+
+  const bindings = getSetting("bindings") || {};
+  const prompts = getSetting("post_process_prompts") || [];
+  const extraBindings = Object.values(bindings)
+    .filter((binding) => binding.id.startsWith("transcribe_"))
+    .sort((a, b) => a.id.localeCompare(b.id));
+
+  // An empty value dictates without post-processing
+  const promptOptions = [
+    {
+      value: "",
+      label: t(
+        "settings.postProcessing.transcribeBindings.noPrompt",
+        "No post-processing",
+      ),
+    },
+    ...prompts.map((p) => ({ value: p.id, label: p.name })),
+  ];
+
+  const handleAddBinding = async () => {
+    if (!draftName.trim()) return;
+
+    try {
+      // Added unbound; the shortcut is recorded below like any other
+      const result = await commands.addTranscribeBinding(
+        draftName.trim(),
+        "",
+        draftPromptId || null,
+      );
+      if (result.status === "ok") {
+        await refreshSettings();
+        setDraftName("");
+        setDraftPromptId("");
+      }
+    } catch (error) {
+      console.error("Failed to add transcribe binding:", error);
+    }
+  };
+
+  const handleRemoveBinding = async (id: string) => {
+    try {
+      await commands.removeTranscribeBinding(id);
+      await refreshSettings();
+    } catch (error) {
+      console.error("Failed to remove transcribe binding:", error);
+    }
+  };
+
+  const handlePromptSelect = async (id: string, promptId: string) => {
+    try {
+      await commands.changeBindingPromptSetting(id, promptId || null);
+      await refreshSettings();
+    } catch (error) {
+      console.error("Failed to change binding prompt:", error);
+    }
+  };
+
+  return (
+    <>
+      {extraBindings.map((binding) => (
+        <div key={binding.id}>
+          <HandyShortcut shortcutId={binding.id} grouped={true} />
+          <SettingContainer
+            title={t(
+              "settings.postProcessing.transcribeBindings.prompt",
+              "Prompt",
+            )}
+            description={t(
+              "settings.postProcessing.transcribeBindings.promptDescription",
+              "Prompt used to post-process dictations from this shortcut.",
+            )}
+            descriptionMode="tooltip"
+            layout="horizontal"
+            grouped={true}
+          >
+            <div className="flex items-center gap-2">
+              <Dropdown
+                selectedValue={binding.prompt_id ?? ""}
+                options={promptOptions}
+                onSelect={(value) => handlePromptSelect(binding.id, value)}
+                className="min-w-[200px]"
+              />
+              <Button
+                onClick={() => handleRemoveBinding(binding.id)}
+                variant="secondary"
+                size="md"
+              >
+                {t(
+                  "settings.postProcessing.transcribeBindings.remove",
+                  "Remove",
+                )}
+              </Button>
+            </div>
+          </SettingContainer>
+        </div>
+      ))}
+
+      <SettingContainer
+        title={t(
+          "settings.postProcessing.transcribeBindings.add",
+          "Add shortcut",
+        )}
+        description={t(
+          "settings.postProcessing.transcribeBindings.addDescription",
+          "Adds a dictation shortcut that post-processes with its own prompt.",
+        )}
+        descriptionMode="tooltip"
+        layout="stacked"
+        grouped={true}
+      >
+        <div className="flex gap-2">
+          <Input
+            type="text"
+            value={draftName}
+            onChange={(e) => setDraftName(e.target.value)}
+            placeholder={t(
+              "settings.postProcessing.transcribeBindings.namePlaceholder",
+              "Shortcut name",
+            )}
+            variant="compact"
+            className="flex-1"
+          />
+          <Dropdown
+            selectedValue={draftPromptId}
+            options={promptOptions}
+            onSelect={setDraftPromptId}
+            className="min-w-[200px]"
+          />
+          <Button
+            onClick={handleAddBinding}
+            variant="primary"
+            size="md"
+            disabled={!draftName.trim()}
+          >
+            {t("settings.postProcessing.transcribeBindings.addButton", "Add")}
+          </Button>
+        </div>
+      </SettingContainer>
+    </>
+  );
+};
+
 export const PostProcessingSettingsApi = React.memo(
   PostProcessingSettingsApiComponent,
 );
@@ -462,6 +610,9 @@ export const PostProcessingSettingsPrompts = React.memo(
 );
 PostProcessingSettingsPrompts.displayName = "PostProcessingSettingsPrompts";
 
+export const TranscribeBindings = React.memo(TranscribeBindingsComponent);
+TranscribeBindings.displayName = "TranscribeBindings";
+
 export const PostProcessingSettings: React.FC = () => {
   const { t } = useTranslation();
 
@@ -474,6 +625,21 @@ export const PostProcessingSettings: React.FC = () => {
       <SettingsGroup title={t("settings.postProcessing.prompts.title")}>
         <PostProcessingSettingsPrompts />
       </SettingsGroup>
+
+      <SettingsGroup
+        title={t("settings.postProcessing.voiceEdit.title", "Voice Edit")}
+      >
+        <HandyShortcut shortcutId="voice_edit" grouped={true} />
+      </SettingsGroup>
+
+      <SettingsGroup
+        title={t(
+          "settings.postProcessing.transcribeBindings.title",
+          "Prompt Shortcuts",
+        )}
+      >
+        <TranscribeBindings />
+      </SettingsGroup>
     </div>
   );
 };
@@ -12,11 +12,14 @@ import { syncLanguageFromSettings } from "@/i18n";
 
 type OverlayState = "recording" | "transcribing";
 
+const STREAMED_TEXT_CHARS = 24;
+
 const RecordingOverlay: React.FC = () => {
   const { t } = useTranslation();
   const [isVisible, setIsVisible] = useState(false);
   const [state, setState] = useState<OverlayState>("recording");
   const [levels, setLevels] = useState<number[]>(Array(16).fill(0));
+  const [streamedText, setStreamedText] = useState("");
   const smoothedLevelsRef = useRef<number[]>(Array(16).fill(0));
 
   useEffect(() => {
@@ -27,6 +30,7 @@ const RecordingOverlay: React.FC = () => {
         await syncLanguageFromSettings();
         const overlayState = event.payload as OverlayState;
         setState(overlayState);
+        setStreamedText("");
         setIsVisible(true);
       });
 
@@ -35,6 +39,11 @@ const RecordingOverlay: React.FC = () => {
         setIsVisible(false);
       });
 
+      // Listen for LLM output streamed in while post-processing
+      const unlistenText = await listen<string>("overlay-text", (event) => {
+        setStreamedText(event.payload);
+      });
+
       // Listen for mic-level updates
       const unlistenLevel = await listen<number[]>("mic-level", (event) => {
         const newLevels = event.payload as number[];
@@ -53,6 +62,7 @@ const RecordingOverlay: React.FC = () => {
       return () => {
         unlistenShow();
         unlistenHide();
+        unlistenText();
         unlistenLevel();
       };
     };
@@ -88,9 +98,17 @@ const RecordingOverlay: React.FC = () => {
             ))}
           </div>
         )}
-        {state === "transcribing" && (
-          <div className="transcribing-text">{t("overlay.transcribing")}</div>
-        )}
+        {state === "transcribing" &&
+          (streamedText ? (
+            // The latest words fit best in the small overlay
+            <div className="streamed-text">
+              {streamedText.replace(/\s+/g, " ").slice(-STREAMED_TEXT_CHARS)}
+            </div>
+          ) : (
+            <div className="transcribing-text">
+              {t("overlay.transcribing")}
+            </div>
+          ))}
       </div>
 
       <div className="overlay-right">